@@ -0,0 +1,46 @@
+//! Time four threads wait to take a shared fork, held for 200µs and then released for 200µs each
+//! time, with a polling [Mutex] and with a [NotifyingFork]. Run with
+//! `cargo bench --bench fork_latency`.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use actividad_12::{Fork, NotifyingFork, TimeoutLock};
+
+const THREADS: usize = 4;
+const ACQUISITIONS: usize = 200;
+const HOLD: Duration = Duration::from_micros(200);
+const TIMEOUT: Duration = Duration::from_secs(1);
+const INTERVAL: Duration = Duration::from_millis(1);
+
+/// Mean time waited for each acquisition of `fork`.
+fn mean_wait(fork: Arc<dyn Fork<u64>>) -> Duration {
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let fork = fork.clone();
+            thread::spawn(move || {
+                let mut waited = Duration::ZERO;
+                for _ in 0..ACQUISITIONS {
+                    let start = Instant::now();
+                    let mut guard = fork.try_lock_for(TIMEOUT, INTERVAL).expect("the timeout is long enough");
+                    waited += start.elapsed();
+                    **guard += 1;
+                    thread::sleep(HOLD);
+                    drop(guard);
+                    thread::sleep(HOLD);
+                }
+                waited
+            })
+        })
+        .collect();
+    let waited: Duration = handles.into_iter().map(|handle| handle.join().unwrap()).sum();
+    waited / (THREADS * ACQUISITIONS) as u32
+}
+
+fn main() {
+    let polling = mean_wait(Arc::new(Mutex::new(0)));
+    let notifying = mean_wait(Arc::new(NotifyingFork::new(0)));
+    println!("polling Mutex:  {polling:.2?} per acquisition");
+    println!("NotifyingFork:  {notifying:.2?} per acquisition");
+}
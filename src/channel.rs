@@ -0,0 +1,152 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvError, Sender, SyncSender, TryRecvError, TrySendError};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{Event, Observer};
+
+/// What a [ChannelObserver] with a bounded channel does with an event when the channel is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventChannelPolicy {
+    /// Wait until the consumer makes room, slowing the philosopher down.
+    #[default]
+    Block,
+    /// Drop the oldest event in the channel to make room for the new one.
+    DropOldest,
+    /// Drop the new event.
+    DropNewest,
+}
+
+/// Send every event to a channel so another thread can consume them.
+/// ```rust
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+/// use actividad_12::{run_simulation, ChannelObserver, EventChannelPolicy, SimulationConfig};
+///
+/// for policy in [EventChannelPolicy::DropOldest, EventChannelPolicy::DropNewest] {
+///     let (observer, events) = ChannelObserver::bounded(1, policy);
+///     let observer = Arc::new(observer);
+///     // A consumer much slower than the philosophers
+///     let consumer = thread::spawn(move || {
+///         let mut received = 0;
+///         while events.recv().is_ok() {
+///             received += 1;
+///             thread::sleep(Duration::from_millis(20));
+///         }
+///         received
+///     });
+///     let config = SimulationConfig::builder()
+///         .philosophers(3)
+///         .meals(2)
+///         .think_time(Duration::from_millis(1))
+///         .eat_time(Duration::from_millis(1))
+///         .observer(observer.clone())
+///         .build()
+///         .unwrap();
+///     let sent = run_simulation(&config).unwrap().total_meals() as u64 * 4;
+///     let dropped = observer.dropped();
+///     assert!(dropped > 0);
+///
+///     // The channel is closed once every copy of the observer is gone
+///     drop((config, observer));
+///     let received: u64 = consumer.join().unwrap();
+///     assert!(received + dropped >= sent);
+/// }
+/// ```
+pub struct ChannelObserver {
+    sender: EventSender,
+    policy: EventChannelPolicy,
+    /// The receiving end, only kept to drop the oldest event with [EventChannelPolicy::DropOldest]
+    /// so otherwise the channel is disconnected when the consumer leaves.
+    receiver: Option<Arc<Mutex<Receiver<Event>>>>,
+    dropped: AtomicU64,
+}
+
+enum EventSender {
+    Unbounded(Sender<Event>),
+    Bounded(SyncSender<Event>),
+}
+
+impl ChannelObserver {
+    /// Send the events to a channel that grows as much as needed.
+    pub fn unbounded() -> (Self, EventReceiver) {
+        let (sender, receiver) = mpsc::channel();
+        Self::with_sender(EventSender::Unbounded(sender), receiver, EventChannelPolicy::Block)
+    }
+
+    /// Send the events to a channel that holds at most `capacity` of them, when it is full
+    /// `policy` decides what happens ([See][EventChannelPolicy]).
+    pub fn bounded(capacity: usize, policy: EventChannelPolicy) -> (Self, EventReceiver) {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        Self::with_sender(EventSender::Bounded(sender), receiver, policy)
+    }
+
+    fn with_sender(sender: EventSender, receiver: Receiver<Event>, policy: EventChannelPolicy) -> (Self, EventReceiver) {
+        let receiver = Arc::new(Mutex::new(receiver));
+        let observer = Self {
+            sender,
+            policy,
+            receiver: (policy == EventChannelPolicy::DropOldest).then(|| receiver.clone()),
+            dropped: AtomicU64::new(0),
+        };
+        (observer, EventReceiver { receiver })
+    }
+
+    /// Number of events dropped because the channel was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::SeqCst)
+    }
+
+    fn send_bounded(&self, sender: &SyncSender<Event>, event: Event) {
+        let event = match sender.try_send(event) {
+            Err(TrySendError::Full(event)) => event,
+            // Sent, or nobody is listening anymore
+            _ => return,
+        };
+        match (self.policy, &self.receiver) {
+            (EventChannelPolicy::Block, _) => {
+                let _ = sender.send(event);
+            }
+            (EventChannelPolicy::DropOldest, Some(receiver)) => {
+                if receiver.lock().unwrap_or_else(PoisonError::into_inner).try_recv().is_ok() {
+                    self.dropped.fetch_add(1, Ordering::SeqCst);
+                }
+                // Another philosopher may have taken the room made, then the new event is dropped
+                if let Err(TrySendError::Full(_)) = sender.try_send(event) {
+                    self.dropped.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            _ => {
+                self.dropped.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+impl Observer for ChannelObserver {
+    fn on_event(&self, event: &Event) {
+        match &self.sender {
+            EventSender::Unbounded(sender) => {
+                let _ = sender.send(event.clone());
+            }
+            EventSender::Bounded(sender) => self.send_bounded(sender, event.clone()),
+        }
+    }
+}
+
+/// Receiving end of a [ChannelObserver], it is disconnected once the observer is dropped.
+pub struct EventReceiver {
+    receiver: Arc<Mutex<Receiver<Event>>>,
+}
+
+impl EventReceiver {
+    /// Wait for the next event.
+    pub fn recv(&self) -> Result<Event, RecvError> {
+        self.receiver.lock().unwrap_or_else(PoisonError::into_inner).recv()
+    }
+
+    /// The next event if there is one already.
+    pub fn try_recv(&self) -> Result<Event, TryRecvError> {
+        self.receiver.lock().unwrap_or_else(PoisonError::into_inner).try_recv()
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use crate::{run_simulation, AcquisitionStyle, SimulationConfig, SimulationError, SimulationResult};
+
+/// Run `config` once with each [AcquisitionStyle], with the same seed and every other parameter
+/// equal, and compare them. A run that exhausts the time budget is compared with its partial
+/// result, that is what a deadlock of [AcquisitionStyle::BlockingFirst] looks like.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{compare_acquisition_styles, AcquisitionStyle, SimulationConfig};
+///
+/// let config = SimulationConfig::builder()
+///     .philosophers(3)
+///     .meals(3)
+///     .think_time(Duration::from_millis(5))
+///     .eat_time(Duration::from_millis(10))
+///     .fork_timeout(Duration::from_millis(15))
+///     .fork_interval(Duration::from_millis(1))
+///     .time_budget(Duration::from_secs(10))
+///     .build()
+///     .unwrap();
+/// let report = compare_acquisition_styles(&config).unwrap();
+/// assert_eq!(report.timeout_first.style, AcquisitionStyle::TimeoutFirst);
+/// assert_eq!(report.blocking_first.style, AcquisitionStyle::BlockingFirst);
+/// assert!(report.timeout_first.result.is_complete() && report.blocking_first.result.is_complete());
+/// assert!(report.timeout_first.fairness() > 0.9);
+/// // Without a timeout the first fork is waited for through the meals of the neighbors
+/// # #[cfg(not(feature = "no-timeouts"))]
+/// assert!(report.timeout_first.longest_fork_wait() < report.blocking_first.longest_fork_wait());
+/// ```
+pub fn compare_acquisition_styles(config: &SimulationConfig) -> Result<ComparisonReport, SimulationError> {
+    let run = |style| {
+        let result = match run_simulation(&config.clone().with_acquisition_style(style)) {
+            Ok(result) => result,
+            Err(SimulationError::BudgetExceeded(partial)) => *partial,
+            Err(error) => return Err(error),
+        };
+        Ok(StyleReport { style, result })
+    };
+    Ok(ComparisonReport {
+        timeout_first: run(AcquisitionStyle::TimeoutFirst)?,
+        blocking_first: run(AcquisitionStyle::BlockingFirst)?,
+    })
+}
+
+/// Outcome of [compare_acquisition_styles].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonReport {
+    pub timeout_first: StyleReport,
+    pub blocking_first: StyleReport,
+}
+
+impl ComparisonReport {
+    /// Meals eaten with [AcquisitionStyle::TimeoutFirst] minus the ones eaten with
+    /// [AcquisitionStyle::BlockingFirst].
+    pub fn meals_difference(&self) -> i64 {
+        i64::from(self.timeout_first.result.total_meals()) - i64::from(self.blocking_first.result.total_meals())
+    }
+}
+
+/// Run of a [ComparisonReport] with one [AcquisitionStyle].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleReport {
+    pub style: AcquisitionStyle,
+    pub result: SimulationResult,
+}
+
+impl StyleReport {
+    /// Jain's fairness index of the meals ([See][SimulationResult::fairness]).
+    pub fn fairness(&self) -> f64 {
+        self.result.fairness()
+    }
+
+    /// Mean time a philosopher held one fork while waiting for the other one.
+    pub fn mean_single_fork_hold(&self) -> Duration {
+        let holds = &self.result.single_fork_holds;
+        if holds.is_empty() {
+            return Duration::ZERO;
+        }
+        holds.iter().sum::<Duration>() / holds.len() as u32
+    }
+
+    /// Longest wait for a fork, a wait that keeps growing with the first fork waited for
+    /// without a timeout is the risk of a deadlock.
+    pub fn longest_fork_wait(&self) -> Duration {
+        self.result.fork_waits.last().copied().unwrap_or_default()
+    }
+}
@@ -0,0 +1,2132 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "verify-exclusion")]
+use crate::ExclusionMonitor;
+use crate::{AcquisitionOrder, AcquisitionStyle, ForkBackend, HandednessPattern, Observer, SeededSource, SimulationResult, Strategy, TimeDistribution, WaitForGraph};
+
+const DEFAULT_PHILOSOPHERS: usize = 5;
+const DEFAULT_MEALS: u32 = 6;
+const DEFAULT_FORK_TIMEOUT: Duration = Duration::from_millis(100);
+const DEFAULT_FORK_INTERVAL: Duration = Duration::from_millis(10);
+const DEFAULT_THINK_TIME: Duration = Duration::from_millis(750);
+const DEFAULT_EAT_TIME: Duration = Duration::from_millis(750);
+const DEFAULT_PHILOSOPHERS_PER_CPU: usize = 4;
+const DEFAULT_CONTENTION_WINDOW: Duration = Duration::from_secs(1);
+
+/// Fork timeout of a philosopher with the given number of meals left
+/// ([See][SimulationConfigBuilder::fork_timeout_fn]).
+pub(crate) type ForkTimeoutFn = Arc<dyn Fn(u32) -> Duration + Send + Sync>;
+
+/// Called with the name of a philosopher and the id of a fork it found taken
+/// ([See][SimulationConfigBuilder::on_contention]).
+pub(crate) type ContentionFn = Arc<dyn Fn(&str, usize) + Send + Sync>;
+
+/// Called with the name of a philosopher and the bites it took of its meal, tells whether it
+/// releases its forks before the next bite ([See][SimulationConfigBuilder::between_bites]).
+pub(crate) type BiteFn = Arc<dyn Fn(&str, u32) -> bool + Send + Sync>;
+
+/// Parameters of a simulation. Use [SimulationConfig::builder] to create a validated one or
+/// [SimulationConfig::default] to get the classic table of five philosophers eating six times.
+#[derive(Clone)]
+pub struct SimulationConfig {
+    philosophers: usize,
+    stop_condition: StopCondition,
+    warmup_meals: u32,
+    /// Number of rounds, when the philosophers dine in rounds instead of until the stop condition.
+    rounds: Option<u32>,
+    round_pause: Duration,
+    /// Meals already eaten by each philosopher when the simulation begins.
+    initial_meals: Vec<u32>,
+    left_handed_index: Option<usize>,
+    /// Id of the fork at the left of each philosopher, by position, when they are not in order.
+    fork_permutation: Option<Vec<usize>>,
+    handedness: HandednessPattern,
+    fork_timeout: Duration,
+    /// Fork timeout by meals left, instead of `fork_timeout`.
+    fork_timeout_fn: Option<ForkTimeoutFn>,
+    on_contention: Option<ContentionFn>,
+    fork_interval: Duration,
+    fork_backoff: Option<Duration>,
+    think_time: TimeDistribution,
+    eat_time: TimeDistribution,
+    /// Think and eat times of the philosophers that do not use the ones of the table.
+    philosopher_times: Vec<(usize, TimeDistribution, TimeDistribution)>,
+    /// Time between the arrivals of consecutive philosophers at the table.
+    start_stagger: TimeDistribution,
+    seed: u64,
+    cooldown: Duration,
+    hunger_deadline: Option<Duration>,
+    /// Longest time from beginning to think to the end of the meal.
+    round_budget: Option<Duration>,
+    /// Seconds of eating after which a philosopher is full, even with meals left.
+    satiation_target: Option<f64>,
+    /// Probability of tasting instead of trying to eat, and how long a taste takes.
+    taste_probability: f64,
+    taste_time: Duration,
+    /// Bites each meal is split into and what is checked between them.
+    bites: u32,
+    between_bites: Option<BiteFn>,
+    /// Priorities of the philosophers that do not have the lowest one.
+    priorities: Vec<(usize, u32)>,
+    /// Explicit order in which some philosophers take their forks, by position.
+    fork_orders: Vec<(usize, Vec<usize>)>,
+    priority_inheritance: bool,
+    /// Nice values of the threads of the philosophers that do not keep the one of the process.
+    #[cfg(feature = "thread-priority")]
+    niceness: Vec<(usize, i32)>,
+    strategy: Strategy,
+    acquisition_order: AcquisitionOrder,
+    acquisition_style: AcquisitionStyle,
+    fork_backend: ForkBackend,
+    time_budget: Option<Duration>,
+    oversubscription: Oversubscription,
+    philosophers_per_cpu: usize,
+    join_error_policy: JoinErrorPolicy,
+    contention_window: Duration,
+    exclude_paused_time: bool,
+    collect_samples: bool,
+    collect_timeline: bool,
+    max_samples: Option<usize>,
+    measure_hand_offs: bool,
+    profile: bool,
+    observer: Arc<dyn Observer>,
+    wait_for_graph: Option<Arc<WaitForGraph>>,
+    #[cfg(feature = "verify-exclusion")]
+    exclusion_monitor: Option<Arc<ExclusionMonitor>>,
+    /// Philosopher whose thread can not be spawned, to test how the table recovers from it.
+    #[cfg(feature = "test-util")]
+    failed_spawn: Option<usize>,
+}
+
+impl SimulationConfig {
+    pub fn builder() -> SimulationConfigBuilder {
+        SimulationConfigBuilder::default()
+    }
+
+    /// Number of philosophers sitting at the table, there is one fork between each pair of them.
+    pub fn philosophers(&self) -> usize {
+        self.philosophers
+    }
+
+    /// Number of meals each philosopher has to eat.
+    pub fn meals(&self) -> u32 {
+        self.stop_condition.meals()
+    }
+
+    /// Meals the philosopher at `index` already ate when the simulation begins, they count
+    /// towards its meals.
+    pub fn initial_meals(&self, index: usize) -> u32 {
+        self.initial_meals.get(index).copied().unwrap_or(0)
+    }
+
+    /// When the philosophers leave the table.
+    pub fn stop_condition(&self) -> StopCondition {
+        self.stop_condition
+    }
+
+    /// Meals each philosopher eats before the measurement begins, they are not counted in the stats
+    /// nor towards [SimulationConfig::meals].
+    pub fn warmup_meals(&self) -> u32 {
+        self.warmup_meals
+    }
+
+    /// Number of rounds the philosophers dine for instead of following the stop condition, `None`
+    /// unless [SimulationConfigBuilder::rounds] was used.
+    pub fn rounds(&self) -> Option<u32> {
+        self.rounds
+    }
+
+    /// How long the table waits between rounds.
+    pub fn round_pause(&self) -> Duration {
+        self.round_pause
+    }
+
+    /// Position of the only left handed philosopher with [HandednessPattern::SingleLeftHanded],
+    /// the last one by default.
+    pub fn left_handed_index(&self) -> usize {
+        self.left_handed_index.unwrap_or(self.philosophers.saturating_sub(1))
+    }
+
+    /// Id of the fork at the left of each philosopher, by position, `None` when philosopher `i`
+    /// has fork `i` at its left ([See][SimulationConfigBuilder::fork_permutation]).
+    pub fn fork_permutation(&self) -> Option<&[usize]> {
+        self.fork_permutation.as_deref()
+    }
+
+    /// Id of the fork at the left of the philosopher at `index`, the one at its right is the one
+    /// at the left of the next philosopher.
+    pub fn fork_at(&self, index: usize) -> usize {
+        self.fork_permutation.as_ref().map_or(index, |permutation| permutation[index])
+    }
+
+    /// Ids of the forks of the philosopher at `index` in the order it takes them, `None` when the
+    /// [AcquisitionOrder] decides ([See][SimulationConfigBuilder::fork_order]).
+    pub fn fork_order_of(&self, index: usize) -> Option<&[usize]> {
+        self.fork_orders
+            .iter()
+            .rev()
+            .find(|(i, _)| *i == index)
+            .map(|(_, order)| order.as_slice())
+    }
+
+    /// Whether the philosopher at `index` takes its left fork first, following its fork order or
+    /// else the [AcquisitionOrder].
+    pub(crate) fn takes_left_first(&self, index: usize) -> bool {
+        let (left_id, right_id) = (self.fork_at(index), self.fork_at((index + 1) % self.philosophers));
+        match (self.fork_order_of(index), self.acquisition_order) {
+            (Some(order), _) => order[0] == left_id,
+            (None, AcquisitionOrder::Handedness) => self.is_left_handed(index),
+            (None, AcquisitionOrder::LowerIdFirst) => left_id < right_id,
+            (None, AcquisitionOrder::HigherIdFirst) => left_id > right_id,
+        }
+    }
+
+    /// Which philosophers are left handed.
+    pub fn handedness(&self) -> HandednessPattern {
+        self.handedness
+    }
+
+    /// Whether the philosopher at `index` is left handed ([See][HandednessPattern]).
+    pub fn is_left_handed(&self, index: usize) -> bool {
+        match self.handedness {
+            HandednessPattern::SingleLeftHanded => index == self.left_handed_index(),
+            pattern => pattern.is_left_handed(index, self.philosophers),
+        }
+    }
+
+    /// How long a philosopher waits for each fork, unless it depends on the meals left
+    /// ([See][SimulationConfig::fork_timeout_for]).
+    pub fn fork_timeout(&self) -> Duration {
+        self.fork_timeout
+    }
+
+    /// How long a philosopher with `remaining_meals` meals left waits for each fork.
+    pub fn fork_timeout_for(&self, remaining_meals: u32) -> Duration {
+        self.fork_timeout_fn.as_ref().map_or(self.fork_timeout, |timeout| timeout(remaining_meals))
+    }
+
+    /// The function giving the fork timeout by meals left, if there is one.
+    pub(crate) fn fork_timeout_fn(&self) -> Option<&ForkTimeoutFn> {
+        self.fork_timeout_fn.as_ref()
+    }
+
+    /// The function called when a fork is found taken, if there is one.
+    pub(crate) fn on_contention(&self) -> Option<&ContentionFn> {
+        self.on_contention.as_ref()
+    }
+
+    /// How often a philosopher retries to take a fork while waiting for it.
+    pub fn fork_interval(&self) -> Duration {
+        self.fork_interval
+    }
+
+    /// Longest retry interval when it doubles after each failed attempt to eat, `None` when the
+    /// fork interval is always used.
+    pub fn fork_backoff(&self) -> Option<Duration> {
+        self.fork_backoff
+    }
+
+    pub fn think_time(&self) -> TimeDistribution {
+        self.think_time
+    }
+
+    pub fn eat_time(&self) -> TimeDistribution {
+        self.eat_time
+    }
+
+    /// Think and eat times of the philosopher at `index`.
+    pub fn times_of(&self, index: usize) -> (TimeDistribution, TimeDistribution) {
+        self.philosopher_times
+            .iter()
+            .rev()
+            .find(|(i, _, _)| *i == index)
+            .map_or((self.think_time, self.eat_time), |&(_, think, eat)| (think, eat))
+    }
+
+    /// Time between the arrivals of consecutive philosophers at the table.
+    pub fn start_stagger(&self) -> TimeDistribution {
+        self.start_stagger
+    }
+
+    /// How long after the simulation begins each philosopher arrives at the table, the first one
+    /// arrives at once and each of the others a [SimulationConfig::start_stagger] after the
+    /// previous one.
+    pub fn start_delays(&self) -> Vec<Duration> {
+        // Another stream than the one of the first philosopher
+        let mut random = SeededSource::new(!self.seed);
+        let mut delay = Duration::ZERO;
+        (0..self.philosophers)
+            .map(|i| {
+                if i > 0 {
+                    delay = delay.saturating_add(self.start_stagger.sample(&mut random));
+                }
+                delay
+            })
+            .collect()
+    }
+
+    /// Seed of the random times of the philosophers.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Minimum time between the end of a meal and the next attempt to take the forks.
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// How long a philosopher can go hungry before [Observer::on_starving] is called.
+    pub fn hunger_deadline(&self) -> Option<Duration> {
+        self.hunger_deadline
+    }
+
+    /// Longest a round of thinking, taking the forks and eating can last ([See][SimulationConfigBuilder::round_budget]).
+    pub fn round_budget(&self) -> Option<Duration> {
+        self.round_budget
+    }
+
+    /// Seconds a philosopher has to eat to be full ([See][SimulationConfigBuilder::satiation_target]).
+    pub fn satiation_target(&self) -> Option<f64> {
+        self.satiation_target
+    }
+
+    /// Probability that a philosopher tastes with one fork instead of trying to eat
+    /// ([See][SimulationConfigBuilder::taste]).
+    pub fn taste_probability(&self) -> f64 {
+        self.taste_probability
+    }
+
+    /// How long a taste takes.
+    pub fn taste_time(&self) -> Duration {
+        self.taste_time
+    }
+
+    /// Bites each meal is split into ([See][SimulationConfigBuilder::bites]).
+    pub fn bites(&self) -> u32 {
+        self.bites
+    }
+
+    /// The function called between the bites of a meal, if there is one.
+    pub(crate) fn between_bites(&self) -> Option<&BiteFn> {
+        self.between_bites.as_ref()
+    }
+
+    /// Priority of the philosopher at `index`, `0` unless it was set.
+    pub fn priority_of(&self, index: usize) -> u32 {
+        self.priorities
+            .iter()
+            .rev()
+            .find(|(i, _)| *i == index)
+            .map_or(0, |&(_, priority)| priority)
+    }
+
+    /// Nice value of the thread of the philosopher at `index`, `None` when it keeps the one of the
+    /// process.
+    #[cfg(feature = "thread-priority")]
+    pub fn niceness_of(&self, index: usize) -> Option<i32> {
+        self.niceness.iter().rev().find(|(i, _)| *i == index).map(|&(_, nice)| nice)
+    }
+
+    /// Philosopher whose thread can not be spawned ([See][SimulationConfigBuilder::fail_spawn]).
+    #[cfg(feature = "test-util")]
+    pub(crate) fn failed_spawn(&self) -> Option<usize> {
+        self.failed_spawn
+    }
+
+    /// Whether a philosopher eats faster when a philosopher with a higher priority waits for one
+    /// of its forks.
+    pub fn priority_inheritance(&self) -> bool {
+        self.priority_inheritance
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// Which fork each philosopher tries first.
+    pub fn acquisition_order(&self) -> AcquisitionOrder {
+        self.acquisition_order
+    }
+
+    /// How the first fork is waited for.
+    pub fn acquisition_style(&self) -> AcquisitionStyle {
+        self.acquisition_style
+    }
+
+    /// Kind of forks placed on the table.
+    pub fn fork_backend(&self) -> ForkBackend {
+        self.fork_backend
+    }
+
+    /// Longest time the simulation can run, including the warmup.
+    pub fn time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+
+    /// What [run_simulation][crate::run_simulation] does when there are more philosophers per
+    /// CPU than [SimulationConfig::philosophers_per_cpu].
+    pub fn oversubscription(&self) -> Oversubscription {
+        self.oversubscription
+    }
+
+    /// Most philosophers per CPU before the table is oversubscribed.
+    pub fn philosophers_per_cpu(&self) -> usize {
+        self.philosophers_per_cpu
+    }
+
+    /// What the simulation does when the thread of a philosopher panics.
+    pub fn join_error_policy(&self) -> JoinErrorPolicy {
+        self.join_error_policy
+    }
+
+    /// How far back [Simulation::current_contention][crate::Simulation::current_contention]
+    /// looks, one second by default.
+    pub fn contention_window(&self) -> Duration {
+        self.contention_window
+    }
+
+    /// Whether there are more philosophers than `parallelism` CPUs can run without the context
+    /// switches dominating the timings.
+    pub fn is_oversubscribed(&self, parallelism: usize) -> bool {
+        self.philosophers > parallelism.saturating_mul(self.philosophers_per_cpu)
+    }
+
+    /// Number of threads of the pool ([See][crate::run_on_pool]) the philosophers are moved to
+    /// with `parallelism` CPUs, `None` when each one keeps its own thread.
+    pub fn pool_size_for(&self, parallelism: usize) -> Option<usize> {
+        (self.oversubscription == Oversubscription::UsePool && self.is_oversubscribed(parallelism)).then_some(parallelism.max(1))
+    }
+
+    /// Whether the time the simulation spent paused is left out of the elapsed time.
+    pub fn exclude_paused_time(&self) -> bool {
+        self.exclude_paused_time
+    }
+
+    /// Whether every fork wait and meal is kept in [SimulationResult::samples].
+    pub fn collect_samples(&self) -> bool {
+        self.collect_samples
+    }
+
+    /// Whether the phases of each philosopher are kept in [SimulationResult::timelines].
+    pub fn collect_timeline(&self) -> bool {
+        self.collect_timeline
+    }
+
+    /// Most samples kept in [SimulationResult::samples], `None` when there is no limit.
+    pub fn max_samples(&self) -> Option<usize> {
+        self.max_samples
+    }
+
+    /// Most samples kept by the philosopher at `index`, its share of
+    /// [SimulationConfig::max_samples].
+    pub(crate) fn sample_limit_of(&self, index: usize) -> usize {
+        self.max_samples.map_or(usize::MAX, |max| max / self.philosophers + usize::from(index < max % self.philosophers))
+    }
+
+    /// Whether the hand-off latency of each fork is measured in [SimulationResult::hand_offs].
+    pub fn measure_hand_offs(&self) -> bool {
+        self.measure_hand_offs
+    }
+
+    /// Whether the sleeps and calls to `try_lock` are reported in [SimulationResult::polls].
+    pub fn profile(&self) -> bool {
+        self.profile
+    }
+
+    /// Receiver of the events of the simulation, by default they are ignored.
+    pub fn observer(&self) -> &Arc<dyn Observer> {
+        &self.observer
+    }
+
+    /// Where the philosophers publish the forks they hold and wait for, if anywhere.
+    pub fn wait_for_graph(&self) -> Option<&Arc<WaitForGraph>> {
+        self.wait_for_graph.as_ref()
+    }
+
+    /// Monitor told when each philosopher begins and ends eating, if any.
+    #[cfg(feature = "verify-exclusion")]
+    pub fn exclusion_monitor(&self) -> Option<&Arc<ExclusionMonitor>> {
+        self.exclusion_monitor.as_ref()
+    }
+
+    /// The same config with another acquisition style.
+    pub(crate) fn with_acquisition_style(mut self, acquisition_style: AcquisitionStyle) -> Self {
+        self.acquisition_style = acquisition_style;
+        self
+    }
+
+    /// The same config with another seed and observer.
+    pub(crate) fn with_seed_and_observer(mut self, seed: u64, observer: Arc<dyn Observer>) -> Self {
+        self.seed = seed;
+        self.observer = observer;
+        self
+    }
+
+    /// Lower bound of the time the philosophers need to eat all their meals, using the mean think
+    /// and eat times. With `n` philosophers, `m` meals each and the mean think time `T`, eat time
+    /// `E` and cooldown `C` of each philosopher it is the larger of:
+    ///
+    /// - The time the slowest philosopher needs on its own, `T + m * E + (m - 1) * max(T, C)`,
+    ///   since it thinks before every meal and the cooldown overlaps with the thinking.
+    /// - The eating the whole table does, `m * sum(E)`, divided by the most philosophers that can
+    ///   eat at the same time. Neighbors share a fork so that is `floor(n / 2)`, the size of a
+    ///   maximum independent set of the ring.
+    ///
+    /// The waits for the forks are not counted.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::SimulationConfig;
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(4)
+    ///     .meals(2)
+    ///     .think_time(Duration::from_millis(10))
+    ///     .eat_time(Duration::from_millis(30))
+    ///     .build()
+    ///     .unwrap();
+    /// // Alone: 10 + 2 * 30 + 10 = 80ms, the table: 2 * 4 * 30 / 2 = 120ms
+    /// assert_eq!(config.theoretical_min_time(), Duration::from_millis(120));
+    /// ```
+    pub fn theoretical_min_time(&self) -> Duration {
+        let meals = self.meals();
+        if meals == 0 || self.philosophers == 0 {
+            return Duration::ZERO;
+        }
+        let times: Vec<_> = (0..self.philosophers)
+            .map(|i| {
+                let (think_time, eat_time) = self.times_of(i);
+                (think_time.mean(), eat_time.mean())
+            })
+            .collect();
+        let alone = times
+            .iter()
+            .map(|&(think, eat)| think + eat * meals + think.max(self.cooldown) * (meals - 1))
+            .max()
+            .unwrap_or_default();
+        let simultaneous_eaters = (self.philosophers / 2).max(1) as u32;
+        let table = times.iter().map(|&(_, eat)| eat * meals).sum::<Duration>() / simultaneous_eaters;
+        alone.max(table)
+    }
+}
+
+impl fmt::Debug for SimulationConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("SimulationConfig");
+        debug
+            .field("philosophers", &self.philosophers)
+            .field("stop_condition", &self.stop_condition)
+            .field("warmup_meals", &self.warmup_meals)
+            .field("rounds", &self.rounds)
+            .field("round_pause", &self.round_pause)
+            .field("initial_meals", &self.initial_meals)
+            .field("left_handed_index", &self.left_handed_index())
+            .field("fork_permutation", &self.fork_permutation)
+            .field("handedness", &self.handedness)
+            .field("fork_timeout", &self.fork_timeout)
+            .field("fork_timeout_fn", &self.fork_timeout_fn.is_some())
+            .field("on_contention", &self.on_contention.is_some())
+            .field("fork_interval", &self.fork_interval)
+            .field("fork_backoff", &self.fork_backoff)
+            .field("think_time", &self.think_time)
+            .field("eat_time", &self.eat_time)
+            .field("philosopher_times", &self.philosopher_times)
+            .field("start_stagger", &self.start_stagger)
+            .field("seed", &self.seed)
+            .field("cooldown", &self.cooldown)
+            .field("hunger_deadline", &self.hunger_deadline)
+            .field("round_budget", &self.round_budget)
+            .field("satiation_target", &self.satiation_target)
+            .field("taste_probability", &self.taste_probability)
+            .field("taste_time", &self.taste_time)
+            .field("bites", &self.bites)
+            .field("between_bites", &self.between_bites.is_some())
+            .field("priorities", &self.priorities)
+            .field("priority_inheritance", &self.priority_inheritance)
+            .field("strategy", &self.strategy)
+            .field("acquisition_order", &self.acquisition_order)
+            .field("fork_orders", &self.fork_orders)
+            .field("acquisition_style", &self.acquisition_style)
+            .field("fork_backend", &self.fork_backend)
+            .field("time_budget", &self.time_budget)
+            .field("oversubscription", &self.oversubscription)
+            .field("philosophers_per_cpu", &self.philosophers_per_cpu)
+            .field("join_error_policy", &self.join_error_policy)
+            .field("contention_window", &self.contention_window)
+            .field("exclude_paused_time", &self.exclude_paused_time)
+            .field("collect_samples", &self.collect_samples)
+            .field("collect_timeline", &self.collect_timeline)
+            .field("max_samples", &self.max_samples)
+            .field("measure_hand_offs", &self.measure_hand_offs)
+            .field("profile", &self.profile)
+            .field("wait_for_graph", &self.wait_for_graph.is_some());
+        #[cfg(feature = "verify-exclusion")]
+        debug.field("exclusion_monitor", &self.exclusion_monitor.is_some());
+        #[cfg(feature = "thread-priority")]
+        debug.field("niceness", &self.niceness);
+        #[cfg(feature = "test-util")]
+        debug.field("failed_spawn", &self.failed_spawn);
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            philosophers: DEFAULT_PHILOSOPHERS,
+            stop_condition: StopCondition::EachReaches(DEFAULT_MEALS),
+            warmup_meals: 0,
+            rounds: None,
+            round_pause: Duration::ZERO,
+            initial_meals: Vec::new(),
+            left_handed_index: None,
+            fork_permutation: None,
+            handedness: HandednessPattern::default(),
+            fork_timeout: DEFAULT_FORK_TIMEOUT,
+            fork_timeout_fn: None,
+            on_contention: None,
+            fork_interval: DEFAULT_FORK_INTERVAL,
+            fork_backoff: None,
+            think_time: TimeDistribution::Constant(DEFAULT_THINK_TIME),
+            eat_time: TimeDistribution::Constant(DEFAULT_EAT_TIME),
+            philosopher_times: Vec::new(),
+            start_stagger: TimeDistribution::Constant(Duration::ZERO),
+            seed: 0,
+            cooldown: Duration::ZERO,
+            hunger_deadline: None,
+            round_budget: None,
+            satiation_target: None,
+            taste_probability: 0.0,
+            taste_time: Duration::ZERO,
+            bites: 1,
+            between_bites: None,
+            priorities: Vec::new(),
+            fork_orders: Vec::new(),
+            priority_inheritance: false,
+            #[cfg(feature = "thread-priority")]
+            niceness: Vec::new(),
+            strategy: Strategy::default(),
+            acquisition_order: AcquisitionOrder::default(),
+            acquisition_style: AcquisitionStyle::default(),
+            fork_backend: ForkBackend::default(),
+            time_budget: None,
+            oversubscription: Oversubscription::default(),
+            philosophers_per_cpu: DEFAULT_PHILOSOPHERS_PER_CPU,
+            join_error_policy: JoinErrorPolicy::default(),
+            contention_window: DEFAULT_CONTENTION_WINDOW,
+            exclude_paused_time: false,
+            collect_samples: false,
+            collect_timeline: false,
+            max_samples: None,
+            measure_hand_offs: false,
+            profile: false,
+            observer: Arc::new(()),
+            wait_for_graph: None,
+            #[cfg(feature = "verify-exclusion")]
+            exclusion_monitor: None,
+            #[cfg(feature = "test-util")]
+            failed_spawn: None,
+        }
+    }
+}
+
+/// When the philosophers leave the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopCondition {
+    /// Each philosopher leaves as soon as it eats the given number of meals, so the ones that eat
+    /// faster stop competing for the forks earlier.
+    EachReaches(u32),
+    /// Every philosopher keeps dining until all of them ate the given number of meals, so the
+    /// contention stays the same until the end.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Event, EventKind, Observer, SimulationConfig, StopCondition};
+    ///
+    /// // Filósofo 1 takes long to think before each meal, the meals of everyone are recorded
+    /// #[derive(Default)]
+    /// struct SlowThinker(Mutex<Vec<(String, u32)>>);
+    ///
+    /// impl Observer for SlowThinker {
+    ///     fn on_event(&self, event: &Event) {
+    ///         match event.kind {
+    ///             EventKind::Thinking if event.philosopher == "Filósofo 1" => thread::sleep(Duration::from_millis(20)),
+    ///             EventKind::Ate { meals } => self.0.lock().unwrap().push((event.philosopher.clone(), meals)),
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let observer = Arc::new(SlowThinker::default());
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .stop_condition(StopCondition::AllReach(3))
+    ///     .think_time(Duration::from_millis(2))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .observer(observer.clone())
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.philosophers.iter().all(|stats| stats.meals >= 3));
+    /// // The fast ones go on eating past the target while the slow one catches up
+    /// assert!(result.philosophers[1..].iter().all(|stats| stats.meals > 3));
+    /// let meals = observer.0.lock().unwrap();
+    /// let slow_second = meals.iter().position(|(name, meals)| name == "Filósofo 1" && *meals == 2).unwrap();
+    /// for name in ["Filósofo 2", "Filósofo 3"] {
+    ///     assert!(meals.iter().rposition(|(other, _)| other == name).unwrap() > slow_second);
+    /// }
+    /// ```
+    AllReach(u32),
+}
+
+impl StopCondition {
+    /// Number of meals each philosopher has to eat.
+    pub fn meals(&self) -> u32 {
+        match *self {
+            Self::EachReaches(meals) | Self::AllReach(meals) => meals,
+        }
+    }
+}
+
+/// What [run_simulation][crate::run_simulation] does when the table has many more philosophers
+/// than CPUs ([See][SimulationConfigBuilder::oversubscription]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Oversubscription {
+    /// Give each philosopher its own thread anyway.
+    #[default]
+    Ignore,
+    /// Give each philosopher its own thread but print a warning to stderr.
+    Warn,
+    /// Share one thread per CPU between the philosophers with [run_on_pool][crate::run_on_pool],
+    /// which leaves out some parameters of the config such as the warmup and the time budget.
+    UsePool,
+}
+
+/// What a [Simulation][crate::Simulation] does when the thread of a philosopher panics
+/// ([See][SimulationConfigBuilder::join_error_policy]). A philosopher that panics while eating
+/// leaves its forks poisoned, and its neighbors fail every attempt to take them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinErrorPolicy {
+    /// Count the philosopher as panicked, with no meals, and let the rest of the table go on.
+    #[default]
+    Ignore,
+    /// Stop the rest of the table before its next attempt to eat and return
+    /// [SimulationError::PhilosopherPanicked][crate::SimulationError::PhilosopherPanicked].
+    FailFast,
+    /// Clear the poison of the forks of the philosopher and sit it again in a new thread, at
+    /// most the given times, continuing from the meals and failures of its last attempt to eat.
+    /// It comes back without a mailbox, meal counts nor fork pool, like a newcomer of
+    /// [Simulation::add_philosopher][crate::Simulation::add_philosopher], and the rest of its
+    /// state is lost with its thread: its samples, timeline and fork stats start over. Since the
+    /// others would wait for it forever at the end of the warmup or of a round, it is rejected
+    /// with a warmup or rounds ([See][ConfigError::RetrySpawnWithBarriers]). Once it
+    /// panicked more times it is ignored.
+    RetrySpawn(u32),
+}
+
+/// Builder of a [SimulationConfig], every parameter not set keeps its default value.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{ConfigError, SimulationConfig};
+///
+/// let config = SimulationConfig::builder()
+///     .philosophers(3)
+///     .meals(2)
+///     .build()
+///     .unwrap();
+/// assert_eq!(config.philosophers(), 3);
+///
+/// let error = SimulationConfig::builder()
+///     .fork_interval(Duration::from_millis(200))
+///     .build()
+///     .unwrap_err();
+/// assert!(matches!(error, ConfigError::IntervalNotBelowTimeout { .. }));
+/// ```
+/// Each way to get the parameters wrong is rejected with its own error:
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{ConfigError, JoinErrorPolicy, SimulationConfig, SimulationConfigBuilder, TimeDistribution};
+///
+/// let rejected = |builder: SimulationConfigBuilder| builder.build().unwrap_err();
+/// let builder = SimulationConfig::builder;
+/// let (ms, backwards) = (Duration::from_millis, TimeDistribution::Uniform { min: Duration::from_millis(2), max: Duration::from_millis(1) });
+/// assert_eq!(rejected(builder().philosophers(1)), ConfigError::TooFewPhilosophers(1));
+/// assert_eq!(rejected(builder().left_handed_index(5)), ConfigError::LeftHandedOutOfRange(5));
+/// assert_eq!(rejected(builder().priority(7, 1)), ConfigError::PhilosopherOutOfRange(7));
+/// assert_eq!(rejected(builder().eat_distribution(backwards)), ConfigError::InvalidDistribution(backwards));
+/// assert_eq!(rejected(builder().fork_interval(Duration::ZERO)), ConfigError::ZeroInterval);
+/// assert_eq!(rejected(builder().fork_timeout(ms(10)).fork_backoff(ms(10))), ConfigError::IntervalNotBelowTimeout { interval: ms(10), timeout: ms(10) });
+/// assert_eq!(rejected(builder().satiation_target(0.0)), ConfigError::InvalidSatiationTarget);
+/// assert_eq!(rejected(builder().taste(1.5, ms(1))), ConfigError::InvalidTasteProbability);
+/// assert_eq!(rejected(builder().bites(0)), ConfigError::ZeroBites);
+/// assert_eq!(rejected(builder().round_budget(Duration::ZERO)), ConfigError::ZeroRoundBudget);
+/// assert_eq!(rejected(builder().contention_window(Duration::ZERO)), ConfigError::ZeroContentionWindow);
+/// assert_eq!(rejected(builder().fork_permutation(vec![0, 1, 2, 3, 3])), ConfigError::InvalidForkPermutation(vec![0, 1, 2, 3, 3]));
+/// assert_eq!(rejected(builder().fork_order(0, vec![0, 2])), ConfigError::InvalidForkOrder { philosopher: 0, order: vec![0, 2] });
+/// assert_eq!(rejected(builder().warmup_meals(1).join_error_policy(JoinErrorPolicy::RetrySpawn(1))), ConfigError::RetrySpawnWithBarriers);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SimulationConfigBuilder {
+    config: SimulationConfig,
+    fork_timeout_set: bool,
+    scale_fork_timeout: bool,
+    left_handed_seed: Option<u64>,
+    fork_permutation_seed: Option<u64>,
+}
+
+impl SimulationConfigBuilder {
+    /// Number of philosophers at the table. An empty table is valid and nobody eats, but a single
+    /// philosopher would share its only fork with itself and is rejected with
+    /// [ConfigError::TooFewPhilosophers].
+    /// ```rust
+    /// use actividad_12::{run_simulation, ConfigError, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder().philosophers(0).build().unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.philosophers.is_empty() && result.fork_acquisitions.is_empty());
+    /// assert_eq!(result.total_meals(), 0);
+    /// assert!(result.is_complete());
+    ///
+    /// let error = SimulationConfig::builder().philosophers(1).build().unwrap_err();
+    /// assert_eq!(error, ConfigError::TooFewPhilosophers(1));
+    /// ```
+    pub fn philosophers(mut self, philosophers: usize) -> Self {
+        self.config.philosophers = philosophers;
+        self
+    }
+
+    /// Each philosopher leaves the table after eating `meals` times, same as
+    /// `stop_condition(StopCondition::EachReaches(meals))`.
+    pub fn meals(mut self, meals: u32) -> Self {
+        self.config.stop_condition = StopCondition::EachReaches(meals);
+        self
+    }
+
+    pub fn stop_condition(mut self, stop_condition: StopCondition) -> Self {
+        self.config.stop_condition = stop_condition;
+        self
+    }
+
+    /// Let each philosopher eat `warmup_meals` meals before the measurement begins
+    /// ([See][SimulationConfig::warmup_meals]). The time is measured from the moment the whole
+    /// table finished the warmup.
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(1)
+    ///     .warmup_meals(5)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(20))
+    ///     .fork_timeout(Duration::from_millis(100))
+    ///     .build()
+    ///     .unwrap();
+    /// let started = Instant::now();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert_eq!(result.total_meals(), 3);
+    /// // Nobody eats at once at a table of three, the warmup takes at least 15 meals of 20ms
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(started.elapsed() >= Duration::from_millis(300));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(result.elapsed + Duration::from_millis(250) <= started.elapsed(), "{:?} of {:?}", result.elapsed, started.elapsed());
+    /// assert!(result.first_meals.iter().all(|first| first.is_some_and(|first| first <= result.elapsed)));
+    /// ```
+    pub fn warmup_meals(mut self, warmup_meals: u32) -> Self {
+        self.config.warmup_meals = warmup_meals;
+        self
+    }
+
+    /// Dine in `rounds` rounds instead of until the stop condition is met. In each round every
+    /// philosopher tries to eat once, then waits for the rest of the table and the whole table
+    /// waits `pause` before the next round. The time budget is checked between rounds.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Event, EventKind, Observer, SimulationConfig};
+    ///
+    /// /// Philosophers in the order they began each attempt to eat
+    /// #[derive(Default)]
+    /// struct Attempts(Mutex<Vec<String>>);
+    ///
+    /// impl Observer for Attempts {
+    ///     fn on_event(&self, event: &Event) {
+    ///         if event.kind == EventKind::Thinking {
+    ///             self.0.lock().unwrap().push(event.philosopher.clone());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let attempts = Arc::new(Attempts::default());
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .rounds(3, Duration::from_millis(10))
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .observer(attempts.clone())
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(result.elapsed >= Duration::from_millis(20));
+    ///
+    /// let attempts = attempts.0.lock().unwrap();
+    /// assert_eq!(attempts.len(), 3 * 3);
+    /// // Nobody begins a round before the whole table finished the previous one
+    /// for round in attempts.chunks(3) {
+    ///     let mut philosophers = round.to_vec();
+    ///     philosophers.sort();
+    ///     philosophers.dedup();
+    ///     assert_eq!(philosophers.len(), 3);
+    /// }
+    /// ```
+    pub fn rounds(mut self, rounds: u32, pause: Duration) -> Self {
+        self.config.rounds = Some(rounds);
+        self.config.round_pause = pause;
+        self
+    }
+
+    /// Make the philosopher at `index` the only left handed one, instead of the last one.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// // Wherever the left handed philosopher sits the table never deadlocks
+    /// for index in 0..5 {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(5)
+    ///         .meals(5)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(1))
+    ///         .left_handed_index(index)
+    ///         .time_budget(Duration::from_secs(10))
+    ///         .build()
+    ///         .unwrap();
+    ///     assert_eq!(config.left_handed_index(), index);
+    ///     assert!(run_simulation(&config).unwrap().is_complete());
+    /// }
+    /// ```
+    pub fn left_handed_index(mut self, index: usize) -> Self {
+        self.config.left_handed_index = Some(index);
+        self.left_handed_seed = None;
+        self
+    }
+
+    /// Choose the only left handed philosopher at random, the same `seed` always chooses the
+    /// same philosopher for the same number of philosophers.
+    /// ```rust
+    /// use actividad_12::SimulationConfig;
+    ///
+    /// let left_handed = |seed| SimulationConfig::builder().philosophers(7).random_left_handed(seed).build().unwrap().left_handed_index();
+    /// assert_eq!(left_handed(42), left_handed(42));
+    /// assert!(left_handed(42) < 7);
+    /// ```
+    pub fn random_left_handed(mut self, seed: u64) -> Self {
+        self.left_handed_seed = Some(seed);
+        self
+    }
+
+    /// Give the philosopher at position `i` the fork with the id `permutation[i]` at its left, and
+    /// the one of the next philosopher at its right, instead of fork `i` and `i + 1`. Each fork
+    /// is still shared by two neighbors so the table is still a ring, only the ids change, to
+    /// check that a strategy does not rely on the forks being numbered around the table.
+    /// `permutation` must have each id from `0` to the number of philosophers once.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, AcquisitionOrder, AcquisitionStyle, SimulationConfig};
+    ///
+    /// // Taking the fork with the lower id first never deadlocks, whatever the ids
+    /// for seed in 0..5 {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(5)
+    ///         .meals(5)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(1))
+    ///         .acquisition_order(AcquisitionOrder::LowerIdFirst)
+    ///         .acquisition_style(AcquisitionStyle::BlockingFirst)
+    ///         .shuffle_forks(seed)
+    ///         .time_budget(Duration::from_secs(10))
+    ///         .build()
+    ///         .unwrap();
+    ///     let mut ids = config.fork_permutation().unwrap().to_vec();
+    ///     ids.sort_unstable();
+    ///     assert_eq!(ids, [0, 1, 2, 3, 4]);
+    ///     assert!(run_simulation(&config).unwrap().is_complete());
+    /// }
+    ///
+    /// let config = SimulationConfig::builder().philosophers(3).fork_permutation(vec![2, 0, 1]).build().unwrap();
+    /// assert_eq!((config.fork_at(0), config.fork_at(1)), (2, 0));
+    /// assert!(SimulationConfig::builder().philosophers(3).fork_permutation(vec![0, 0, 1]).build().is_err());
+    /// ```
+    pub fn fork_permutation(mut self, permutation: Vec<usize>) -> Self {
+        self.config.fork_permutation = Some(permutation);
+        self.fork_permutation_seed = None;
+        self
+    }
+
+    /// Give the forks ids in a random order ([See][SimulationConfigBuilder::fork_permutation]), the
+    /// same `seed` always gives the same ids for the same number of philosophers.
+    pub fn shuffle_forks(mut self, seed: u64) -> Self {
+        self.fork_permutation_seed = Some(seed);
+        self
+    }
+
+    /// Choose which philosophers are left handed, by default only one is.
+    pub fn handedness(mut self, pattern: HandednessPattern) -> Self {
+        self.config.handedness = pattern;
+        self
+    }
+
+    pub fn fork_timeout(mut self, timeout: Duration) -> Self {
+        self.config.fork_timeout = timeout;
+        self.fork_timeout_set = true;
+        self
+    }
+
+    /// Compute the fork timeout of each attempt to eat from the meals the philosopher has left,
+    /// for example to wait longer near the end so the table finishes sooner. It replaces the
+    /// fixed fork timeout, which is still used to validate the fork interval.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig, SimulationConfigBuilder};
+    ///
+    /// fn elapsed(builder: SimulationConfigBuilder) -> Duration {
+    ///     let config = builder
+    ///         .philosophers(5)
+    ///         .meals(6)
+    ///         .think_time(Duration::from_millis(20))
+    ///         .eat_time(Duration::from_millis(10))
+    ///         .fork_timeout(Duration::from_millis(2))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .build()
+    ///         .unwrap();
+    ///     let result = run_simulation(&config).unwrap();
+    ///     assert!(result.is_complete());
+    ///     result.elapsed
+    /// }
+    ///
+    /// // 2ms with 6 meals left up to 52ms with 1 left, a failed attempt costs a 20ms think
+    /// let finishing_kick = SimulationConfig::builder().fork_timeout_fn(|remaining| Duration::from_millis(2 + 10 * u64::from(6 - remaining.min(6))));
+    /// let (fixed, kick) = (elapsed(SimulationConfig::builder()), elapsed(finishing_kick));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(kick < fixed, "{kick:?} with the finishing kick, {fixed:?} without");
+    /// ```
+    pub fn fork_timeout_fn(mut self, timeout: impl Fn(u32) -> Duration + Send + Sync + 'static) -> Self {
+        self.config.fork_timeout_fn = Some(Arc::new(timeout));
+        self
+    }
+
+    /// Call `on_contention` with the name of the philosopher and the id of the fork each time a
+    /// philosopher tries to take a fork and finds it taken, from the thread of the philosopher
+    /// and while it waits for the fork, to log or react to the contention. It is on the path
+    /// that polls the forks so it should return quickly.
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let contentions = Arc::new(AtomicU64::new(0));
+    /// let counter = contentions.clone();
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(5)
+    ///     .meals(5)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .profile(true)
+    ///     .on_contention(move |philosopher, fork| {
+    ///         assert!(philosopher.starts_with("Filósofo") && fork < 5);
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// // Every try_lock either took its fork or found it taken
+    /// let taken: u64 = result.fork_acquisitions.iter().sum();
+    /// assert_eq!(contentions.load(Ordering::Relaxed), result.total_polls().try_locks - taken);
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(contentions.load(Ordering::Relaxed) > 0);
+    /// ```
+    pub fn on_contention(mut self, on_contention: impl Fn(&str, usize) + Send + Sync + 'static) -> Self {
+        self.config.on_contention = Some(Arc::new(on_contention));
+        self
+    }
+
+    /// When the fork timeout is not set grow the default one with the size of the table, since
+    /// more philosophers means more contention. The timeout becomes
+    /// `100ms * sqrt(philosophers / 5)`, so it is never shorter than the default one.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::SimulationConfig;
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(20)
+    ///     .scale_fork_timeout(true)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(config.fork_timeout(), Duration::from_millis(200));
+    /// ```
+    /// At a table of 50 the longer timeout outlasts a neighbor eating for 150ms, while a fixed one
+    /// of 100ms gives up:
+    /// ```rust
+    /// use std::sync::{mpsc, Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{Philosopher, SimulationConfig};
+    ///
+    /// let table = || SimulationConfig::builder().philosophers(50).think_time(Duration::ZERO).eat_time(Duration::ZERO);
+    /// let scaled = table().scale_fork_timeout(true).build().unwrap();
+    /// let fixed = table().fork_timeout(Duration::from_millis(100)).build().unwrap();
+    /// assert_eq!(scaled.fork_timeout(), Duration::from_secs_f64(0.1 * 10f64.sqrt()));
+    ///
+    /// let meals = |config: &SimulationConfig| {
+    ///     let (left, right) = (Arc::new(Mutex::new(())), Arc::new(Mutex::new(())));
+    ///     let (taken, wait) = mpsc::channel();
+    ///     let neighbor = {
+    ///         let right = right.clone();
+    ///         thread::spawn(move || {
+    ///             let _eating = right.lock().unwrap();
+    ///             taken.send(()).unwrap();
+    ///             thread::sleep(Duration::from_millis(150));
+    ///         })
+    ///     };
+    ///     wait.recv().unwrap();
+    ///     // Right handed, it waits for the right fork first
+    ///     let mut philosopher = Philosopher::new("Filósofo 1", left, right, false).unwrap().with_config(config);
+    ///     philosopher.dine();
+    ///     neighbor.join().unwrap();
+    ///     philosopher.meals()
+    /// };
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// # {
+    /// assert_eq!(meals(&scaled), 1);
+    /// assert_eq!(meals(&fixed), 0);
+    /// # }
+    /// ```
+    pub fn scale_fork_timeout(mut self, scale: bool) -> Self {
+        self.scale_fork_timeout = scale;
+        self
+    }
+
+    pub fn fork_interval(mut self, interval: Duration) -> Self {
+        self.config.fork_interval = interval;
+        self
+    }
+
+    /// Double the retry interval after each failed attempt to eat, up to `max_interval`, and go
+    /// back to the fork interval after each meal. A philosopher that keeps failing polls its
+    /// forks less often ([See][SimulationConfigBuilder::profile]).
+    pub fn fork_backoff(mut self, max_interval: Duration) -> Self {
+        self.config.fork_backoff = Some(max_interval);
+        self
+    }
+
+    /// Time spent thinking before each attempt to eat. [Duration] can not be negative so any
+    /// value is valid.
+    pub fn think_time(mut self, think_time: Duration) -> Self {
+        self.config.think_time = TimeDistribution::Constant(think_time);
+        self
+    }
+
+    /// Draw the time spent thinking before each attempt to eat from `distribution`.
+    pub fn think_distribution(mut self, distribution: TimeDistribution) -> Self {
+        self.config.think_time = distribution;
+        self
+    }
+
+    /// Time spent eating while holding both forks. [Duration] can not be negative so any value
+    /// is valid.
+    pub fn eat_time(mut self, eat_time: Duration) -> Self {
+        self.config.eat_time = TimeDistribution::Constant(eat_time);
+        self
+    }
+
+    /// Draw the time spent eating from `distribution`.
+    pub fn eat_distribution(mut self, distribution: TimeDistribution) -> Self {
+        self.config.eat_time = distribution;
+        self
+    }
+
+    /// Let the philosophers arrive one after the other instead of all at once, with a time drawn
+    /// from `distribution` between consecutive arrivals ([See][SimulationConfig::start_delays]),
+    /// so they do not all fight for the forks at the beginning.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig, TimeDistribution};
+    ///
+    /// // Number of times a philosopher had to wait for a fork
+    /// let contentions = |stagger: Duration| {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(5)
+    ///         .meals(1)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(10))
+    ///         .fork_timeout(Duration::from_millis(100))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .start_stagger(TimeDistribution::Constant(stagger))
+    ///         .build()
+    ///         .unwrap();
+    ///     assert_eq!(config.start_delays()[4], stagger * 4);
+    ///     let result = run_simulation(&config).unwrap();
+    ///     assert!(result.is_complete());
+    ///     result.fork_waits.iter().filter(|&&wait| wait >= Duration::from_millis(1)).count()
+    /// };
+    /// // Each philosopher arrives once the previous one finished eating
+    /// let (synchronized, staggered) = (contentions(Duration::ZERO), contentions(Duration::from_millis(20)));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(staggered < synchronized, "{staggered} contentions staggered, {synchronized} synchronized");
+    /// ```
+    pub fn start_stagger(mut self, distribution: TimeDistribution) -> Self {
+        self.config.start_stagger = distribution;
+        self
+    }
+
+    /// Use other think and eat times for the philosopher at `index`.
+    pub fn philosopher_times(mut self, index: usize, think_time: TimeDistribution, eat_time: TimeDistribution) -> Self {
+        self.config.philosopher_times.push((index, think_time, eat_time));
+        self
+    }
+
+    /// Seed of the random times, the same seed draws the same times.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    /// Make each philosopher keep thinking for at least `cooldown` after each meal before trying
+    /// to take the forks again. Like the other sleeps it is skipped with the `no-timeouts` feature.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.config.cooldown = cooldown;
+        self
+    }
+
+    /// Call [Observer::on_starving] when a philosopher has been trying to eat for longer than
+    /// `deadline` without getting both forks, telling a genuine starvation apart from a momentary
+    /// contention for the forks.
+    pub fn hunger_deadline(mut self, deadline: Duration) -> Self {
+        self.config.hunger_deadline = Some(deadline);
+        self
+    }
+
+    /// Give each round of a philosopher, from beginning to think to the end of the meal, at most
+    /// `budget`, like a real-time deadline after which a meal is useless. The forks are waited for
+    /// at most what is left of the budget, and a round whose meal would end after it is abandoned
+    /// releasing the forks without eating, counted in [FailureCounts::over_budget][crate::FailureCounts::over_budget].
+    /// With the `no-timeouts` feature nothing sleeps and the forks are waited for as long as it
+    /// takes, so only the rounds that waited too long are abandoned.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Philosopher, SimulationConfig, TimeDistribution};
+    ///
+    /// // A meal alone takes longer than the budget so no round ends with a meal
+    /// let config = SimulationConfig::builder()
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(20))
+    ///     .round_budget(Duration::from_millis(10))
+    ///     .build()
+    ///     .unwrap();
+    /// let mut philosopher = Philosopher::new("Filósofo 1", Arc::new(Mutex::new(())), Arc::new(Mutex::new(())), false)
+    ///     .unwrap()
+    ///     .with_config(&config);
+    /// for _ in 0..3 {
+    ///     philosopher.dine();
+    /// }
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// # {
+    /// assert_eq!(philosopher.meals(), 0);
+    /// assert_eq!(philosopher.failures().over_budget, 3);
+    /// # }
+    ///
+    /// // Only the meals short enough for the budget are eaten
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(5)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_distribution(TimeDistribution::Uniform { min: Duration::from_millis(1), max: Duration::from_millis(10) })
+    ///     .fork_timeout(Duration::from_millis(20))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .round_budget(Duration::from_millis(6))
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.is_complete());
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(result.failures.iter().map(|failures| failures.over_budget).sum::<u64>() > 0);
+    /// ```
+    pub fn round_budget(mut self, budget: Duration) -> Self {
+        self.config.round_budget = Some(budget);
+        self
+    }
+
+    /// Let the meals have different sizes: each meal fills a philosopher by the seconds it
+    /// actually ate, and once it ate for `target` seconds in total it is full and leaves the table
+    /// even if it has meals left ([See][crate::PhilosopherStats::satiated]).
+    /// With the `no-timeouts` feature nobody eats for any time so only the meals count.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig, TimeDistribution};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(2)
+    ///     .meals(20)
+    ///     .philosopher_times(0, TimeDistribution::Constant(Duration::ZERO), TimeDistribution::Constant(Duration::from_millis(5)))
+    ///     .philosopher_times(1, TimeDistribution::Constant(Duration::ZERO), TimeDistribution::Constant(Duration::from_millis(20)))
+    ///     .fork_timeout(Duration::from_secs(1))
+    ///     .satiation_target(0.05)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.is_complete());
+    /// let [quick, slow] = [&result.philosophers[0], &result.philosophers[1]];
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// # {
+    /// // Both ate for about 50ms, the slow eater in fewer and bigger meals
+    /// assert!(quick.satiated && slow.satiated);
+    /// assert!(slow.meals < quick.meals, "{} meals of the slow eater, {} of the quick one", slow.meals, quick.meals);
+    /// assert!(slow.meals <= 3 && quick.meals <= 10);
+    /// # }
+    /// ```
+    pub fn satiation_target(mut self, target: f64) -> Self {
+        self.config.satiation_target = Some(target);
+        self
+    }
+
+    /// After thinking, taste the food with `probability` instead of trying to eat: take only the
+    /// first fork in the acquisition order, waiting at most the fork timeout, and hold it for
+    /// `taste_time`. A taste is not a meal, it is counted apart
+    /// ([See][crate::SimulationResult::tastes]) and does not use the payload of the fork.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Philosopher, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .taste(1.0, Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// // Tasting only needs the right fork, taken first, while the left one is held elsewhere
+    /// let (left, right) = (Arc::new(Mutex::new(())), Arc::new(Mutex::new(())));
+    /// let mut philosopher = Philosopher::new("Filósofo 1", left.clone(), right, false).unwrap().with_config(&config);
+    /// let _held = left.lock().unwrap();
+    /// for _ in 0..5 {
+    ///     philosopher.dine();
+    /// }
+    /// assert_eq!((philosopher.tastes(), philosopher.meals()), (5, 0));
+    /// assert_eq!(philosopher.fork_acquisitions(), [(1, 5)]);
+    ///
+    /// // Tasting now and then, every philosopher still eats all its meals
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(4)
+    ///     .meals(10)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .taste(0.5, Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.is_complete());
+    /// assert_eq!(result.total_meals(), 40);
+    /// assert!(result.tastes.iter().all(|&tastes| tastes > 0));
+    /// ```
+    pub fn taste(mut self, probability: f64, taste_time: Duration) -> Self {
+        self.config.taste_probability = probability;
+        self.config.taste_time = taste_time;
+        self
+    }
+
+    /// Split each meal into `bites` bites of equal length, with a point between them where the
+    /// philosopher may release its forks early ([See][SimulationConfigBuilder::between_bites]).
+    /// A meal cut short still counts as a meal.
+    pub fn bites(mut self, bites: u32) -> Self {
+        self.config.bites = bites;
+        self
+    }
+
+    /// Call `between_bites` with the name of the philosopher and the bites it took each time it
+    /// finishes a bite of its meal but the last one, from the thread of the philosopher and while
+    /// it holds its forks. Returning `true` ends the meal there and releases the forks, for
+    /// example when a more important neighbor needs one of them.
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let run = |preempt: bool| {
+    ///     let pauses = Arc::new(AtomicU32::new(0));
+    ///     let counter = pauses.clone();
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(2)
+    ///         .meals(3)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(100))
+    ///         .bites(4)
+    ///         .between_bites(move |philosopher, bites| {
+    ///             assert!(philosopher.starts_with("Filósofo") && (1..4).contains(&bites));
+    ///             counter.fetch_add(1, Ordering::SeqCst);
+    ///             preempt
+    ///         })
+    ///         .build()
+    ///         .unwrap();
+    ///     let start = Instant::now();
+    ///     assert!(run_simulation(&config).unwrap().is_complete());
+    ///     (pauses.load(Ordering::SeqCst), start.elapsed())
+    /// };
+    /// // Each of the 6 meals has 4 bites with a pause between each two of them
+    /// let (pauses, whole_meals) = run(false);
+    /// assert_eq!(pauses, 18);
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(whole_meals >= Duration::from_millis(600));
+    /// // Releasing the forks after the first bite, each meal takes a quarter of the time
+    /// let (pauses, first_bites) = run(true);
+    /// assert_eq!(pauses, 6);
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(first_bites < Duration::from_millis(450), "{first_bites:?}");
+    /// ```
+    pub fn between_bites(mut self, between_bites: impl Fn(&str, u32) -> bool + Send + Sync + 'static) -> Self {
+        self.config.between_bites = Some(Arc::new(between_bites));
+        self
+    }
+
+    /// Give the philosopher at `index` a priority, the higher the more important. Priorities only
+    /// matter with [SimulationConfigBuilder::priority_inheritance] and [Strategy::Reservation].
+    pub fn priority(mut self, index: usize, priority: u32) -> Self {
+        self.config.priorities.push((index, priority));
+        self
+    }
+
+    /// Set the nice value of the thread of the philosopher at `index` once it is spawned, to
+    /// study how the OS scheduler shares the meals. It is best effort, a nice value that can not
+    /// be set is ignored ([See][crate::set_thread_niceness]).
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(2)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .thread_niceness(0, 10)
+    ///     .thread_niceness(1, -20)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(config.niceness_of(1), Some(-20));
+    /// assert_eq!(config.niceness_of(2), None);
+    /// // Even without the privileges to raise the priority of philosopher 1 everybody eats
+    /// assert!(run_simulation(&config).unwrap().is_complete());
+    /// ```
+    #[cfg(feature = "thread-priority")]
+    pub fn thread_niceness(mut self, index: usize, nice: i32) -> Self {
+        self.config.niceness.push((index, nice));
+        self
+    }
+
+    /// Let a philosopher waiting for a fork lend its priority to the one holding it, which
+    /// finishes its meal early when the waiter has a higher priority than its own. This
+    /// approximates priority inheritance, reducing the waits of the important philosophers.
+    pub fn priority_inheritance(mut self, enabled: bool) -> Self {
+        self.config.priority_inheritance = enabled;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.config.strategy = strategy;
+        self
+    }
+
+    /// Choose which of its two forks each philosopher tries first ([See][AcquisitionOrder]). A
+    /// [fork order][SimulationConfigBuilder::fork_order] set for a philosopher comes first. The
+    /// order is the same on every attempt and on every run.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex, TryLockResult};
+    /// use std::time::Duration;
+    /// use actividad_12::{AcquisitionOrder, Fork, ForkGuard, Philosopher, SimulationConfig};
+    ///
+    /// // A fork that writes down its id each time it is taken
+    /// struct Logged(usize, Mutex<()>, Arc<Mutex<Vec<usize>>>);
+    ///
+    /// impl Fork<()> for Logged {
+    ///     fn try_lock(&self) -> TryLockResult<ForkGuard<'_, ()>> {
+    ///         let guard = Fork::try_lock(&self.1)?;
+    ///         self.2.lock().unwrap().push(self.0);
+    ///         Ok(guard)
+    ///     }
+    /// }
+    ///
+    /// let taken = |order| {
+    ///     let config = SimulationConfig::builder()
+    ///         .acquisition_order(order)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::ZERO)
+    ///         .build()
+    ///         .unwrap();
+    ///     let log = Arc::new(Mutex::new(Vec::new()));
+    ///     let (left, right) = (Logged(3, Mutex::new(()), log.clone()), Logged(4, Mutex::new(()), log.clone()));
+    ///     // Right handed
+    ///     let mut philosopher = Philosopher::new("Filósofo 1", Arc::new(left), Arc::new(right), false)
+    ///         .unwrap()
+    ///         .with_config(&config)
+    ///         .with_fork_ids(3, 4);
+    ///     for _ in 0..3 {
+    ///         philosopher.dine();
+    ///     }
+    ///     let log = log.lock().unwrap().clone();
+    ///     log
+    /// };
+    /// assert_eq!(taken(AcquisitionOrder::LowerIdFirst), [3, 4, 3, 4, 3, 4]);
+    /// assert_eq!(taken(AcquisitionOrder::LowerIdFirst), taken(AcquisitionOrder::LowerIdFirst));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// # {
+    /// assert_eq!(taken(AcquisitionOrder::Handedness), [4, 3, 4, 3, 4, 3]);
+    /// assert_eq!(taken(AcquisitionOrder::HigherIdFirst), [4, 3, 4, 3, 4, 3]);
+    /// # }
+    /// ```
+    pub fn acquisition_order(mut self, acquisition_order: AcquisitionOrder) -> Self {
+        self.config.acquisition_order = acquisition_order;
+        self
+    }
+
+    /// Let the philosopher at `index` take its forks in the order of their ids in `order`,
+    /// whatever its handedness, the [AcquisitionOrder] and [Strategy::AdaptiveOrder], to set up
+    /// the lock orderings that deadlock and the ones that do not. `order` must have the ids of
+    /// the two forks of the philosopher once each. The philosophers of a [Table][crate::Table]
+    /// and the ones taking whichever fork is free first do not follow it.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{estimate_deadlock_probability, AcquisitionStyle, SimulationConfig, TimeDistribution};
+    ///
+    /// let builder = || {
+    ///     SimulationConfig::builder()
+    ///         .philosophers(4)
+    ///         .meals(20)
+    ///         .acquisition_style(AcquisitionStyle::BlockingFirst)
+    ///         .think_distribution(TimeDistribution::Uniform { min: Duration::ZERO, max: Duration::from_millis(2) })
+    ///         .eat_time(Duration::from_millis(1))
+    ///         .fork_timeout(Duration::from_millis(20))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .time_budget(Duration::from_secs(5))
+    ///         .fork_order(0, vec![0, 1])
+    ///         .fork_order(1, vec![1, 2])
+    ///         .fork_order(2, vec![2, 3])
+    /// };
+    /// // Everybody waits for the next fork of the circle
+    /// let cyclic = builder().fork_order(3, vec![3, 0]).build().unwrap();
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(estimate_deadlock_probability(&cyclic, 5).unwrap() > 0.0);
+    /// // Fork 0 is taken first by both of its philosophers, which breaks the circle
+    /// let acyclic = builder().fork_order(3, vec![0, 3]).build().unwrap();
+    /// assert_eq!(estimate_deadlock_probability(&acyclic, 5).unwrap(), 0.0);
+    /// assert!(actividad_12::run_simulation(&acyclic).unwrap().is_complete());
+    ///
+    /// assert!(builder().fork_order(3, vec![3, 1]).build().is_err());
+    /// assert!(builder().fork_order(3, vec![3]).build().is_err());
+    /// ```
+    pub fn fork_order(mut self, index: usize, order: Vec<usize>) -> Self {
+        self.config.fork_orders.push((index, order));
+        self
+    }
+
+    pub fn acquisition_style(mut self, acquisition_style: AcquisitionStyle) -> Self {
+        self.config.acquisition_style = acquisition_style;
+        self
+    }
+
+    pub fn fork_backend(mut self, fork_backend: ForkBackend) -> Self {
+        self.config.fork_backend = fork_backend;
+        self
+    }
+
+    /// Stop the philosophers once `budget` passes since the simulation started, even if they did
+    /// not eat all their meals. Each philosopher checks it before each attempt to eat, so a
+    /// paused simulation does not stop until it is resumed.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig, SimulationError};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .think_time(Duration::from_millis(10))
+    ///     .eat_time(Duration::from_millis(10))
+    ///     .meals(u32::MAX)
+    ///     .time_budget(Duration::from_millis(100))
+    ///     .build()
+    ///     .unwrap();
+    /// let Err(SimulationError::BudgetExceeded(partial)) = run_simulation(&config) else {
+    ///     panic!("the budget should run out");
+    /// };
+    /// assert!(partial.total_meals() > 0);
+    /// assert!(!partial.is_complete());
+    /// ```
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.config.time_budget = Some(budget);
+        self
+    }
+
+    /// Decide with `policy` what happens when there are more than `philosophers_per_cpu`
+    /// philosophers per CPU ([See][std::thread::available_parallelism]), since then the context
+    /// switches between their threads dominate the timings.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Oversubscription, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(50)
+    ///     .oversubscription(Oversubscription::UsePool, 4)
+    ///     .build()
+    ///     .unwrap();
+    /// // 50 philosophers are too many for 4 CPUs but not for 16
+    /// assert!(config.is_oversubscribed(4));
+    /// assert_eq!(config.pool_size_for(4), Some(4));
+    /// assert!(!config.is_oversubscribed(16));
+    /// assert_eq!(config.pool_size_for(16), None);
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(50)
+    ///     .oversubscription(Oversubscription::Warn, 4)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(config.is_oversubscribed(4));
+    /// assert_eq!(config.pool_size_for(4), None);
+    ///
+    /// // The table eats the same on its own threads or on the pool
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(8)
+    ///     .meals(2)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .oversubscription(Oversubscription::UsePool, 1)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.is_complete());
+    /// assert_eq!(result.total_meals(), 16);
+    /// ```
+    pub fn oversubscription(mut self, policy: Oversubscription, philosophers_per_cpu: usize) -> Self {
+        self.config.oversubscription = policy;
+        self.config.philosophers_per_cpu = philosophers_per_cpu;
+        self
+    }
+
+    /// Decide with `policy` what happens when the thread of a philosopher panics, by default it is
+    /// ignored. [run_on_pool][crate::run_on_pool] does not use it.
+    /// ```rust
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::{run_simulation, ConfigError, Event, EventKind, JoinErrorPolicy, Observer, SimulationConfig, SimulationError};
+    ///
+    /// // The second philosopher panics the first time it does `kind`
+    /// struct PanicOnce {
+    ///     kind: EventKind,
+    ///     armed: AtomicBool,
+    /// }
+    ///
+    /// impl Observer for PanicOnce {
+    ///     fn on_event(&self, event: &Event) {
+    ///         if event.philosopher == "Filósofo 2" && event.kind == self.kind && self.armed.swap(false, Ordering::SeqCst) {
+    ///             panic!("se le cayó el tenedor");
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let run = |policy, kind, meals| {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(3)
+    ///         .meals(meals)
+    ///         .think_time(Duration::from_millis(1))
+    ///         .eat_time(Duration::from_millis(1))
+    ///         .time_budget(Duration::from_secs(30))
+    ///         .join_error_policy(policy)
+    ///         .observer(Arc::new(PanicOnce { kind, armed: AtomicBool::new(true) }))
+    ///         .build()
+    ///         .unwrap();
+    ///     run_simulation(&config)
+    /// };
+    ///
+    /// // The rest of the table eats all its meals
+    /// let result = run(JoinErrorPolicy::Ignore, EventKind::Thinking, 5).unwrap();
+    /// assert!(result.philosophers[1].panicked);
+    /// assert!([0, 2].iter().all(|&i| result.philosophers[i].meals == 5));
+    ///
+    /// // The table stops long before the time budget runs out
+    /// let start = Instant::now();
+    /// let error = run(JoinErrorPolicy::FailFast, EventKind::Eating, u32::MAX).unwrap_err();
+    /// assert!(matches!(error, SimulationError::PhilosopherPanicked(name) if name == "Filósofo 2"));
+    /// assert!(start.elapsed() < Duration::from_secs(10));
+    ///
+    /// // Panicking while eating poisons the forks, they are cleared when it sits again
+    /// let result = run(JoinErrorPolicy::RetrySpawn(1), EventKind::Eating, 5).unwrap();
+    /// assert!(result.is_complete());
+    /// assert!(result.philosophers.iter().all(|philosopher| !philosopher.panicked));
+    ///
+    /// // The rest of the table does not wait for it at the end of each round
+    /// let rounds = || SimulationConfig::builder().philosophers(3).rounds(4, Duration::ZERO).think_time(Duration::from_millis(1)).eat_time(Duration::from_millis(1));
+    /// let config = rounds().observer(Arc::new(PanicOnce { kind: EventKind::Eating, armed: AtomicBool::new(true) })).build().unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.philosophers[1].panicked);
+    /// assert_eq!(result.round_progress.len(), 4);
+    /// // Nor can it sit again, it would miss the end of the round
+    /// let error = rounds().join_error_policy(JoinErrorPolicy::RetrySpawn(1)).build().unwrap_err();
+    /// assert_eq!(error, ConfigError::RetrySpawnWithBarriers);
+    /// ```
+    pub fn join_error_policy(mut self, policy: JoinErrorPolicy) -> Self {
+        self.config.join_error_policy = policy;
+        self
+    }
+
+    /// Measure [Simulation::current_contention][crate::Simulation::current_contention] over the
+    /// last `window`, a shorter one follows the changes sooner but is noisier.
+    pub fn contention_window(mut self, window: Duration) -> Self {
+        self.config.contention_window = window;
+        self
+    }
+
+    /// Leave the time the simulation spent paused ([See][crate::Simulation::pause]) out of the
+    /// elapsed time.
+    pub fn exclude_paused_time(mut self, exclude: bool) -> Self {
+        self.config.exclude_paused_time = exclude;
+        self
+    }
+
+    /// Keep every fork wait and meal in [SimulationResult::samples] for an analysis outside of
+    /// the crate. They are not collected by default so nothing is stored when they are not used.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SampleKind, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(2)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .collect_samples(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// let fork_waits = result.samples.iter().filter(|sample| matches!(sample.kind, SampleKind::ForkWait { .. })).count();
+    /// assert_eq!(fork_waits, result.fork_waits.len());
+    /// let meals = result.samples.iter().filter(|sample| sample.kind == SampleKind::Meal).count();
+    /// assert_eq!(meals as u32, result.total_meals());
+    /// ```
+    pub fn collect_samples(mut self, collect: bool) -> Self {
+        self.config.collect_samples = collect;
+        self
+    }
+
+    /// Keep every phase each philosopher went through in [SimulationResult::timelines], to draw
+    /// them as a Gantt chart. A phase lasts until the next one begins, so the timeline of a
+    /// philosopher has no gaps from its first round until it leaves the table. Like the samples
+    /// it is not bounded, so it is not collected by default.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Phase, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(3)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .collect_timeline(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert_eq!(result.timelines.len(), 3);
+    /// for (stats, timeline) in result.philosophers.iter().zip(&result.timelines) {
+    ///     assert_eq!(timeline[0].phase, Phase::Thinking);
+    ///     for pair in timeline.windows(2) {
+    ///         assert_ne!(pair[0].phase, pair[1].phase);
+    ///         assert_eq!(pair[0].end, pair[1].start);
+    ///     }
+    ///     assert!(timeline.last().unwrap().end <= result.elapsed);
+    ///     let meals = timeline.iter().filter(|span| span.phase == Phase::Eating).count();
+    ///     assert_eq!(meals as u32, stats.meals);
+    /// }
+    /// ```
+    pub fn collect_timeline(mut self, collect: bool) -> Self {
+        self.config.collect_timeline = collect;
+        self
+    }
+
+    /// Keep at most `max` samples, shared among the philosophers, so the memory of a long run is
+    /// bounded. The ones left out are still aggregated in [SimulationResult::sample_stats].
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(200)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::ZERO)
+    ///     .collect_samples(true)
+    ///     .max_samples(10)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.samples.len() <= 10);
+    ///
+    /// let stats = &result.sample_stats;
+    /// assert_eq!(stats.meals.count(), u64::from(result.total_meals()));
+    /// assert_eq!(stats.fork_waits.count(), result.fork_waits.len() as u64);
+    /// assert_eq!(stats.fork_waits.histogram().total(), result.fork_waits.len() as u64);
+    /// let total: u128 = result.fork_waits.iter().map(Duration::as_nanos).sum();
+    /// let mean = total / result.fork_waits.len() as u128;
+    /// assert_eq!(stats.fork_waits.mean().unwrap().as_nanos(), mean);
+    /// assert_eq!(stats.fork_waits.min(), result.fork_waits.first().copied());
+    /// assert_eq!(stats.fork_waits.max(), result.fork_waits.last().copied());
+    /// ```
+    pub fn max_samples(mut self, max: usize) -> Self {
+        self.config.max_samples = Some(max);
+        self
+    }
+
+    /// Measure how long each fork sits idle between its release by a philosopher and its
+    /// acquisition by a neighbor ([See][crate::HandOffFork]) in [SimulationResult::hand_offs].
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(5)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .measure_hand_offs(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert_eq!(result.hand_offs.len(), 3);
+    /// let hand_offs: u64 = result.hand_offs.iter().map(|latencies| latencies.count()).sum();
+    /// assert!(hand_offs > 0);
+    /// for latencies in result.hand_offs.iter().filter(|latencies| latencies.count() > 0) {
+    ///     let (min, max) = (latencies.min().unwrap(), latencies.max().unwrap());
+    ///     assert!(Duration::ZERO <= min && min <= max && max <= result.elapsed);
+    /// }
+    /// ```
+    pub fn measure_hand_offs(mut self, measure: bool) -> Self {
+        self.config.measure_hand_offs = measure;
+        self
+    }
+
+    /// Count the sleeps between the retries and the calls to `try_lock` of each philosopher in
+    /// [SimulationResult::polls], the overhead of polling the forks.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let sleeps = |backoff: bool| {
+    ///     let mut builder = SimulationConfig::builder()
+    ///         .philosophers(2)
+    ///         .meals(3)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(40))
+    ///         .fork_timeout(Duration::from_millis(10))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .profile(true);
+    ///     if backoff {
+    ///         builder = builder.fork_backoff(Duration::from_millis(8));
+    ///     }
+    ///     let result = run_simulation(&builder.build().unwrap()).unwrap();
+    ///     assert_eq!(result.polls.len(), 2);
+    ///     assert!(result.total_polls().try_locks >= 2 * result.total_meals() as u64);
+    ///     result.total_polls().sleeps
+    /// };
+    /// let (fixed, backoff) = (sleeps(false), sleeps(true));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(backoff < fixed, "{backoff} sleeps with backoff, {fixed} without");
+    /// ```
+    pub fn profile(mut self, profile: bool) -> Self {
+        self.config.profile = profile;
+        self
+    }
+
+    pub fn observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.config.observer = observer;
+        self
+    }
+
+    /// Make the philosophers publish to `graph` the forks they hold and the one they wait for, so
+    /// a [DeadlockDetector][crate::DeadlockDetector] can show who waits for whom
+    /// ([See][crate::DeadlockDetector::with_wait_for_graph]).
+    pub fn wait_for_graph(mut self, graph: Arc<WaitForGraph>) -> Self {
+        self.config.wait_for_graph = Some(graph);
+        self
+    }
+
+    /// Check with `monitor` that no two philosophers sharing a fork eat at the same time
+    /// ([See][ExclusionMonitor]).
+    #[cfg(feature = "verify-exclusion")]
+    pub fn exclusion_monitor(mut self, monitor: Arc<ExclusionMonitor>) -> Self {
+        self.config.exclusion_monitor = Some(monitor);
+        self
+    }
+
+    /// Make spawning the thread of the philosopher at `index` fail, to test that the philosophers
+    /// already sitting leave the table when the rest can not join them.
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use actividad_12::{Event, Observer, Simulation, SimulationConfig, SimulationError};
+    ///
+    /// struct Quiet;
+    ///
+    /// impl Observer for Quiet {
+    ///     fn on_event(&self, _event: &Event) {}
+    /// }
+    ///
+    /// let observer = Arc::new(Quiet);
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(5)
+    ///     .observer(observer.clone())
+    ///     .fail_spawn(3)
+    ///     .build()
+    ///     .unwrap();
+    /// let Err(SimulationError::SpawnFailed(_)) = Simulation::start(&config) else {
+    ///     panic!("the thread of philosopher 3 should not be spawned");
+    /// };
+    /// // The threads of philosophers 0 to 2 were joined, only the config still has the observer
+    /// assert_eq!(Arc::strong_count(&observer), 2);
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn fail_spawn(mut self, index: usize) -> Self {
+        self.config.failed_spawn = Some(index);
+        self
+    }
+
+    /// Continue where the simulation of `result` left off, every philosopher begins with the
+    /// meals it ate there. Together with the `serde` feature a simulation can be saved and
+    /// restored later.
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Event, EventKind, Observer, PhilosopherStats, RetryHistogram, SampleStats, SimulationConfig, SimulationResult};
+    ///
+    /// #[derive(Default)]
+    /// struct MealCounter(AtomicU32);
+    ///
+    /// impl Observer for MealCounter {
+    ///     fn on_event(&self, event: &Event) {
+    ///         if let EventKind::Ate { .. } = event.kind {
+    ///             self.0.fetch_add(1, Ordering::SeqCst);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let saved = SimulationResult {
+    ///     philosophers: (1..=3).map(|i| PhilosopherStats { name: format!("Filósofo {i}"), meals: 3, panicked: false, satiated: false }).collect(),
+    ///     meals_target: 6,
+    ///     elapsed: Duration::from_millis(100),
+    ///     fork_waits: Vec::new(),
+    ///     single_fork_holds: Vec::new(),
+    ///     fork_acquisitions: Vec::new(),
+    ///     retry_sleeps: Vec::new(),
+    ///     failures: Vec::new(),
+    ///     polls: Vec::new(),
+    ///     first_meals: Vec::new(),
+    ///     samples: Vec::new(),
+    ///     sample_stats: SampleStats::default(),
+    ///     hand_offs: Vec::new(),
+    ///     peak_forks_held: 0,
+    ///     timelines: Vec::new(),
+    ///     cpu_time: None,
+    ///     fork_stats: Vec::new(),
+    ///     second_fork_retries: RetryHistogram::default(),
+    ///     round_progress: Vec::new(),
+    ///     tastes: Vec::new(),
+    /// };
+    /// let counter = Arc::new(MealCounter::default());
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(6)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .resume_from(&saved)
+    ///     .observer(counter.clone())
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.philosophers.iter().all(|stats| stats.meals == 6));
+    /// // Each philosopher ate the 3 meals it had left
+    /// assert_eq!(counter.0.load(Ordering::SeqCst), 9);
+    /// ```
+    pub fn resume_from(mut self, result: &SimulationResult) -> Self {
+        self.config.initial_meals = result.philosophers.iter().map(|stats| stats.meals).collect();
+        self
+    }
+
+    /// Validate the combination of parameters and build the config.
+    pub fn build(self) -> Result<SimulationConfig, ConfigError> {
+        let mut config = self.config;
+        if self.scale_fork_timeout && !self.fork_timeout_set {
+            let scale = (config.philosophers as f64 / DEFAULT_PHILOSOPHERS as f64).sqrt();
+            config.fork_timeout = DEFAULT_FORK_TIMEOUT.mul_f64(scale.max(1.0));
+        }
+        if config.philosophers == 1 {
+            return Err(ConfigError::TooFewPhilosophers(config.philosophers));
+        }
+        if let Some(seed) = self.left_handed_seed.filter(|_| config.philosophers > 0) {
+            let index = SeededSource::new(seed).next_u64() % config.philosophers as u64;
+            config.left_handed_index = Some(index as usize);
+        }
+        if let Some(index) = config.left_handed_index.filter(|&index| index >= config.philosophers) {
+            return Err(ConfigError::LeftHandedOutOfRange(index));
+        }
+        if let Some(seed) = self.fork_permutation_seed {
+            // Fisher-Yates shuffle
+            let mut random = SeededSource::new(seed);
+            let mut permutation: Vec<usize> = (0..config.philosophers).collect();
+            for i in (1..permutation.len()).rev() {
+                permutation.swap(i, (random.next_u64() % (i as u64 + 1)) as usize);
+            }
+            config.fork_permutation = Some(permutation);
+        }
+        if let Some(permutation) = &config.fork_permutation {
+            let mut ids = permutation.clone();
+            ids.sort_unstable();
+            if !ids.iter().copied().eq(0..config.philosophers) {
+                return Err(ConfigError::InvalidForkPermutation(permutation.clone()));
+            }
+        }
+        let distributions = config.philosopher_times.iter().flat_map(|&(_, think, eat)| [think, eat]);
+        if let Some(distribution) = [config.think_time, config.eat_time, config.start_stagger]
+            .into_iter()
+            .chain(distributions)
+            .find(|distribution| !distribution.is_valid())
+        {
+            return Err(ConfigError::InvalidDistribution(distribution));
+        }
+        if config.initial_meals.len() > config.philosophers {
+            return Err(ConfigError::PhilosopherOutOfRange(config.initial_meals.len() - 1));
+        }
+        if let Some(&(index, _, _)) = config.philosopher_times.iter().find(|(index, _, _)| *index >= config.philosophers) {
+            return Err(ConfigError::PhilosopherOutOfRange(index));
+        }
+        if let Some(&(index, _)) = config.priorities.iter().find(|(index, _)| *index >= config.philosophers) {
+            return Err(ConfigError::PhilosopherOutOfRange(index));
+        }
+        for (index, order) in &config.fork_orders {
+            if *index >= config.philosophers {
+                return Err(ConfigError::PhilosopherOutOfRange(*index));
+            }
+            let forks = [config.fork_at(*index), config.fork_at((index + 1) % config.philosophers)];
+            if order[..] != forks && order[..] != [forks[1], forks[0]] {
+                return Err(ConfigError::InvalidForkOrder { philosopher: *index, order: order.clone() });
+            }
+        }
+        #[cfg(feature = "thread-priority")]
+        if let Some(&(index, _)) = config.niceness.iter().find(|(index, _)| *index >= config.philosophers) {
+            return Err(ConfigError::PhilosopherOutOfRange(index));
+        }
+        if config.satiation_target.is_some_and(|target| !(target.is_finite() && target > 0.0)) {
+            return Err(ConfigError::InvalidSatiationTarget);
+        }
+        if !(0.0..=1.0).contains(&config.taste_probability) {
+            return Err(ConfigError::InvalidTasteProbability);
+        }
+        if config.bites == 0 {
+            return Err(ConfigError::ZeroBites);
+        }
+        if config.round_budget.is_some_and(|budget| budget.is_zero()) {
+            return Err(ConfigError::ZeroRoundBudget);
+        }
+        if config.contention_window.is_zero() {
+            return Err(ConfigError::ZeroContentionWindow);
+        }
+        if matches!(config.join_error_policy, JoinErrorPolicy::RetrySpawn(_)) && (config.warmup_meals > 0 || config.rounds.is_some()) {
+            return Err(ConfigError::RetrySpawnWithBarriers);
+        }
+        if config.fork_interval.is_zero() {
+            return Err(ConfigError::ZeroInterval);
+        }
+        if config.fork_interval >= config.fork_timeout {
+            return Err(ConfigError::IntervalNotBelowTimeout {
+                interval: config.fork_interval,
+                timeout: config.fork_timeout,
+            });
+        }
+        if let Some(max_interval) = config.fork_backoff.filter(|&max_interval| max_interval >= config.fork_timeout) {
+            return Err(ConfigError::IntervalNotBelowTimeout {
+                interval: max_interval,
+                timeout: config.fork_timeout,
+            });
+        }
+        Ok(config)
+    }
+}
+
+/// Reason why a [SimulationConfigBuilder] rejected its parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// A table with philosophers needs at least two of them so each one has two different forks,
+    /// an empty table is valid.
+    TooFewPhilosophers(usize),
+    /// There is no philosopher at the position of the left handed one.
+    LeftHandedOutOfRange(usize),
+    /// There is no philosopher at the position given to [SimulationConfigBuilder::philosopher_times]
+    /// or [SimulationConfigBuilder::priority] or [SimulationConfigBuilder::fork_order] or
+    /// `thread_niceness`, or
+    /// [SimulationConfigBuilder::resume_from] has more philosophers than the table.
+    PhilosopherOutOfRange(usize),
+    /// The minimum of a uniform distribution is greater than its maximum.
+    InvalidDistribution(TimeDistribution),
+    /// A zero retry interval would busy loop while waiting for a fork.
+    ZeroInterval,
+    /// The retry interval, and the longest one with [SimulationConfigBuilder::fork_backoff], must
+    /// be shorter than the fork timeout, otherwise a fork is tried only once.
+    IntervalNotBelowTimeout { interval: Duration, timeout: Duration },
+    /// The satiation target must be a positive number of seconds, otherwise a philosopher is full
+    /// before its first meal or never.
+    InvalidSatiationTarget,
+    /// The probability of tasting is not between 0 and 1.
+    InvalidTasteProbability,
+    /// A meal needs at least one bite.
+    ZeroBites,
+    /// With a zero round budget every round is abandoned and nobody ever eats.
+    ZeroRoundBudget,
+    /// A zero contention window holds no attempts.
+    ZeroContentionWindow,
+    /// The fork permutation does not have each id from `0` to the number of philosophers once,
+    /// so a fork would be missing and another one shared by more than two philosophers.
+    InvalidForkPermutation(Vec<usize>),
+    /// The fork order of a philosopher does not have the ids of its two forks once each
+    /// ([See][SimulationConfigBuilder::fork_order]).
+    InvalidForkOrder { philosopher: usize, order: Vec<usize> },
+    /// [JoinErrorPolicy::RetrySpawn] with a warmup or rounds, where the rest of the table would
+    /// wait for the philosopher that panicked forever.
+    RetrySpawnWithBarriers,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewPhilosophers(n) => write!(f, "either no philosophers or at least 2 are needed, got {n}"),
+            Self::LeftHandedOutOfRange(index) => write!(f, "there is no philosopher at position {index} to be left handed"),
+            Self::PhilosopherOutOfRange(index) => write!(f, "there is no philosopher at position {index}"),
+            Self::InvalidDistribution(distribution) => write!(f, "invalid time distribution {distribution:?}"),
+            Self::ZeroInterval => write!(f, "the fork interval must be greater than zero"),
+            Self::IntervalNotBelowTimeout { interval, timeout } => write!(
+                f,
+                "the fork interval ({interval:?}) must be shorter than the fork timeout ({timeout:?})"
+            ),
+            Self::InvalidSatiationTarget => write!(f, "the satiation target must be a positive number of seconds"),
+            Self::InvalidTasteProbability => write!(f, "the probability of tasting must be between 0 and 1"),
+            Self::ZeroBites => write!(f, "a meal needs at least one bite"),
+            Self::ZeroRoundBudget => write!(f, "the round budget must be greater than zero"),
+            Self::ZeroContentionWindow => write!(f, "the contention window must be greater than zero"),
+            Self::InvalidForkPermutation(permutation) => write!(f, "{permutation:?} is not a permutation of the fork ids"),
+            Self::InvalidForkOrder { philosopher, order } => write!(f, "{order:?} is not an order of the forks of the philosopher at position {philosopher}"),
+            Self::RetrySpawnWithBarriers => write!(f, "philosophers can not be respawned with a warmup or rounds"),
+        }
+    }
+}
+
+impl Error for ConfigError {}
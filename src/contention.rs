@@ -0,0 +1,72 @@
+use std::array;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Buckets the window of a [ContentionWindow] is split into.
+const BUCKETS: usize = 10;
+
+/// Attempts to take the forks and how many of them failed over the last window of time, for
+/// [Simulation::current_contention][crate::Simulation::current_contention]. The window is split
+/// into buckets of equal length that are reused once they get too old, so recording is a few
+/// atomic operations and the window slides a bucket at a time.
+pub(crate) struct ContentionWindow {
+    start: Instant,
+    bucket: Duration,
+    buckets: [Bucket; BUCKETS],
+}
+
+#[derive(Default)]
+struct Bucket {
+    /// Number of the bucket since the start, the counts belong to it.
+    slot: AtomicU64,
+    attempts: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl ContentionWindow {
+    pub(crate) fn new(window: Duration) -> Self {
+        Self {
+            start: Instant::now(),
+            bucket: (window / BUCKETS as u32).max(Duration::from_nanos(1)),
+            buckets: array::from_fn(|_| Bucket::default()),
+        }
+    }
+
+    /// Number of the current bucket since the start.
+    fn slot(&self) -> u64 {
+        (self.start.elapsed().as_nanos() / self.bucket.as_nanos()) as u64
+    }
+
+    /// Record an attempt to take the forks and whether it `failed`.
+    pub(crate) fn record(&self, failed: bool) {
+        let slot = self.slot();
+        let bucket = &self.buckets[slot as usize % BUCKETS];
+        let seen = bucket.slot.load(Ordering::SeqCst);
+        // Whoever moves the bucket to the new slot clears it, an attempt recorded by another
+        // thread in between may be lost, which is fine for an estimate
+        if seen != slot && bucket.slot.compare_exchange(seen, slot, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+            bucket.attempts.store(0, Ordering::SeqCst);
+            bucket.failures.store(0, Ordering::SeqCst);
+        }
+        bucket.attempts.fetch_add(1, Ordering::SeqCst);
+        if failed {
+            bucket.failures.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Failed attempts over all the attempts in the window, `0.0` without attempts.
+    pub(crate) fn ratio(&self) -> f64 {
+        let slot = self.slot();
+        let (attempts, failures) = self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.slot.load(Ordering::SeqCst) + BUCKETS as u64 > slot)
+            .fold((0, 0), |(attempts, failures), bucket| {
+                (attempts + bucket.attempts.load(Ordering::SeqCst), failures + bucket.failures.load(Ordering::SeqCst))
+            });
+        if attempts == 0 {
+            return 0.0;
+        }
+        failures as f64 / attempts as f64
+    }
+}
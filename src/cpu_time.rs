@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// CPU time the current thread consumed since it began, `None` where it can not be measured.
+///
+/// Only Linux is supported. Unlike the wall time it does not grow while the thread sleeps or
+/// waits for a lock, so comparing both tells how much of a run was spent waiting
+/// ([See][crate::SimulationResult::cpu_time]).
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::thread_cpu_time;
+///
+/// let before = thread_cpu_time();
+/// let mut sum = 0u64;
+/// for i in 0..1_000_000u64 {
+///     sum = sum.wrapping_add(std::hint::black_box(i));
+/// }
+/// match (before, thread_cpu_time()) {
+///     (Some(before), Some(after)) => assert!(after > before && after > Duration::ZERO),
+///     // Nothing is measured where it is unsupported
+///     (before, after) => assert!(before.is_none() && after.is_none()),
+/// }
+/// ```
+pub fn thread_cpu_time() -> Option<Duration> {
+    sys::thread_cpu_time()
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::ffi::{c_int, c_long};
+    use std::time::Duration;
+
+    const CLOCK_THREAD_CPUTIME_ID: c_int = 3;
+
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    extern "C" {
+        fn clock_gettime(clock_id: c_int, tp: *mut Timespec) -> c_int;
+    }
+
+    pub(super) fn thread_cpu_time() -> Option<Duration> {
+        let mut time = Timespec { tv_sec: 0, tv_nsec: 0 };
+        // SAFETY: clock_gettime only writes the timespec it is given
+        if unsafe { clock_gettime(CLOCK_THREAD_CPUTIME_ID, &mut time) } != 0 {
+            return None;
+        }
+        Some(Duration::new(u64::try_from(time.tv_sec).ok()?, u32::try_from(time.tv_nsec).ok()?))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::time::Duration;
+
+    pub(super) fn thread_cpu_time() -> Option<Duration> {
+        None
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::{run_simulation, Event, EventKind, Observer, SimulationConfig, SimulationError, SimulationResult};
+
+/// Run `config` `trials` times, each one with another seed, and return the fraction of the runs
+/// where a [DeadlockDetector] found a circular wait, an estimate of how likely the config is to
+/// deadlock. A run that exhausts the time budget still counts, so a config that can deadlock
+/// for real needs one.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{estimate_deadlock_probability, AcquisitionOrder, AcquisitionStyle, HandednessPattern, SimulationConfig, TimeDistribution};
+///
+/// let builder = || {
+///     SimulationConfig::builder()
+///         .philosophers(4)
+///         .meals(20)
+///         .acquisition_style(AcquisitionStyle::BlockingFirst)
+///         .think_distribution(TimeDistribution::Uniform { min: Duration::ZERO, max: Duration::from_millis(2) })
+///         .eat_time(Duration::from_millis(1))
+///         .fork_timeout(Duration::from_millis(20))
+///         .fork_interval(Duration::from_millis(1))
+///         .time_budget(Duration::from_secs(5))
+/// };
+/// // Taking the fork with the lower id first never closes a circle
+/// let ordered = builder().acquisition_order(AcquisitionOrder::LowerIdFirst).build().unwrap();
+/// assert_eq!(estimate_deadlock_probability(&ordered, 5).unwrap(), 0.0);
+///
+/// let naive = builder().handedness(HandednessPattern::AllRightHanded).build().unwrap();
+/// let probability = estimate_deadlock_probability(&naive, 5).unwrap();
+/// assert!((0.0..=1.0).contains(&probability));
+/// # #[cfg(not(feature = "no-timeouts"))]
+/// assert!(probability > 0.0);
+/// ```
+pub fn estimate_deadlock_probability(config: &SimulationConfig, trials: usize) -> Result<f64, SimulationError> {
+    if trials == 0 {
+        return Ok(0.0);
+    }
+    let n = config.philosophers();
+    let mut deadlocked = 0;
+    for trial in 0..trials {
+        let detector = Arc::new(DeadlockDetector::new(n).forward_to(config.observer().clone()));
+        // Every philosopher draws from its own seed, so the trials are spaced by the table size
+        let seed = config.seed().wrapping_add((trial * n) as u64);
+        match run_simulation(&config.clone().with_seed_and_observer(seed, detector.clone())) {
+            Ok(_) | Err(SimulationError::BudgetExceeded(_)) => {}
+            Err(error) => return Err(error),
+        }
+        if detector.detected() {
+            deadlocked += 1;
+        }
+    }
+    Ok(deadlocked as f64 / trials as f64)
+}
+
+/// Flag circular waits, when every philosopher of the table holds one fork and waits for the
+/// other one. With a fork timeout it ends once someone gives up, without it that is a deadlock.
+/// Every event is forwarded to another observer.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{DeadlockDetector, Event, EventKind, Observer};
+///
+/// let detector = DeadlockDetector::new(2);
+/// detector.on_fork_acquired("Filósofo 1", Duration::ZERO);
+/// detector.on_fork_acquired("Filósofo 2", Duration::ZERO);
+/// assert_eq!(detector.detections(), 1);
+///
+/// // Once someone gives up a new circular wait is needed for another detection
+/// detector.on_event(&Event::new("Filósofo 1", EventKind::Failed));
+/// detector.on_fork_acquired("Filósofo 2", Duration::ZERO);
+/// detector.on_fork_acquired("Filósofo 1", Duration::ZERO);
+/// assert_eq!(detector.detections(), 1);
+/// ```
+pub struct DeadlockDetector {
+    philosophers: usize,
+    inner: Arc<dyn Observer>,
+    /// Wait-for graph captured on each detection.
+    wait_for_graph: Option<Arc<WaitForGraph>>,
+    state: Mutex<HoldState>,
+}
+
+#[derive(Default)]
+struct HoldState {
+    /// Forks held by each philosopher holding any.
+    held: HashMap<String, u32>,
+    /// Whether every philosopher holds one fork right now.
+    waiting_in_circle: bool,
+    detections: usize,
+    snapshots: Vec<WaitForSnapshot>,
+}
+
+impl DeadlockDetector {
+    /// Detect the circular waits of a table of `philosophers` philosophers.
+    pub fn new(philosophers: usize) -> Self {
+        Self {
+            philosophers,
+            inner: Arc::new(()),
+            wait_for_graph: None,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Forward every event to `observer`.
+    pub fn forward_to(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.inner = observer;
+        self
+    }
+
+    /// Capture `graph` on each detection, the philosophers publish to it what they hold and wait
+    /// for when it is given to [SimulationConfigBuilder::wait_for_graph][crate::SimulationConfigBuilder::wait_for_graph].
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, AcquisitionStyle, DeadlockDetector, HandednessPattern, SimulationConfig, TimeDistribution, WaitForGraph};
+    ///
+    /// let graph = Arc::new(WaitForGraph::new());
+    /// let detector = Arc::new(DeadlockDetector::new(4).with_wait_for_graph(graph.clone()));
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(4)
+    ///     .meals(20)
+    ///     .handedness(HandednessPattern::AllRightHanded)
+    ///     .acquisition_style(AcquisitionStyle::BlockingFirst)
+    ///     .think_distribution(TimeDistribution::Uniform { min: Duration::ZERO, max: Duration::from_millis(2) })
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .fork_timeout(Duration::from_millis(20))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .time_budget(Duration::from_secs(5))
+    ///     .observer(detector.clone())
+    ///     .wait_for_graph(graph)
+    ///     .build()
+    ///     .unwrap();
+    /// let _ = run_simulation(&config);
+    /// let snapshots = detector.snapshots();
+    /// assert_eq!(snapshots.len(), detector.detections());
+    /// // Each philosopher holds the fork at its right and waits for the one at its left
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(snapshots.iter().any(|snapshot| snapshot.cycle().is_some_and(|cycle| cycle.len() == 4)));
+    /// ```
+    pub fn with_wait_for_graph(mut self, graph: Arc<WaitForGraph>) -> Self {
+        self.wait_for_graph = Some(graph);
+        self
+    }
+
+    /// The wait-for graph at each detection so far, empty without
+    /// [DeadlockDetector::with_wait_for_graph].
+    pub fn snapshots(&self) -> Vec<WaitForSnapshot> {
+        self.state.lock().unwrap().snapshots.clone()
+    }
+
+    /// Number of circular waits detected so far.
+    pub fn detections(&self) -> usize {
+        self.state.lock().unwrap().detections
+    }
+
+    pub fn detected(&self) -> bool {
+        self.detections() > 0
+    }
+
+    /// Set the forks held by `philosopher`, counting a detection when that closes the circle.
+    fn hold(&self, philosopher: &str, forks: impl FnOnce(u32) -> u32) {
+        let mut state = self.state.lock().unwrap();
+        let held = forks(state.held.get(philosopher).copied().unwrap_or(0));
+        if held == 0 {
+            state.held.remove(philosopher);
+        } else {
+            state.held.insert(philosopher.to_string(), held);
+        }
+        let waiting_in_circle = state.held.len() == self.philosophers && state.held.values().all(|&held| held == 1);
+        if waiting_in_circle && !state.waiting_in_circle {
+            state.detections += 1;
+            if let Some(graph) = &self.wait_for_graph {
+                state.snapshots.push(graph.snapshot());
+            }
+        }
+        state.waiting_in_circle = waiting_in_circle;
+    }
+}
+
+impl Observer for DeadlockDetector {
+    fn on_event(&self, event: &Event) {
+        // The forks are released after a failed attempt and after a meal
+        if matches!(event.kind, EventKind::Failed | EventKind::DoneEating | EventKind::Panicked) {
+            self.hold(&event.philosopher, |_| 0);
+        }
+        self.inner.on_event(event);
+    }
+
+    fn on_fork_acquired(&self, philosopher: &str, waited: Duration) {
+        self.hold(philosopher, |held| held + 1);
+        self.inner.on_fork_acquired(philosopher, waited);
+    }
+
+    fn on_starving(&self, philosopher: &str, hungry_for: Duration) {
+        self.inner.on_starving(philosopher, hungry_for);
+    }
+
+    fn on_finish(&self, result: &SimulationResult) {
+        self.inner.on_finish(result);
+    }
+}
+
+/// What each philosopher holds and waits for right now, published by the philosophers themselves
+/// ([See][crate::SimulationConfigBuilder::wait_for_graph]). A philosopher waiting for a fork held
+/// by another one waits for that philosopher, and a cycle of those waits is a deadlock.
+/// ```rust
+/// use actividad_12::WaitForGraph;
+///
+/// // Three philosophers each holding the fork at its right and waiting for the one at its left
+/// let graph = WaitForGraph::new();
+/// graph.publish("Filósofo 1", &[1], Some(0));
+/// graph.publish("Filósofo 2", &[2], Some(1));
+/// graph.publish("Filósofo 3", &[0], Some(2));
+/// let snapshot = graph.snapshot();
+/// assert_eq!(snapshot.cycle().unwrap(), ["Filósofo 1", "Filósofo 3", "Filósofo 2"]);
+/// let dot = snapshot.to_dot();
+/// assert!(dot.starts_with("digraph espera {"));
+/// assert!(dot.contains("\"Filósofo 1\" -> \"Tenedor 1\" [label=\"espera\"];"));
+/// assert!(dot.contains("\"Tenedor 1\" -> \"Filósofo 3\" [label=\"tomado por\"];"));
+///
+/// // Once one of them gives up nobody is waiting in a circle
+/// graph.publish("Filósofo 2", &[], None);
+/// assert_eq!(graph.snapshot().cycle(), None);
+/// ```
+#[derive(Debug, Default)]
+pub struct WaitForGraph {
+    philosophers: Mutex<BTreeMap<String, Waits>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Waits {
+    holding: Vec<usize>,
+    waiting: Option<usize>,
+}
+
+impl WaitForGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the ids of the forks `philosopher` holds and of the one it waits for, if any. A
+    /// philosopher holding and waiting for nothing is removed from the graph.
+    pub fn publish(&self, philosopher: &str, holding: &[usize], waiting: Option<usize>) {
+        let mut philosophers = self.philosophers.lock().unwrap();
+        if holding.is_empty() && waiting.is_none() {
+            philosophers.remove(philosopher);
+        } else {
+            let waits = Waits { holding: holding.to_vec(), waiting };
+            philosophers.insert(philosopher.to_string(), waits);
+        }
+    }
+
+    /// The graph as it is right now.
+    pub fn snapshot(&self) -> WaitForSnapshot {
+        let philosophers = self.philosophers.lock().unwrap();
+        WaitForSnapshot {
+            philosophers: philosophers.iter().map(|(name, waits)| (name.clone(), waits.holding.clone(), waits.waiting)).collect(),
+        }
+    }
+}
+
+/// The state of a [WaitForGraph] at some instant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaitForSnapshot {
+    /// Each philosopher in the graph, by name, with the ids of the forks it holds and of the one
+    /// it waits for.
+    pub philosophers: Vec<(String, Vec<usize>, Option<usize>)>,
+}
+
+impl WaitForSnapshot {
+    /// Philosophers waiting in a circle, each one waiting for a fork held by the next and the last
+    /// one for a fork held by the first, `None` if there is no such circle.
+    pub fn cycle(&self) -> Option<Vec<String>> {
+        let holders: HashMap<usize, usize> = self
+            .philosophers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (_, holding, _))| holding.iter().map(move |&fork| (fork, i)))
+            .collect();
+        // Who each philosopher waits for, following the fork it waits for to its holder
+        let next = |i: usize| self.philosophers[i].2.and_then(|fork| holders.get(&fork).copied());
+        for start in 0..self.philosophers.len() {
+            let mut path = vec![start];
+            let mut current = start;
+            while let Some(waited) = next(current) {
+                if let Some(position) = path.iter().position(|&i| i == waited) {
+                    return Some(path[position..].iter().map(|&i| self.philosophers[i].0.clone()).collect());
+                }
+                path.push(waited);
+                current = waited;
+            }
+        }
+        None
+    }
+
+    /// The graph in the DOT format of Graphviz, with an edge from each philosopher to the fork
+    /// it waits for and from each fork to the philosopher holding it.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph espera {\n");
+        for (name, holding, waiting) in &self.philosophers {
+            if let Some(fork) = waiting {
+                let _ = writeln!(dot, "    \"{name}\" -> \"Tenedor {}\" [label=\"espera\"];", fork + 1);
+            }
+            for fork in holding {
+                let _ = writeln!(dot, "    \"Tenedor {}\" -> \"{name}\" [label=\"tomado por\"];", fork + 1);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
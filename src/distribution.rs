@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use crate::SeededSource;
+
+/// Distribution of the time a philosopher spends thinking or eating.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{SeededSource, TimeDistribution};
+///
+/// let mut random = SeededSource::new(7);
+/// let distribution = TimeDistribution::Exponential { mean: Duration::from_millis(10) };
+/// let samples = 10_000;
+/// let total: Duration = (0..samples).map(|_| distribution.sample(&mut random)).sum();
+/// let mean = total / samples;
+/// assert!(mean > Duration::from_millis(9) && mean < Duration::from_millis(11));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeDistribution {
+    /// Always the same time.
+    Constant(Duration),
+    /// Any time between `min` and `max` with the same probability.
+    Uniform { min: Duration, max: Duration },
+    /// Exponentially distributed times, the memoryless service times of queueing theory.
+    Exponential { mean: Duration },
+}
+
+impl TimeDistribution {
+    #[track_caller]
+    pub fn sample(&self, random: &mut SeededSource) -> Duration {
+        match *self {
+            Self::Constant(duration) => duration,
+            Self::Uniform { min, max } => min + (max.saturating_sub(min)).mul_f64(random.next_f64()),
+            // Inverse transform sampling, `1 - u` is never zero
+            Self::Exponential { mean } => mean.mul_f64(-(1.0 - random.next_f64()).ln()),
+        }
+    }
+
+    /// The expected time.
+    pub fn mean(&self) -> Duration {
+        match *self {
+            Self::Constant(duration) => duration,
+            Self::Uniform { min, max } => (min + max) / 2,
+            Self::Exponential { mean } => mean,
+        }
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        match *self {
+            Self::Uniform { min, max } => min <= max,
+            Self::Constant(_) | Self::Exponential { .. } => true,
+        }
+    }
+}
+
+impl From<Duration> for TimeDistribution {
+    fn from(duration: Duration) -> Self {
+        Self::Constant(duration)
+    }
+}
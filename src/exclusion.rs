@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Check that two philosophers sharing a fork never eat at the same time. Every philosopher
+/// configured with [SimulationConfigBuilder::exclusion_monitor][crate::SimulationConfigBuilder::exclusion_monitor]
+/// tells the monitor the ids of its forks when it begins and ends eating, and beginning to eat
+/// with a fork someone else is eating with is a violation, written to stderr and kept in
+/// [ExclusionMonitor::violations].
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use actividad_12::{run_simulation, ExclusionMonitor, SimulationConfig};
+///
+/// let monitor = Arc::new(ExclusionMonitor::new());
+/// let config = SimulationConfig::builder()
+///     .philosophers(5)
+///     .meals(10)
+///     .think_time(Duration::ZERO)
+///     .eat_time(Duration::from_millis(1))
+///     .exclusion_monitor(monitor.clone())
+///     .build()
+///     .unwrap();
+/// let result = run_simulation(&config).unwrap();
+/// assert_eq!(monitor.meals_checked(), result.total_meals() as u64);
+/// assert!(monitor.violations().is_empty());
+///
+/// // Two neighbors eating at once with the fork they share is reported
+/// let monitor = ExclusionMonitor::new();
+/// monitor.begin_eating("Filósofo 1", [0, 1]);
+/// monitor.begin_eating("Filósofo 2", [1, 2]);
+/// assert_eq!(monitor.violations().len(), 1);
+/// assert_eq!(monitor.violations()[0].fork, 1);
+/// monitor.end_eating("Filósofo 1", [0, 1]);
+/// monitor.end_eating("Filósofo 2", [1, 2]);
+/// monitor.begin_eating("Filósofo 2", [1, 2]);
+/// assert_eq!(monitor.violations().len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct ExclusionMonitor {
+    state: Mutex<ExclusionState>,
+}
+
+#[derive(Debug, Default)]
+struct ExclusionState {
+    /// Who is eating with each fork, by fork id.
+    eaters: HashMap<usize, String>,
+    meals_checked: u64,
+    violations: Vec<ExclusionViolation>,
+}
+
+/// Two philosophers that were eating at the same time with the same fork.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExclusionViolation {
+    /// Id of the shared fork.
+    pub fork: usize,
+    /// The philosopher already eating.
+    pub eating: String,
+    /// The philosopher that began eating too.
+    pub intruder: String,
+}
+
+impl ExclusionMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `philosopher` began eating with `forks`, reporting a violation for each one
+    /// someone else is eating with.
+    pub fn begin_eating(&self, philosopher: &str, forks: [usize; 2]) {
+        let mut state = self.state.lock().unwrap();
+        state.meals_checked += 1;
+        for fork in forks {
+            if let Some(eating) = state.eaters.insert(fork, philosopher.to_string()) {
+                eprintln!("{philosopher} empieza a comer con el tenedor {} mientras {eating} come con él.", fork + 1);
+                state.violations.push(ExclusionViolation {
+                    fork,
+                    eating,
+                    intruder: philosopher.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Record that `philosopher` finished eating and is about to release `forks`.
+    pub fn end_eating(&self, philosopher: &str, forks: [usize; 2]) {
+        let mut state = self.state.lock().unwrap();
+        for fork in forks {
+            if state.eaters.get(&fork).is_some_and(|eating| eating == philosopher) {
+                state.eaters.remove(&fork);
+            }
+        }
+    }
+
+    /// Number of meals whose forks were checked so far.
+    pub fn meals_checked(&self) -> u64 {
+        self.state.lock().unwrap().meals_checked
+    }
+
+    /// Every violation found so far.
+    pub fn violations(&self) -> Vec<ExclusionViolation> {
+        self.state.lock().unwrap().violations.clone()
+    }
+}
@@ -0,0 +1,711 @@
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, TryLockError, TryLockResult};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use crate::lock::{lock_forever, lock_until, WAIT_FOREVER};
+use crate::{RunningStats, SeededSource};
+
+/// Guard of a taken fork, the fork is released when it is dropped.
+pub type ForkGuard<'a, T> = Box<dyn DerefMut<Target = T> + 'a>;
+
+/// Something a philosopher can take to eat. Any [Mutex] is a fork, other implementations change
+/// how the fork behaves without the philosophers knowing about it.
+pub trait Fork<T>: Send + Sync {
+    /// Try to take the fork without waiting.
+    fn try_lock(&self) -> TryLockResult<ForkGuard<'_, T>>;
+
+    /// Wait up to `timeout` for the fork, giving up as soon as `cancel` is set. By default the
+    /// fork is tried every `interval`, or waited for with [Fork::lock_blocking] when the timeout
+    /// is [WAIT_FOREVER] or longer and nothing can cancel the wait. A fork that knows when it is
+    /// released can wait for that instead ([See][NotifyingFork]).
+    fn lock_for(&self, timeout: Duration, interval: Duration, cancel: Option<&AtomicBool>) -> TryLockResult<ForkGuard<'_, T>> {
+        if timeout >= WAIT_FOREVER && cancel.is_none() {
+            return lock_forever(|| self.try_lock(), || self.lock_blocking());
+        }
+        lock_until(timeout, interval, cancel, || self.try_lock())
+    }
+
+    /// Wait for the fork as long as it takes without ever sleeping, the way the philosophers
+    /// take their forks with the `no-timeouts` feature. It never fails with
+    /// [TryLockError::WouldBlock]. By default the thread yields between the attempts, a fork that
+    /// can block until it is released does that instead.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(5)
+    ///     .meals(20)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .profile(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.is_complete());
+    /// // Without the timeouts nobody sleeps between attempts to take a fork
+    /// # #[cfg(feature = "no-timeouts")]
+    /// assert_eq!(result.total_polls().sleeps, 0);
+    /// # #[cfg(feature = "no-timeouts")]
+    /// assert!(result.failures.iter().all(|failures| failures.total() == 0));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(result.total_polls().sleeps > 0);
+    /// ```
+    fn lock_blocking(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        loop {
+            match self.try_lock() {
+                Err(TryLockError::WouldBlock) => thread::yield_now(),
+                result => return result,
+            }
+        }
+    }
+
+    /// Forget that a thread panicked while holding the fork, so it is taken again without
+    /// [TryLockError::Poisoned]. By default nothing is done, for forks that are never poisoned.
+    fn clear_poison(&self) {}
+}
+
+impl<T: Send> Fork<T> for Mutex<T> {
+    #[inline]
+    fn try_lock(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        match Mutex::try_lock(self) {
+            Ok(guard) => Ok(Box::new(guard)),
+            Err(TryLockError::Poisoned(guard)) => Err(TryLockError::Poisoned(PoisonError::new(Box::new(guard.into_inner())))),
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+
+    fn lock_blocking(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        match Mutex::lock(self) {
+            Ok(guard) => Ok(Box::new(guard)),
+            Err(poisoned) => Err(TryLockError::Poisoned(PoisonError::new(Box::new(poisoned.into_inner())))),
+        }
+    }
+
+    fn clear_poison(&self) {
+        Mutex::clear_poison(self);
+    }
+}
+
+/// A fork that breaks from time to time. Each attempt to take a working fork breaks it with a
+/// `failure_probability`, then every attempt fails until `downtime` passes and the fork works
+/// again.
+/// ```rust
+/// use std::sync::TryLockError;
+/// use std::time::Duration;
+/// use actividad_12::{FaultyFork, Fork};
+///
+/// let fork = FaultyFork::new((), 1.0, Duration::from_secs(60), 0);
+/// assert!(matches!(fork.try_lock(), Err(TryLockError::WouldBlock)));
+/// ```
+pub struct FaultyFork<T> {
+    fork: Mutex<T>,
+    failure_probability: f64,
+    downtime: Duration,
+    state: Mutex<FaultState>,
+}
+
+struct FaultState {
+    broken_until: Option<Instant>,
+    random: SeededSource,
+}
+
+impl<T> FaultyFork<T> {
+    pub fn new(value: T, failure_probability: f64, downtime: Duration, seed: u64) -> Self {
+        Self {
+            fork: Mutex::new(value),
+            failure_probability,
+            downtime,
+            state: Mutex::new(FaultState {
+                broken_until: None,
+                random: SeededSource::new(seed),
+            }),
+        }
+    }
+
+    /// Whether the fork is broken right now or breaks with this attempt.
+    fn is_broken(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        if state.broken_until.is_some_and(|until| now < until) {
+            return true;
+        }
+        state.broken_until = None;
+        if state.random.next_f64() < self.failure_probability {
+            state.broken_until = Some(now + self.downtime);
+            return true;
+        }
+        false
+    }
+}
+
+impl<T: Send> Fork<T> for FaultyFork<T> {
+    fn try_lock(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        if self.is_broken() {
+            return Err(TryLockError::WouldBlock);
+        }
+        Fork::try_lock(&self.fork)
+    }
+
+    fn clear_poison(&self) {
+        self.fork.clear_poison();
+    }
+}
+
+/// A fork that wakes whoever waits for it as soon as it is released, instead of letting them
+/// poll it every retry interval. The retry interval is only used to check if the wait was
+/// cancelled.
+/// ```rust
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::{Duration, Instant};
+/// use actividad_12::{Fork, NotifyingFork, TimeoutLock};
+///
+/// let fork: Arc<dyn Fork<u32>> = Arc::new(NotifyingFork::new(0));
+/// let guard = fork.try_lock().unwrap();
+/// let waiter = {
+///     let fork = fork.clone();
+///     thread::spawn(move || {
+///         let start = Instant::now();
+///         let mut guard = fork.try_lock_for(Duration::from_secs(5), Duration::from_secs(1)).unwrap();
+///         **guard += 1;
+///         start.elapsed()
+///     })
+/// };
+/// thread::sleep(Duration::from_millis(20));
+/// drop(guard);
+/// // Woken long before the next retry would have happened
+/// assert!(waiter.join().unwrap() < Duration::from_secs(1));
+/// assert_eq!(**fork.try_lock().unwrap(), 1);
+/// ```
+pub struct NotifyingFork<T> {
+    value: Mutex<T>,
+    /// Whether someone holds the fork, the value is only locked by whoever set it.
+    taken: Mutex<bool>,
+    released: Condvar,
+}
+
+impl<T> NotifyingFork<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Mutex::new(value),
+            taken: Mutex::new(false),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Lock the value once the fork was taken.
+    fn guard(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        let guard = |value| -> ForkGuard<'_, T> { Box::new(NotifyingGuard { value: Some(value), fork: self }) };
+        match self.value.lock() {
+            Ok(value) => Ok(guard(value)),
+            Err(poisoned) => Err(TryLockError::Poisoned(PoisonError::new(guard(poisoned.into_inner())))),
+        }
+    }
+}
+
+impl<T: Send> Fork<T> for NotifyingFork<T> {
+    fn try_lock(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        let mut taken = self.taken.lock().unwrap_or_else(PoisonError::into_inner);
+        if *taken {
+            return Err(TryLockError::WouldBlock);
+        }
+        *taken = true;
+        drop(taken);
+        self.guard()
+    }
+
+    fn lock_for(&self, timeout: Duration, interval: Duration, cancel: Option<&AtomicBool>) -> TryLockResult<ForkGuard<'_, T>> {
+        let cancelled = || cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed));
+        // A timeout too long to have a deadline never runs out
+        let deadline = Instant::now().checked_add(timeout);
+        let mut taken = self.taken.lock().unwrap_or_else(PoisonError::into_inner);
+        while *taken {
+            let now = Instant::now();
+            if deadline.is_some_and(|deadline| now >= deadline) || cancelled() {
+                return Err(TryLockError::WouldBlock);
+            }
+            // Wake up every interval to check if the wait was cancelled
+            let wait = deadline.map_or(interval, |deadline| (deadline - now).min(interval));
+            taken = self.released.wait_timeout(taken, wait).unwrap_or_else(PoisonError::into_inner).0;
+        }
+        *taken = true;
+        drop(taken);
+        self.guard()
+    }
+
+    fn lock_blocking(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        let taken = self.taken.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut taken = self.released.wait_while(taken, |taken| *taken).unwrap_or_else(PoisonError::into_inner);
+        *taken = true;
+        drop(taken);
+        self.guard()
+    }
+
+    fn clear_poison(&self) {
+        self.value.clear_poison();
+    }
+}
+
+struct NotifyingGuard<'a, T> {
+    /// Always `Some` until the guard is dropped.
+    value: Option<MutexGuard<'a, T>>,
+    fork: &'a NotifyingFork<T>,
+}
+
+impl<T> Deref for NotifyingGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("the value is locked while the guard lives")
+    }
+}
+
+impl<T> DerefMut for NotifyingGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("the value is locked while the guard lives")
+    }
+}
+
+impl<T> Drop for NotifyingGuard<'_, T> {
+    fn drop(&mut self) {
+        // Unlock the value before anyone is woken up to take it
+        self.value = None;
+        *self.fork.taken.lock().unwrap_or_else(PoisonError::into_inner) = false;
+        self.fork.released.notify_one();
+    }
+}
+
+/// A fork handed over in the order it was waited for, so no philosopher starves however unlucky
+/// its retries are. Waiting with [Fork::lock_for] takes a place in a queue and only the first one
+/// in the queue takes the fork once it is free, [Fork::try_lock] only takes it when nobody waits.
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use std::thread;
+/// use std::time::Duration;
+/// use actividad_12::{Fork, QueuedMutex, TimeoutLock};
+///
+/// let fork: Arc<dyn Fork<()>> = Arc::new(QueuedMutex::new(()));
+/// let order = Arc::new(Mutex::new(Vec::new()));
+/// let guard = fork.try_lock().unwrap();
+/// let waiters: Vec<_> = (0..4)
+///     .map(|i| {
+///         let (fork, order) = (fork.clone(), order.clone());
+///         let waiter = thread::spawn(move || {
+///             let _guard = fork.try_lock_for(Duration::from_secs(5), Duration::from_millis(1)).unwrap();
+///             order.lock().unwrap().push(i);
+///             thread::sleep(Duration::from_millis(5));
+///         });
+///         // Let the waiter take its place in the queue before the next one comes
+///         thread::sleep(Duration::from_millis(20));
+///         waiter
+///     })
+///     .collect();
+/// drop(guard);
+/// for waiter in waiters {
+///     waiter.join().unwrap();
+/// }
+/// assert_eq!(*order.lock().unwrap(), [0, 1, 2, 3]);
+/// ```
+pub struct QueuedMutex<T> {
+    value: Mutex<T>,
+    queue: Mutex<WaitQueue>,
+}
+
+#[derive(Default)]
+struct WaitQueue {
+    /// Tickets of the waiters, the first one is next.
+    waiting: VecDeque<u64>,
+    next_ticket: u64,
+}
+
+impl<T> QueuedMutex<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Mutex::new(value),
+            queue: Mutex::new(WaitQueue::default()),
+        }
+    }
+
+    fn queue(&self) -> MutexGuard<'_, WaitQueue> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Send> Fork<T> for QueuedMutex<T> {
+    fn try_lock(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        let queue = self.queue();
+        if !queue.waiting.is_empty() {
+            return Err(TryLockError::WouldBlock);
+        }
+        Fork::try_lock(&self.value)
+    }
+
+    fn lock_for(&self, timeout: Duration, interval: Duration, cancel: Option<&AtomicBool>) -> TryLockResult<ForkGuard<'_, T>> {
+        if let Ok(guard) = self.try_lock() {
+            return Ok(guard);
+        }
+        let ticket = {
+            let mut queue = self.queue();
+            let ticket = queue.next_ticket;
+            queue.next_ticket += 1;
+            queue.waiting.push_back(ticket);
+            ticket
+        };
+        // Take the fork when it is the turn of the ticket, or leave the queue when giving up
+        lock_until(timeout, interval, cancel, || {
+            let mut queue = self.queue();
+            if queue.waiting.front() != Some(&ticket) {
+                return Err(TryLockError::WouldBlock);
+            }
+            let result = Fork::try_lock(&self.value);
+            if !matches!(result, Err(TryLockError::WouldBlock)) {
+                queue.waiting.pop_front();
+            }
+            result
+        })
+        .inspect_err(|error| {
+            if let TryLockError::WouldBlock = error {
+                self.queue().waiting.retain(|&waiting| waiting != ticket);
+            }
+        })
+    }
+
+    fn clear_poison(&self) {
+        self.value.clear_poison();
+    }
+}
+
+/// A fork that measures its hand-off latency, the time from its release by a thread to its
+/// acquisition by another one, with any fork inside. Short latencies mean the fork does not sit
+/// idle while a neighbor waits for it, long ones reveal a coarse retry interval or a slow
+/// scheduler. A thread taking the fork again after releasing it is not a hand-off.
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use std::thread;
+/// use std::time::Duration;
+/// use actividad_12::{Fork, HandOffFork, TimeoutLock};
+///
+/// let fork = Arc::new(HandOffFork::new(Arc::new(Mutex::new(()))));
+/// let guard = fork.try_lock().unwrap();
+/// let neighbor = {
+///     let fork = fork.clone();
+///     thread::spawn(move || {
+///         let fork: &dyn Fork<()> = &*fork;
+///         drop(fork.try_lock_for(Duration::from_secs(5), Duration::from_millis(1)).unwrap());
+///         // Taking it again from the same thread is not a hand-off
+///         drop(fork.try_lock().unwrap());
+///     })
+/// };
+/// thread::sleep(Duration::from_millis(20));
+/// drop(guard);
+/// neighbor.join().unwrap();
+/// let latencies = fork.latencies();
+/// assert_eq!(latencies.count(), 1);
+/// assert!(latencies.max().unwrap() < Duration::from_millis(20));
+/// ```
+pub struct HandOffFork<T> {
+    fork: Arc<dyn Fork<T>>,
+    state: Mutex<HandOffState>,
+}
+
+#[derive(Default)]
+struct HandOffState {
+    /// Thread that released the fork last and when, `None` while it is held.
+    released: Option<(ThreadId, Instant)>,
+    latencies: RunningStats,
+}
+
+impl<T> HandOffFork<T> {
+    pub fn new(fork: Arc<dyn Fork<T>>) -> Self {
+        Self {
+            fork,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Hand-off latencies measured so far.
+    pub fn latencies(&self) -> RunningStats {
+        self.state().latencies.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, HandOffState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record the hand-off, if the fork was released by another thread, and wrap the guard so
+    /// the release is recorded too.
+    fn taken<'a>(&'a self, result: TryLockResult<ForkGuard<'a, T>>) -> TryLockResult<ForkGuard<'a, T>> {
+        let guard = |guard| -> ForkGuard<'a, T> { Box::new(HandOffGuard { guard: Some(guard), fork: self }) };
+        if let Err(TryLockError::WouldBlock) = result {
+            return Err(TryLockError::WouldBlock);
+        }
+        let mut state = self.state();
+        if let Some((thread, released_at)) = state.released.take() {
+            if thread != thread::current().id() {
+                state.latencies.record(released_at.elapsed());
+            }
+        }
+        match result {
+            Ok(taken) => Ok(guard(taken)),
+            Err(TryLockError::Poisoned(poisoned)) => Err(TryLockError::Poisoned(PoisonError::new(guard(poisoned.into_inner())))),
+            Err(TryLockError::WouldBlock) => unreachable!("a fork that was not taken returned early"),
+        }
+    }
+}
+
+impl<T: Send> Fork<T> for HandOffFork<T> {
+    fn try_lock(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        self.taken(self.fork.try_lock())
+    }
+
+    fn lock_for(&self, timeout: Duration, interval: Duration, cancel: Option<&AtomicBool>) -> TryLockResult<ForkGuard<'_, T>> {
+        self.taken(self.fork.lock_for(timeout, interval, cancel))
+    }
+
+    fn lock_blocking(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        self.taken(self.fork.lock_blocking())
+    }
+
+    fn clear_poison(&self) {
+        self.fork.clear_poison();
+    }
+}
+
+struct HandOffGuard<'a, T> {
+    /// Always `Some` until the guard is dropped.
+    guard: Option<ForkGuard<'a, T>>,
+    fork: &'a HandOffFork<T>,
+}
+
+impl<T> Deref for HandOffGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().expect("the fork is held while the guard lives")
+    }
+}
+
+impl<T> DerefMut for HandOffGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.guard.as_mut().expect("the fork is held while the guard lives")
+    }
+}
+
+impl<T> Drop for HandOffGuard<'_, T> {
+    fn drop(&mut self) {
+        // Whoever takes the fork next waits for the release to be recorded
+        let mut state = self.fork.state();
+        self.guard = None;
+        state.released = Some((thread::current().id(), Instant::now()));
+    }
+}
+
+/// Forks of a table held right now and the most held at once, and how long each fork was held,
+/// shared by their [CountedFork]s.
+#[derive(Debug, Default)]
+pub(crate) struct HeldForks {
+    held: AtomicUsize,
+    peak: AtomicUsize,
+    /// Time each fork was held so far, the position is the id of the fork.
+    held_times: Mutex<Vec<Duration>>,
+}
+
+impl HeldForks {
+    /// Most forks held at once so far.
+    pub(crate) fn peak(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+
+    /// Time each of the first `forks` forks was held so far.
+    pub(crate) fn held_times(&self, forks: usize) -> Vec<Duration> {
+        let mut held_times = self.held_times.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        held_times.resize(forks, Duration::ZERO);
+        held_times
+    }
+
+    fn acquired(&self) {
+        let held = self.held.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut peak = self.peak.load(Ordering::SeqCst);
+        while held > peak {
+            match self.peak.compare_exchange_weak(peak, held, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => break,
+                Err(current) => peak = current,
+            }
+        }
+    }
+
+    fn released(&self, id: usize, held_for: Duration) {
+        self.held.fetch_sub(1, Ordering::SeqCst);
+        let mut held_times = self.held_times.lock().unwrap_or_else(PoisonError::into_inner);
+        if held_times.len() <= id {
+            held_times.resize(id + 1, Duration::ZERO);
+        }
+        held_times[id] += held_for;
+    }
+}
+
+/// A fork counted in [HeldForks] while it is held, with any fork inside.
+pub(crate) struct CountedFork<T> {
+    fork: Arc<dyn Fork<T>>,
+    id: usize,
+    held: Arc<HeldForks>,
+}
+
+impl<T> CountedFork<T> {
+    pub(crate) fn new(fork: Arc<dyn Fork<T>>, id: usize, held: Arc<HeldForks>) -> Self {
+        Self { fork, id, held }
+    }
+
+    /// Count the fork as held, also when it was poisoned, until the guard is dropped.
+    fn taken<'a>(&'a self, result: TryLockResult<ForkGuard<'a, T>>) -> TryLockResult<ForkGuard<'a, T>> {
+        let guard = |guard| -> ForkGuard<'a, T> {
+            self.held.acquired();
+            Box::new(CountedGuard { guard, fork: self, since: Instant::now() })
+        };
+        match result {
+            Ok(taken) => Ok(guard(taken)),
+            Err(TryLockError::Poisoned(poisoned)) => Err(TryLockError::Poisoned(PoisonError::new(guard(poisoned.into_inner())))),
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+}
+
+impl<T: Send> Fork<T> for CountedFork<T> {
+    fn try_lock(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        self.taken(self.fork.try_lock())
+    }
+
+    fn lock_for(&self, timeout: Duration, interval: Duration, cancel: Option<&AtomicBool>) -> TryLockResult<ForkGuard<'_, T>> {
+        self.taken(self.fork.lock_for(timeout, interval, cancel))
+    }
+
+    fn lock_blocking(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        self.taken(self.fork.lock_blocking())
+    }
+
+    fn clear_poison(&self) {
+        self.fork.clear_poison();
+    }
+}
+
+struct CountedGuard<'a, T> {
+    guard: ForkGuard<'a, T>,
+    fork: &'a CountedFork<T>,
+    /// When the fork was taken.
+    since: Instant,
+}
+
+impl<T> Deref for CountedGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for CountedGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for CountedGuard<'_, T> {
+    fn drop(&mut self) {
+        // Counted as released slightly before the fork is, so the peak never overshoots
+        self.fork.held.released(self.fork.id, self.since.elapsed());
+    }
+}
+
+/// A fork that takes `delay` to be taken, to test the timeout paths without real contention.
+/// Each call to `try_lock` sleeps `delay` before trying the fork. Waiting for it with
+/// [TimeoutLock::try_lock_for][crate::TimeoutLock::try_lock_for] sleeps the delay only when it
+/// fits in the timeout, otherwise it sleeps the timeout and gives up with
+/// [TryLockError::WouldBlock].
+/// ```rust
+/// use std::sync::{Arc, TryLockError};
+/// use std::time::{Duration, Instant};
+/// use actividad_12::{Fork, SlowFork, TimeoutLock};
+///
+/// let fork: Arc<dyn Fork<()>> = Arc::new(SlowFork::new((), Duration::from_millis(200)));
+/// let start = Instant::now();
+/// let result = fork.try_lock_for(Duration::from_millis(50), Duration::from_millis(5));
+/// assert!(matches!(result, Err(TryLockError::WouldBlock)));
+/// let elapsed = start.elapsed();
+/// assert!(Duration::from_millis(50) <= elapsed && elapsed < Duration::from_millis(150), "{elapsed:?}");
+///
+/// let fork: Arc<dyn Fork<()>> = Arc::new(SlowFork::new((), Duration::from_millis(20)));
+/// let start = Instant::now();
+/// assert!(fork.try_lock().is_ok());
+/// assert!(start.elapsed() >= Duration::from_millis(20));
+/// ```
+#[cfg(feature = "test-util")]
+pub struct SlowFork<T> {
+    fork: Mutex<T>,
+    delay: Duration,
+}
+
+#[cfg(feature = "test-util")]
+impl<T> SlowFork<T> {
+    pub fn new(value: T, delay: Duration) -> Self {
+        Self { fork: Mutex::new(value), delay }
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl<T: Send> Fork<T> for SlowFork<T> {
+    fn try_lock(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        thread::sleep(self.delay);
+        Fork::try_lock(&self.fork)
+    }
+
+    fn lock_for(&self, timeout: Duration, interval: Duration, cancel: Option<&AtomicBool>) -> TryLockResult<ForkGuard<'_, T>> {
+        if self.delay > timeout {
+            thread::sleep(timeout);
+            return Err(TryLockError::WouldBlock);
+        }
+        thread::sleep(self.delay);
+        lock_until(timeout - self.delay, interval, cancel, || Fork::try_lock(&self.fork))
+    }
+
+    fn lock_blocking(&self) -> TryLockResult<ForkGuard<'_, T>> {
+        thread::sleep(self.delay);
+        Fork::lock_blocking(&self.fork)
+    }
+
+    fn clear_poison(&self) {
+        self.fork.clear_poison();
+    }
+}
+
+/// Kind of forks placed on the table of a simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ForkBackend {
+    /// A plain [Mutex].
+    #[default]
+    Mutex,
+    /// A [FaultyFork], each fork is seeded with its position on the table.
+    Faulty { failure_probability: f64, downtime: Duration },
+    /// A [NotifyingFork].
+    Notifying,
+    /// A [QueuedMutex].
+    Queued,
+}
+
+impl ForkBackend {
+    /// Create the fork at `index` on the table.
+    pub(crate) fn create<T: Send + 'static>(&self, value: T, index: usize) -> Arc<dyn Fork<T>> {
+        match *self {
+            Self::Mutex => Arc::new(Mutex::new(value)),
+            Self::Faulty { failure_probability, downtime } => {
+                Arc::new(FaultyFork::new(value, failure_probability, downtime, index as u64))
+            }
+            Self::Notifying => Arc::new(NotifyingFork::new(value)),
+            Self::Queued => Arc::new(QueuedMutex::new(value)),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, TryLockError};
+
+use crate::lock::counted_try_lock;
+use crate::{Fork, ForkGuard};
+
+/// Forks that are all alike, so a philosopher eats with any two of them instead of the ones at
+/// its sides ([See][crate::Strategy::SharedPool]). Two forks are checked out at once or none, so
+/// nobody holds a fork while waiting for another one and the pool never deadlocks.
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use actividad_12::{Fork, ForkPool};
+///
+/// let forks: Vec<Arc<dyn Fork<()>>> = (0..3).map(|_| Arc::new(Mutex::new(())) as _).collect();
+/// let pool = ForkPool::new(forks);
+/// let forks = pool.checkout().unwrap();
+/// let [(_, first), (_, second)] = &forks;
+/// assert_ne!(first, second);
+/// // Only one fork is left, which is not enough to eat
+/// assert!(pool.checkout().is_none());
+/// ```
+/// Many threads checking out forks at once never have more of them than the pool:
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::{Arc, Mutex};
+/// use std::thread;
+/// use actividad_12::{Fork, ForkPool};
+///
+/// let forks: Vec<Arc<dyn Fork<()>>> = (0..5).map(|_| Arc::new(Mutex::new(())) as _).collect();
+/// let pool = ForkPool::new(forks);
+/// let (out, most_out) = (AtomicUsize::new(0), AtomicUsize::new(0));
+/// thread::scope(|scope| {
+///     for _ in 0..8 {
+///         scope.spawn(|| {
+///             for _ in 0..1000 {
+///                 if let Some(forks) = pool.checkout() {
+///                     let now_out = out.fetch_add(2, Ordering::SeqCst) + 2;
+///                     most_out.fetch_max(now_out, Ordering::SeqCst);
+///                     thread::yield_now();
+///                     out.fetch_sub(2, Ordering::SeqCst);
+///                     drop(forks);
+///                 }
+///             }
+///         });
+///     }
+/// });
+/// assert!(most_out.into_inner() <= pool.size());
+/// ```
+pub struct ForkPool<T> {
+    forks: Vec<Arc<dyn Fork<T>>>,
+    /// Where the next checkout begins looking, so the forks are used evenly.
+    next: AtomicUsize,
+}
+
+impl<T> ForkPool<T> {
+    /// A pool of `forks`, their ids are their positions.
+    pub fn new(forks: Vec<Arc<dyn Fork<T>>>) -> Self {
+        Self {
+            forks,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of forks in the pool, never more of them are checked out at once.
+    pub fn size(&self) -> usize {
+        self.forks.len()
+    }
+
+    /// Take two free forks, with their ids, or none of them when fewer than two are free. They
+    /// return to the pool when their guards are dropped. A poisoned fork is taken like a free
+    /// one, the forks do not guard anything that a panic could leave half updated.
+    pub fn checkout(&self) -> Option<[(ForkGuard<'_, T>, usize); 2]> {
+        let n = self.forks.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut taken = Vec::with_capacity(2);
+        for id in (0..n).map(|offset| (start + offset) % n) {
+            match counted_try_lock(|| self.forks[id].try_lock()) {
+                Ok(guard) => taken.push((guard, id)),
+                Err(TryLockError::Poisoned(poisoned)) => taken.push((poisoned.into_inner(), id)),
+                Err(TryLockError::WouldBlock) => continue,
+            }
+            if taken.len() == 2 {
+                break;
+            }
+        }
+        // A single fork goes back at once
+        taken.try_into().ok()
+    }
+}
@@ -0,0 +1,268 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::fork::{CountedFork, HeldForks};
+use crate::lock::retry_sleep_time;
+use crate::philosopher::pause;
+use crate::{Event, EventKind, FailureCounts, Fork, ForkGuard, ForkStats, PhilosopherStats, RetryHistogram, SampleStats, SeededSource, SimulationConfig, SimulationError, SimulationResult, TimeDistribution, TimeoutLock};
+
+/// Which philosophers share a fork, generalizing the round table to any topology. There is a fork
+/// on each edge and a philosopher needs every fork on its edges to eat.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{ConflictGraph, SimulationConfig};
+///
+/// // Philosopher 0 shares a fork with each of the other three, which share none between them
+/// let star = ConflictGraph::new(4, &[(0, 1), (0, 2), (0, 3)]).unwrap();
+/// assert_eq!(star.forks(), 3);
+/// assert_eq!(star.forks_of(0), [0, 1, 2]);
+/// assert_eq!(star.forks_of(2), [1]);
+///
+/// let config = SimulationConfig::builder()
+///     .meals(3)
+///     .think_time(Duration::from_millis(1))
+///     .eat_time(Duration::from_millis(1))
+///     .build()
+///     .unwrap();
+/// let result = star.run(&config).unwrap();
+/// assert!(result.is_complete());
+/// assert_eq!(result.philosophers.len(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictGraph {
+    philosophers: usize,
+    /// The philosophers at both ends of each fork, the position of an edge is the id of its fork.
+    edges: Vec<(usize, usize)>,
+}
+
+impl ConflictGraph {
+    /// A graph of `philosophers` philosophers with a fork on each of `edges`. Two philosophers may
+    /// share more than one fork.
+    pub fn new(philosophers: usize, edges: &[(usize, usize)]) -> Result<Self, GraphError> {
+        for &(a, b) in edges {
+            if let Some(philosopher) = [a, b].into_iter().find(|&philosopher| philosopher >= philosophers) {
+                return Err(GraphError::PhilosopherOutOfRange(philosopher));
+            }
+            if a == b {
+                return Err(GraphError::SelfLoop(a));
+            }
+        }
+        Ok(Self {
+            philosophers,
+            edges: edges.to_vec(),
+        })
+    }
+
+    /// The round table of the classic problem, fork `i` is between philosopher `i` and `i + 1`.
+    pub fn ring(philosophers: usize) -> Self {
+        Self {
+            philosophers,
+            edges: (0..philosophers).map(|i| (i, (i + 1) % philosophers)).collect(),
+        }
+    }
+
+    pub fn philosophers(&self) -> usize {
+        self.philosophers
+    }
+
+    pub fn forks(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Ids of the forks the philosopher at `index` needs to eat, from the lowest to the highest.
+    pub fn forks_of(&self, index: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .enumerate()
+            .filter(|(_, &(a, b))| a == index || b == index)
+            .map(|(fork, _)| fork)
+            .collect()
+    }
+
+    /// Let each philosopher of the graph eat `config.meals()` meals in its own thread, using the
+    /// fork timeout, also by meals left, retry interval, think/eat times, seed, fork backend and
+    /// observer of `config`. Its number of philosophers, strategy and the rest of the options are
+    /// ignored.
+    ///
+    /// The forks are always taken from the lowest id to the highest, which can not deadlock on any
+    /// graph. If a fork can not be acquired within the timeout the ones held are released. With
+    /// the `no-timeouts` feature each fork is waited for as long as it takes instead
+    /// ([See][Fork::lock_blocking]).
+    pub fn run(&self, config: &SimulationConfig) -> Result<SimulationResult, SimulationError> {
+        let held_forks = Arc::new(HeldForks::default());
+        let forks: Vec<Arc<dyn Fork<()>>> = (0..self.forks()).map(|i| Arc::new(CountedFork::new(config.fork_backend().create((), i), i, held_forks.clone())) as _).collect();
+        let start = Instant::now();
+        let mut handles = Vec::with_capacity(self.philosophers);
+        for i in 0..self.philosophers {
+            let (think_time, eat_time) = config.times_of(i);
+            let mut philosopher = GraphPhilosopher {
+                name: format!("Filósofo {}", i + 1),
+                forks: self.forks_of(i).into_iter().map(|fork| (fork, forks[fork].clone())).collect(),
+                meals: 0,
+                fork_waits: Vec::new(),
+                fork_acquisitions: vec![0; self.forks_of(i).len()],
+                retry_sleep: Duration::ZERO,
+                failures: FailureCounts::default(),
+                random: SeededSource::new(config.seed().wrapping_add(i as u64)),
+            };
+            let config = config.clone();
+            let spawned = thread::Builder::new().name(philosopher.name.clone()).spawn(move || {
+                while philosopher.meals < config.meals() {
+                    philosopher.dine(&config, think_time, eat_time);
+                }
+                philosopher
+            });
+            // The philosophers already sitting finish their meals before the error is returned
+            match spawned {
+                Ok(handle) => handles.push(handle),
+                Err(error) => {
+                    for handle in handles {
+                        let _ = handle.join();
+                    }
+                    return Err(SimulationError::SpawnFailed(error));
+                }
+            }
+        }
+
+        let mut fork_waits = Vec::new();
+        let mut fork_acquisitions = vec![0; self.forks()];
+        let mut retry_sleeps = Vec::with_capacity(self.philosophers);
+        let mut failures = Vec::with_capacity(self.philosophers);
+        let philosophers = handles
+            .into_iter()
+            .enumerate()
+            .map(|(i, handle)| match handle.join() {
+                Ok(philosopher) => {
+                    fork_waits.extend_from_slice(&philosopher.fork_waits);
+                    retry_sleeps.push(philosopher.retry_sleep);
+                    failures.push(philosopher.failures);
+                    for (&(fork, _), acquisitions) in philosopher.forks.iter().zip(&philosopher.fork_acquisitions) {
+                        fork_acquisitions[fork] += acquisitions;
+                    }
+                    PhilosopherStats { name: philosopher.name, meals: philosopher.meals, panicked: false, satiated: false }
+                }
+                Err(_) => {
+                    retry_sleeps.push(Duration::ZERO);
+                    failures.push(FailureCounts::default());
+                    let name = format!("Filósofo {}", i + 1);
+                    config.observer().on_event(&Event::new(&name, EventKind::Panicked));
+                    PhilosopherStats { name, meals: 0, panicked: true, satiated: false }
+                }
+            })
+            .collect();
+        fork_waits.sort_unstable();
+        let elapsed = start.elapsed();
+        let result = SimulationResult {
+            philosophers,
+            meals_target: config.meals(),
+            elapsed,
+            fork_waits,
+            single_fork_holds: Vec::new(),
+            fork_acquisitions,
+            retry_sleeps,
+            failures,
+            polls: Vec::new(),
+            first_meals: Vec::new(),
+            samples: Vec::new(),
+            sample_stats: SampleStats::default(),
+            hand_offs: Vec::new(),
+            peak_forks_held: held_forks.peak(),
+            timelines: Vec::new(),
+            cpu_time: None,
+            fork_stats: held_forks.held_times(self.forks()).into_iter().map(|held| ForkStats::held_for(held, elapsed)).collect(),
+            second_fork_retries: RetryHistogram::default(),
+            round_progress: Vec::new(),
+            tastes: Vec::new(),
+        };
+        config.observer().on_finish(&result);
+        Ok(result)
+    }
+}
+
+struct GraphPhilosopher {
+    name: String,
+    /// Ids of the forks the philosopher needs, with the forks.
+    forks: Vec<(usize, Arc<dyn Fork<()>>)>,
+    meals: u32,
+    fork_waits: Vec<Duration>,
+    /// Times each of the forks was acquired.
+    fork_acquisitions: Vec<u64>,
+    /// Time slept between the retries to take the forks.
+    retry_sleep: Duration,
+    /// Failures on the first fork count as [FailureCounts::first_fork], the ones on any later
+    /// fork as [FailureCounts::second_fork].
+    failures: FailureCounts,
+    random: SeededSource,
+}
+
+impl GraphPhilosopher {
+    fn dine(&mut self, config: &SimulationConfig, think_time: TimeDistribution, eat_time: TimeDistribution) {
+        self.notify(config, EventKind::Thinking);
+        pause(think_time.sample(&mut self.random));
+
+        let forks = self.forks.clone();
+        let mut guards: Vec<ForkGuard<'_, ()>> = Vec::with_capacity(forks.len());
+        for (position, (_, fork)) in forks.iter().enumerate() {
+            let start = Instant::now();
+            let slept = retry_sleep_time();
+            let locked = if cfg!(feature = "no-timeouts") {
+                fork.lock_blocking()
+            } else {
+                fork.try_lock_for(config.fork_timeout_for(config.meals().saturating_sub(self.meals)), config.fork_interval())
+            };
+            self.retry_sleep += retry_sleep_time().saturating_sub(slept);
+            match locked {
+                Ok(guard) => {
+                    let waited = start.elapsed();
+                    self.fork_waits.push(waited);
+                    self.fork_acquisitions[position] += 1;
+                    config.observer().on_fork_acquired(&self.name, waited);
+                    guards.push(guard);
+                }
+                Err(error) => {
+                    match error {
+                        TryLockError::Poisoned(_) => self.failures.poisoned += 1,
+                        TryLockError::WouldBlock if position == 0 => self.failures.first_fork += 1,
+                        TryLockError::WouldBlock => self.failures.second_fork += 1,
+                    }
+                    self.notify(config, EventKind::Failed);
+                    return;
+                }
+            }
+        }
+
+        self.notify(config, EventKind::Eating);
+        pause(eat_time.sample(&mut self.random));
+        drop(guards);
+        self.meals += 1;
+        self.notify(config, EventKind::Ate { meals: self.meals });
+        self.notify(config, EventKind::DoneEating);
+    }
+
+    fn notify(&self, config: &SimulationConfig, kind: EventKind) {
+        config.observer().on_event(&Event::new(&self.name, kind));
+    }
+}
+
+/// Reason why a [ConflictGraph] could not be created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    /// An edge has an end past the last philosopher.
+    PhilosopherOutOfRange(usize),
+    /// An edge goes from a philosopher to itself.
+    SelfLoop(usize),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PhilosopherOutOfRange(index) => write!(f, "there is no philosopher at position {index}"),
+            Self::SelfLoop(index) => write!(f, "philosopher {index} can not share a fork with itself"),
+        }
+    }
+}
+
+impl Error for GraphError {}
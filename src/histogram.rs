@@ -0,0 +1,223 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Upper bounds in milliseconds of the buckets of a [WaitHistogram], the last bucket has no upper
+/// bound.
+const BUCKET_BOUNDS_MS: [u64; 8] = [1, 2, 5, 10, 20, 50, 100, 200];
+
+/// Counts of fork waits grouped in buckets of increasing width.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::WaitHistogram;
+///
+/// let mut histogram = WaitHistogram::default();
+/// histogram.record(Duration::from_micros(300));
+/// histogram.record(Duration::from_millis(15));
+/// histogram.record(Duration::from_millis(18));
+/// histogram.record(Duration::from_secs(1));
+/// assert_eq!(histogram.counts(), &[1, 0, 0, 0, 2, 0, 0, 0, 1]);
+/// assert_eq!(histogram.total(), 4);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaitHistogram {
+    counts: [u64; BUCKET_BOUNDS_MS.len() + 1],
+}
+
+impl WaitHistogram {
+    pub fn record(&mut self, wait: Duration) {
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|&bound| wait < Duration::from_millis(bound))
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.counts[bucket] += 1;
+    }
+
+    /// Add the waits of `other` to their buckets.
+    pub fn merge(&mut self, other: &WaitHistogram) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts) {
+            *count += other;
+        }
+    }
+
+    /// Number of waits in each bucket, from the shortest waits to the longest.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// One line per bucket with a bar of `#` scaled so the fullest bucket is `width` characters.
+    pub fn render(&self, width: usize) -> String {
+        let max = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        let mut output = String::new();
+        let mut lower = 0;
+        for (i, &count) in self.counts.iter().enumerate() {
+            let label = match BUCKET_BOUNDS_MS.get(i) {
+                Some(upper) => format!("{lower:>3}-{upper:<3}ms"),
+                None => format!("{lower:>3}+   ms"),
+            };
+            let bar = "#".repeat((count as usize * width).div_ceil(max as usize));
+            let _ = writeln!(output, "{label} |{bar:<width$}| {count}");
+            lower = BUCKET_BOUNDS_MS.get(i).copied().unwrap_or(lower);
+        }
+        output
+    }
+}
+
+/// Acquisitions of a fork counted by the number of retries each one needed, the position is the
+/// number of retries.
+/// ```rust
+/// use actividad_12::RetryHistogram;
+///
+/// let mut histogram = RetryHistogram::default();
+/// for retries in [0, 0, 0, 2, 5] {
+///     histogram.record(retries);
+/// }
+/// assert_eq!(histogram.counts(), &[3, 0, 1, 0, 0, 1]);
+/// assert_eq!(histogram.total(), 5);
+/// assert_eq!(histogram.share_without_retries(), 0.6);
+/// assert_eq!(histogram.mean(), 1.4);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RetryHistogram {
+    counts: Vec<u64>,
+}
+
+impl RetryHistogram {
+    pub fn record(&mut self, retries: u64) {
+        let retries = retries as usize;
+        if self.counts.len() <= retries {
+            self.counts.resize(retries + 1, 0);
+        }
+        self.counts[retries] += 1;
+    }
+
+    /// Add the acquisitions of `other` to their counts.
+    pub fn merge(&mut self, other: &RetryHistogram) {
+        if self.counts.len() < other.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+    }
+
+    /// Number of acquisitions that needed each number of retries, from none up to the most.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Fraction of the acquisitions that found the fork free at once, `0.0` without acquisitions.
+    pub fn share_without_retries(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.counts[0] as f64 / total as f64,
+        }
+    }
+
+    /// Mean retries per acquisition, `0.0` without acquisitions.
+    pub fn mean(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.counts.iter().enumerate().map(|(retries, &count)| retries as u64 * count).sum::<u64>() as f64 / total as f64,
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+pub use tui::HistogramObserver;
+
+#[cfg(feature = "tui")]
+mod tui {
+    use std::io::{self, IsTerminal, Write};
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use super::WaitHistogram;
+    use crate::{Event, Observer, SimulationResult};
+
+    const REFRESH: Duration = Duration::from_secs(1);
+    const WIDTH: usize = 40;
+
+    /// Draw a histogram of the fork waits in the terminal while the simulation runs, redrawing it
+    /// in place every second. It does nothing when stdout is not a terminal.
+    pub struct HistogramObserver {
+        enabled: bool,
+        state: Mutex<State>,
+    }
+
+    struct State {
+        histogram: WaitHistogram,
+        last_draw: Option<Instant>,
+        /// Lines written by the last draw, they are overwritten by the next one.
+        drawn_lines: usize,
+    }
+
+    impl HistogramObserver {
+        pub fn new() -> Self {
+            Self {
+                enabled: io::stdout().is_terminal(),
+                state: Mutex::new(State {
+                    histogram: WaitHistogram::default(),
+                    last_draw: None,
+                    drawn_lines: 0,
+                }),
+            }
+        }
+
+        /// The waits recorded so far.
+        pub fn histogram(&self) -> WaitHistogram {
+            self.state.lock().unwrap().histogram.clone()
+        }
+
+        fn draw(state: &mut State) {
+            let output = state.histogram.render(WIDTH);
+            let mut stdout = io::stdout().lock();
+            if state.drawn_lines > 0 {
+                // Move the cursor back to the first line of the previous draw
+                let _ = write!(stdout, "\x1b[{}A", state.drawn_lines);
+            }
+            for line in output.lines() {
+                let _ = writeln!(stdout, "\x1b[2K{line}");
+            }
+            let _ = stdout.flush();
+            state.drawn_lines = output.lines().count();
+            state.last_draw = Some(Instant::now());
+        }
+    }
+
+    impl Default for HistogramObserver {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Observer for HistogramObserver {
+        fn on_event(&self, _event: &Event) {}
+
+        fn on_fork_acquired(&self, _philosopher: &str, waited: Duration) {
+            if !self.enabled {
+                return;
+            }
+            let mut state = self.state.lock().unwrap();
+            state.histogram.record(waited);
+            if state.last_draw.is_none_or(|last_draw| last_draw.elapsed() >= REFRESH) {
+                Self::draw(&mut state);
+            }
+        }
+
+        fn on_finish(&self, _result: &SimulationResult) {
+            if self.enabled {
+                Self::draw(&mut self.state.lock().unwrap());
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Where hungry philosophers announce which two forks they want before taking them, so only one
+/// of the philosophers that want the same fork goes for it ([See][crate::Strategy::Reservation]).
+/// A philosopher gets to reserve its forks when none of them is reserved and nobody that wants
+/// one of them has a higher priority or, with the same priority, announced it earlier. Reserved
+/// forks are never wanted by anyone else holding a reservation, so the philosophers with one do
+/// not wait for each other and can not deadlock.
+/// ```rust
+/// use actividad_12::IntentBoard;
+///
+/// let board = IntentBoard::new();
+/// board.announce("Filósofo 1", [0, 1], 0);
+/// board.announce("Filósofo 2", [1, 2], 5);
+/// board.announce("Filósofo 3", [2, 3], 0);
+/// // Filósofo 2 wants forks 1 and 2 too, with a higher priority
+/// assert!(!board.try_reserve("Filósofo 1"));
+/// assert!(!board.try_reserve("Filósofo 3"));
+/// assert!(board.try_reserve("Filósofo 2"));
+/// // Once Filósofo 2 ate its neighbors go, neither wants the fork of the other one
+/// board.withdraw("Filósofo 2");
+/// assert!(board.try_reserve("Filósofo 1"));
+/// assert!(board.try_reserve("Filósofo 3"));
+/// // Someone announcing later waits for the reservations, whatever its priority
+/// board.announce("Filósofo 4", [3, 4], 9);
+/// assert!(!board.try_reserve("Filósofo 4"));
+/// ```
+#[derive(Default)]
+pub struct IntentBoard {
+    state: Mutex<BoardState>,
+}
+
+#[derive(Default)]
+struct BoardState {
+    intents: Vec<Intent>,
+    /// Order of the next announcement.
+    next: u64,
+}
+
+struct Intent {
+    name: String,
+    forks: [usize; 2],
+    priority: u32,
+    order: u64,
+    reserved: bool,
+}
+
+impl Intent {
+    fn overlaps(&self, other: &Intent) -> bool {
+        self.forks.iter().any(|fork| other.forks.contains(fork))
+    }
+
+    /// Whether the intent goes before `other` when both want the same fork.
+    fn precedes(&self, other: &Intent) -> bool {
+        (self.priority, other.order) > (other.priority, self.order)
+    }
+}
+
+impl IntentBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Announce that the philosopher `name` wants `forks`, with `priority`, the higher the more
+    /// important. An earlier intent of the same philosopher is replaced.
+    pub fn announce(&self, name: &str, forks: [usize; 2], priority: u32) {
+        let mut state = self.state();
+        state.intents.retain(|intent| intent.name != name);
+        let order = state.next;
+        state.next += 1;
+        state.intents.push(Intent { name: name.to_string(), forks, priority, order, reserved: false });
+    }
+
+    /// Reserve the forks announced by `name` if it can go for them now, or tell whether it
+    /// already did. It is `false` for someone that did not announce anything.
+    pub fn try_reserve(&self, name: &str) -> bool {
+        let mut state = self.state();
+        let Some(own) = state.intents.iter().position(|intent| intent.name == name) else {
+            return false;
+        };
+        let intent = &state.intents[own];
+        if intent.reserved {
+            return true;
+        }
+        let blocked = state
+            .intents
+            .iter()
+            .filter(|other| other.name != name && other.overlaps(intent))
+            .any(|other| other.reserved || other.precedes(intent));
+        if blocked {
+            return false;
+        }
+        state.intents[own].reserved = true;
+        true
+    }
+
+    /// Forget the intent of `name` and its reservation, once it ate or gave up.
+    pub fn withdraw(&self, name: &str) {
+        self.state().intents.retain(|intent| intent.name != name);
+    }
+
+    fn state(&self) -> MutexGuard<'_, BoardState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// An intent of a philosopher on an [IntentBoard], withdrawn when dropped.
+pub(crate) struct Announcement {
+    board: Arc<IntentBoard>,
+    name: String,
+}
+
+impl Announcement {
+    pub(crate) fn new(board: Arc<IntentBoard>, name: &str, forks: [usize; 2], priority: u32) -> Self {
+        board.announce(name, forks, priority);
+        Self { board, name: name.to_string() }
+    }
+
+    pub(crate) fn try_reserve(&self) -> bool {
+        self.board.try_reserve(&self.name)
+    }
+}
+
+impl Drop for Announcement {
+    fn drop(&mut self) {
+        self.board.withdraw(&self.name);
+    }
+}
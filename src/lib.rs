@@ -0,0 +1,79 @@
+//! Simulation of the dining philosophers problem where the forks are taken with a timeout, so a
+//! philosopher that can not get both forks releases them and tries again later.
+
+mod channel;
+mod comparison;
+mod config;
+mod contention;
+#[cfg(feature = "cpu-time")]
+mod cpu_time;
+mod deadlock;
+mod distribution;
+#[cfg(feature = "verify-exclusion")]
+mod exclusion;
+mod fork;
+mod fork_pool;
+mod graph;
+mod histogram;
+mod intent;
+mod livelock;
+mod lock;
+mod mailbox;
+mod observer;
+mod payload;
+mod philosopher;
+mod pool;
+#[cfg(feature = "thread-priority")]
+mod priority;
+mod random;
+mod reproducible;
+mod result;
+#[cfg(feature = "serde")]
+mod serde_millis;
+mod simulation;
+mod snapshot;
+mod stats;
+mod stepper;
+mod strategy;
+mod stress;
+mod table;
+mod trace;
+
+pub use channel::{ChannelObserver, EventChannelPolicy, EventReceiver};
+pub use comparison::{compare_acquisition_styles, ComparisonReport, StyleReport};
+pub use config::{ConfigError, JoinErrorPolicy, Oversubscription, SimulationConfig, SimulationConfigBuilder, StopCondition};
+#[cfg(feature = "cpu-time")]
+pub use cpu_time::thread_cpu_time;
+pub use deadlock::{estimate_deadlock_probability, DeadlockDetector, WaitForGraph, WaitForSnapshot};
+pub use distribution::TimeDistribution;
+#[cfg(feature = "verify-exclusion")]
+pub use exclusion::{ExclusionMonitor, ExclusionViolation};
+pub use fork::{FaultyFork, Fork, ForkBackend, ForkGuard, HandOffFork, NotifyingFork, QueuedMutex};
+#[cfg(feature = "test-util")]
+pub use fork::SlowFork;
+pub use fork_pool::ForkPool;
+pub use graph::{ConflictGraph, GraphError};
+pub use histogram::{RetryHistogram, WaitHistogram};
+#[cfg(feature = "tui")]
+pub use histogram::HistogramObserver;
+pub use intent::IntentBoard;
+pub use livelock::LivelockDetector;
+pub use lock::{Acquisition, TimeoutLock, WAIT_FOREVER};
+pub use mailbox::{Mailbox, Message};
+pub use observer::{ConsoleObserver, Event, EventKind, Observer};
+pub use payload::{ForkPayload, UsageCounter};
+pub use philosopher::{Philosopher, PhilosopherError};
+pub use pool::run_on_pool;
+#[cfg(feature = "thread-priority")]
+pub use priority::{set_thread_niceness, thread_niceness};
+pub use random::{Draw, SeededSource};
+pub use reproducible::run_reproducible;
+pub use result::{FailureCounts, ForkStats, Phase, PhaseSpan, PhilosopherStats, PollCounts, Sample, SampleKind, SimulationResult, WaitPercentiles};
+pub use simulation::{run_simulation, Simulation, SimulationError};
+pub use snapshot::{Checkpoint, LiveMetrics, SnapshotStream, Snapshots, TableSnapshot};
+pub use stats::{RunningStats, SampleStats};
+pub use stepper::{step_through, Step, Stepper, Transition};
+pub use strategy::{AcquisitionOrder, AcquisitionStyle, HandednessPattern, Strategy};
+pub use stress::stress_test;
+pub use table::Table;
+pub use trace::TraceExporter;
@@ -0,0 +1,119 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Event, EventKind, Observer, SimulationResult};
+
+/// Flag livelocks, when the philosophers keep taking and releasing forks without eating. The
+/// events are counted in windows of fixed length and a window with more fork acquisitions per
+/// meal than the threshold is a detection. Every event is forwarded to another observer.
+/// ```rust
+/// use std::thread;
+/// use std::time::Duration;
+/// use actividad_12::{Event, EventKind, LivelockDetector, Observer};
+///
+/// let detector = LivelockDetector::new(Duration::from_millis(10), 4.0);
+/// // Forks are taken and released over and over but nobody eats
+/// for _ in 0..20 {
+///     detector.on_fork_acquired("Filósofo 1", Duration::ZERO);
+/// }
+/// thread::sleep(Duration::from_millis(10));
+/// detector.on_fork_acquired("Filósofo 1", Duration::ZERO);
+/// assert_eq!(detector.detections(), 1);
+///
+/// // Two forks per meal is no livelock
+/// for meals in 1..=10 {
+///     detector.on_fork_acquired("Filósofo 2", Duration::ZERO);
+///     detector.on_fork_acquired("Filósofo 2", Duration::ZERO);
+///     detector.on_event(&Event::new("Filósofo 2", EventKind::Ate { meals }));
+/// }
+/// thread::sleep(Duration::from_millis(10));
+/// detector.on_fork_acquired("Filósofo 1", Duration::ZERO);
+/// assert_eq!(detector.detections(), 1);
+/// ```
+pub struct LivelockDetector {
+    window: Duration,
+    max_acquisitions_per_meal: f64,
+    inner: Arc<dyn Observer>,
+    state: Mutex<WindowState>,
+}
+
+struct WindowState {
+    start: Instant,
+    acquisitions: u64,
+    meals: u64,
+    detections: usize,
+}
+
+impl LivelockDetector {
+    /// Detect the windows of length `window` where the forks were acquired more than
+    /// `max_acquisitions_per_meal` times per meal. Eating takes two forks so the threshold
+    /// should be above `2.0`.
+    pub fn new(window: Duration, max_acquisitions_per_meal: f64) -> Self {
+        Self {
+            window,
+            max_acquisitions_per_meal,
+            inner: Arc::new(()),
+            state: Mutex::new(WindowState {
+                start: Instant::now(),
+                acquisitions: 0,
+                meals: 0,
+                detections: 0,
+            }),
+        }
+    }
+
+    /// Forward every event to `observer`.
+    pub fn forward_to(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.inner = observer;
+        self
+    }
+
+    /// Number of windows where a livelock was detected so far.
+    pub fn detections(&self) -> usize {
+        self.state.lock().unwrap().detections
+    }
+
+    pub fn detected(&self) -> bool {
+        self.detections() > 0
+    }
+
+    /// Count fork acquisitions and meals, closing the window first if it ended.
+    fn count(&self, acquisitions: u64, meals: u64) {
+        let mut state = self.state.lock().unwrap();
+        if state.start.elapsed() >= self.window {
+            let livelocked = match state.meals {
+                0 => state.acquisitions as f64 > self.max_acquisitions_per_meal,
+                meals => state.acquisitions as f64 / meals as f64 > self.max_acquisitions_per_meal,
+            };
+            if livelocked {
+                state.detections += 1;
+            }
+            state.start = Instant::now();
+            state.acquisitions = 0;
+            state.meals = 0;
+        }
+        state.acquisitions += acquisitions;
+        state.meals += meals;
+    }
+}
+
+impl Observer for LivelockDetector {
+    fn on_event(&self, event: &Event) {
+        let meals = u64::from(matches!(event.kind, EventKind::Ate { .. }));
+        self.count(0, meals);
+        self.inner.on_event(event);
+    }
+
+    fn on_fork_acquired(&self, philosopher: &str, waited: Duration) {
+        self.count(1, 0);
+        self.inner.on_fork_acquired(philosopher, waited);
+    }
+
+    fn on_starving(&self, philosopher: &str, hungry_for: Duration) {
+        self.inner.on_starving(philosopher, hungry_for);
+    }
+
+    fn on_finish(&self, result: &SimulationResult) {
+        self.inner.on_finish(result);
+    }
+}
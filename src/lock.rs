@@ -0,0 +1,355 @@
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError, TryLockResult, TryLockError};
+
+use crate::config::ContentionFn;
+use crate::{Fork, ForkGuard, PollCounts};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub trait TimeoutLock<T> {
+    type Guard<'a>
+    where
+        Self: 'a;
+
+    /// Try to acquire the lock for the mutex until a timeout, retrying every `interval`. A
+    /// timeout of [WAIT_FOREVER] or longer, like [Duration::MAX], never runs out, so the lock is
+    /// tried once and then blocked on until it is released instead of being retried.
+    /// ```rust
+    /// use std::sync::{Mutex, TryLockError};
+    /// use std::time::Duration;
+    /// use actividad_12::TimeoutLock;
+    ///
+    /// let lock = Mutex::new(String::from("Hello world"));
+    /// match lock.try_lock_for(Duration::from_millis(10), Duration::from_millis(1)) {
+    ///     Ok(guard) => { println!("The value behind the mutex is: {}", *guard) }
+    ///     Err(TryLockError::Poisoned(_)) => { eprintln!("The value behind the mutex is poisoned") },
+    ///     Err(TryLockError::WouldBlock) => { eprintln!("The lock of the mutex could not be acquired") }
+    /// };
+    ///
+    /// // While the mutex is held the call gives up after the timeout
+    /// let _guard = lock.lock().unwrap();
+    /// let result = lock.try_lock_for(Duration::from_millis(10), Duration::from_millis(1));
+    /// assert!(matches!(result, Err(TryLockError::WouldBlock)));
+    /// ```
+    /// Waiting forever does not retry every `interval`, the lock is taken as soon as it is
+    /// released:
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::TimeoutLock;
+    ///
+    /// let lock = Arc::new(Mutex::new(0));
+    /// let guard = lock.lock().unwrap();
+    /// let waiter = {
+    ///     let lock = lock.clone();
+    ///     thread::spawn(move || {
+    ///         let start = Instant::now();
+    ///         let mut guard = lock.try_lock_for(Duration::MAX, Duration::from_secs(60)).unwrap();
+    ///         *guard += 1;
+    ///         start.elapsed()
+    ///     })
+    /// };
+    /// thread::sleep(Duration::from_millis(20));
+    /// drop(guard);
+    /// // Retrying every interval it would have slept a whole minute
+    /// assert!(waiter.join().unwrap() < Duration::from_secs(30));
+    /// assert_eq!(*lock.lock().unwrap(), 1);
+    /// ```
+    fn try_lock_for(&self, timeout: Duration, interval: Duration) -> TryLockResult<Self::Guard<'_>>;
+
+    /// Same as [TimeoutLock::try_lock_for] but give up with [TryLockError::WouldBlock] as soon as
+    /// `cancel` is set, without waiting for the rest of the timeout. The lock is retried every
+    /// `interval` even when waiting forever, to notice when `cancel` is set.
+    /// ```rust
+    /// use std::sync::atomic::AtomicBool;
+    /// use std::sync::{Mutex, TryLockError};
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::TimeoutLock;
+    ///
+    /// let lock = Mutex::new(());
+    /// let _guard = lock.lock().unwrap();
+    /// let cancel = AtomicBool::new(true);
+    /// let start = Instant::now();
+    /// let result = lock.try_lock_for_cancellable(Duration::from_secs(10), Duration::from_millis(1), &cancel);
+    /// assert!(matches!(result, Err(TryLockError::WouldBlock)));
+    /// assert!(start.elapsed() < Duration::from_secs(1));
+    /// ```
+    fn try_lock_for_cancellable(&self, timeout: Duration, interval: Duration, cancel: &AtomicBool) -> TryLockResult<Self::Guard<'_>>;
+
+    /// Run `on_lock` with the value behind the lock if it is acquired within `timeout`
+    /// ([See][TimeoutLock::try_lock_for]), or `on_timeout` otherwise. A poisoned lock counts as
+    /// not acquired, its guard is dropped before `on_timeout` runs so the lock is never held by it.
+    /// ```rust
+    /// use std::sync::Mutex;
+    /// use std::time::Duration;
+    /// use actividad_12::TimeoutLock;
+    ///
+    /// let lock = Mutex::new(1);
+    /// let timeout = Duration::from_millis(10);
+    /// let interval = Duration::from_millis(1);
+    /// let doubled = lock.lock_or_timeout_with(timeout, interval, |value| { *value *= 2; *value }, || 0);
+    /// assert_eq!(doubled, 2);
+    ///
+    /// let guard = lock.lock().unwrap();
+    /// let doubled = lock.lock_or_timeout_with(timeout, interval, |value| { *value *= 2; *value }, || 0);
+    /// assert_eq!(doubled, 0);
+    /// drop(guard);
+    /// assert_eq!(*lock.lock().unwrap(), 2);
+    /// ```
+    fn lock_or_timeout_with<R>(&self, timeout: Duration, interval: Duration, on_lock: impl FnOnce(&mut T) -> R, on_timeout: impl FnOnce() -> R) -> R;
+
+    /// Same as [TimeoutLock::try_lock_for] but also tell whether the lock was free at once or had
+    /// to be waited for, to measure how often locks are taken without waiting. The lock is tried
+    /// once first, it is [Acquisition::Immediate] when that try takes it and
+    /// [Acquisition::Waited] when it is taken by [TimeoutLock::try_lock_for] afterwards.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{Acquisition, TimeoutLock};
+    ///
+    /// let lock = Arc::new(Mutex::new(()));
+    /// let (guard, acquisition) = lock.try_lock_for_timed(Duration::from_millis(10), Duration::from_millis(1)).unwrap();
+    /// assert_eq!(acquisition, Acquisition::Immediate);
+    /// drop(guard);
+    ///
+    /// let guard = lock.lock().unwrap();
+    /// let waiter = {
+    ///     let lock = lock.clone();
+    ///     thread::spawn(move || lock.try_lock_for_timed(Duration::from_secs(5), Duration::from_millis(1)).map(|(_, acquisition)| acquisition).unwrap())
+    /// };
+    /// thread::sleep(Duration::from_millis(20));
+    /// drop(guard);
+    /// match waiter.join().unwrap() {
+    ///     Acquisition::Waited(waited) => assert!(Duration::from_millis(10) <= waited && waited < Duration::from_secs(5), "{waited:?}"),
+    ///     Acquisition::Immediate => panic!("the lock was held"),
+    /// }
+    /// ```
+    /// Forks that wait for their release in their own way, blocking until it or queueing for it,
+    /// are measured the same way:
+    /// ```rust
+    /// # use std::sync::Arc;
+    /// # use std::thread;
+    /// # use std::time::Duration;
+    /// use actividad_12::{Acquisition, Fork, NotifyingFork, QueuedMutex, TimeoutLock};
+    ///
+    /// let forks: [Arc<dyn Fork<()>>; 2] = [Arc::new(NotifyingFork::new(())), Arc::new(QueuedMutex::new(()))];
+    /// for fork in forks {
+    ///     let guard = fork.try_lock().unwrap();
+    ///     let waiter = {
+    ///         let fork = fork.clone();
+    ///         thread::spawn(move || fork.try_lock_for_timed(Duration::from_secs(5), Duration::from_millis(5)).map(|(_, acquisition)| acquisition).unwrap())
+    ///     };
+    ///     thread::sleep(Duration::from_millis(20));
+    ///     drop(guard);
+    ///     match waiter.join().unwrap() {
+    ///         Acquisition::Waited(waited) => assert!(Duration::from_millis(10) <= waited && waited < Duration::from_secs(1), "{waited:?}"),
+    ///         Acquisition::Immediate => panic!("the fork was held"),
+    ///     }
+    ///     let (_guard, acquisition) = fork.try_lock_for_timed(Duration::from_secs(5), Duration::from_millis(5)).unwrap();
+    ///     assert_eq!(acquisition, Acquisition::Immediate);
+    /// }
+    /// ```
+    fn try_lock_for_timed(&self, timeout: Duration, interval: Duration) -> TryLockResult<(Self::Guard<'_>, Acquisition)>;
+}
+
+/// Timeouts at least this long, about a hundred years, are taken as waiting forever: instead of
+/// retrying the lock every interval until they run out, which they never do, the lock is blocked
+/// on ([See][TimeoutLock::try_lock_for]).
+pub const WAIT_FOREVER: Duration = Duration::from_secs(100 * 365 * 24 * 60 * 60);
+
+/// How a lock was taken by [TimeoutLock::try_lock_for_timed].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Acquisition {
+    /// The lock was free on the first try.
+    Immediate,
+    /// The lock was taken after waiting for it this long.
+    Waited(Duration),
+}
+
+impl<T> TimeoutLock<T> for Mutex<T> {
+    type Guard<'a> = MutexGuard<'a, T> where T: 'a;
+
+    #[inline]
+    fn try_lock_for(&self, timeout: Duration, interval: Duration) -> TryLockResult<MutexGuard<'_, T>> {
+        if timeout >= WAIT_FOREVER {
+            return lock_forever(|| self.try_lock(), || self.lock().map_err(TryLockError::from));
+        }
+        lock_until(timeout, interval, None, || self.try_lock())
+    }
+
+    #[inline]
+    fn try_lock_for_cancellable(&self, timeout: Duration, interval: Duration, cancel: &AtomicBool) -> TryLockResult<MutexGuard<'_, T>> {
+        lock_until(timeout, interval, Some(cancel), || self.try_lock())
+    }
+
+    #[inline]
+    fn lock_or_timeout_with<R>(&self, timeout: Duration, interval: Duration, on_lock: impl FnOnce(&mut T) -> R, on_timeout: impl FnOnce() -> R) -> R {
+        run_locked(self.try_lock_for(timeout, interval), |mut guard| on_lock(&mut guard), on_timeout)
+    }
+
+    fn try_lock_for_timed(&self, timeout: Duration, interval: Duration) -> TryLockResult<(MutexGuard<'_, T>, Acquisition)> {
+        timed(|| self.try_lock(), || self.try_lock_for(timeout, interval))
+    }
+}
+
+impl<T> TimeoutLock<T> for dyn Fork<T> + '_ {
+    type Guard<'a> = ForkGuard<'a, T> where Self: 'a;
+
+    #[inline]
+    fn try_lock_for(&self, timeout: Duration, interval: Duration) -> TryLockResult<ForkGuard<'_, T>> {
+        self.lock_for(timeout, interval, None)
+    }
+
+    #[inline]
+    fn try_lock_for_cancellable(&self, timeout: Duration, interval: Duration, cancel: &AtomicBool) -> TryLockResult<ForkGuard<'_, T>> {
+        self.lock_for(timeout, interval, Some(cancel))
+    }
+
+    #[inline]
+    fn lock_or_timeout_with<R>(&self, timeout: Duration, interval: Duration, on_lock: impl FnOnce(&mut T) -> R, on_timeout: impl FnOnce() -> R) -> R {
+        run_locked(self.try_lock_for(timeout, interval), |mut guard| on_lock(&mut guard), on_timeout)
+    }
+
+    fn try_lock_for_timed(&self, timeout: Duration, interval: Duration) -> TryLockResult<(ForkGuard<'_, T>, Acquisition)> {
+        timed(|| self.try_lock(), || self.try_lock_for(timeout, interval))
+    }
+}
+
+/// Take a lock with `try_lock` at once or else with `wait`, telling which of them took it
+/// ([See][TimeoutLock::try_lock_for_timed]).
+fn timed<G>(try_lock: impl FnOnce() -> TryLockResult<G>, wait: impl FnOnce() -> TryLockResult<G>) -> TryLockResult<(G, Acquisition)> {
+    let start = Instant::now();
+    let (result, acquisition) = match try_lock() {
+        Err(TryLockError::WouldBlock) => {
+            let result = wait();
+            (result, Acquisition::Waited(start.elapsed()))
+        }
+        result => (result, Acquisition::Immediate),
+    };
+    match result {
+        Ok(guard) => Ok((guard, acquisition)),
+        Err(TryLockError::Poisoned(poisoned)) => Err(TryLockError::Poisoned(PoisonError::new((poisoned.into_inner(), acquisition)))),
+        Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+    }
+}
+
+/// Run `on_lock` with the guard of `result` or, once the error is dropped, `on_timeout`.
+#[inline]
+fn run_locked<G, R>(result: TryLockResult<G>, on_lock: impl FnOnce(G) -> R, on_timeout: impl FnOnce() -> R) -> R {
+    match result {
+        Ok(guard) => on_lock(guard),
+        Err(error) => {
+            drop(error);
+            on_timeout()
+        }
+    }
+}
+
+/// Call `try_lock` once and, if the lock is taken, block on it with `lock` until it is released,
+/// for the timeouts that never run out. Both calls are counted in [poll_counts].
+pub(crate) fn lock_forever<G>(mut try_lock: impl FnMut() -> TryLockResult<G>, lock: impl FnOnce() -> TryLockResult<G>) -> TryLockResult<G> {
+    attempt(&mut try_lock).unwrap_or_else(|| counted_try_lock(lock))
+}
+
+/// Call `try_lock` every `interval` until it stops returning [TryLockError::WouldBlock], `timeout`
+/// elapses or `cancel` is set.
+#[inline]
+pub(crate) fn lock_until<G>(timeout: Duration, interval: Duration, cancel: Option<&AtomicBool>, mut try_lock: impl FnMut() -> TryLockResult<G>) -> TryLockResult<G> {
+    if timeout.is_zero() || is_cancelled(cancel) {
+        return Err(TryLockError::WouldBlock);
+    }
+    // A free lock is taken without reading the clock
+    if let Some(result) = attempt(&mut try_lock) {
+        return result;
+    }
+    retry_until(Instant::now(), timeout, interval, cancel, try_lock)
+}
+
+/// The retries of [lock_until] once the lock was found taken.
+#[cold]
+fn retry_until<G>(start: Instant, timeout: Duration, interval: Duration, cancel: Option<&AtomicBool>, mut try_lock: impl FnMut() -> TryLockResult<G>) -> TryLockResult<G> {
+    loop {
+        sleep_before_retry(interval);
+        if is_cancelled(cancel) || start.elapsed() >= timeout {
+            return Err(TryLockError::WouldBlock);
+        }
+        if let Some(result) = attempt(&mut try_lock) {
+            return result;
+        }
+    }
+}
+
+thread_local! {
+    /// Time the current thread slept between the retries to take a lock.
+    static RETRY_SLEEP: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    /// Sleeps between the retries and calls to `try_lock` of the current thread.
+    static POLLS: Cell<PollCounts> = const { Cell::new(PollCounts { sleeps: 0, try_locks: 0 }) };
+    /// Called when a `try_lock` of the current thread finds its lock taken, with the name of the
+    /// philosopher and the id of the fork it tries to take.
+    static ON_CONTENTION: RefCell<Option<(ContentionFn, String, usize)>> = const { RefCell::new(None) };
+}
+
+/// Sleep `interval` before trying to take a lock again, counting it in [retry_sleep_time] and
+/// [poll_counts].
+pub(crate) fn sleep_before_retry(interval: Duration) {
+    let start = Instant::now();
+    thread::sleep(interval);
+    RETRY_SLEEP.with(|slept| slept.set(slept.get() + start.elapsed()));
+    POLLS.with(|polls| polls.set(PollCounts { sleeps: polls.get().sleeps + 1, ..polls.get() }));
+}
+
+/// Call `try_lock` once, counting it in [poll_counts].
+#[inline(always)]
+pub(crate) fn counted_try_lock<G>(try_lock: impl FnOnce() -> TryLockResult<G>) -> TryLockResult<G> {
+    POLLS.with(|polls| polls.set(PollCounts { try_locks: polls.get().try_locks + 1, ..polls.get() }));
+    let result = try_lock();
+    if let Err(TryLockError::WouldBlock) = result {
+        report_contention();
+    }
+    result
+}
+
+/// Run `acquire`, which tries to take the fork with the id `fork` for `philosopher`, calling
+/// `on_contention` each time it finds the fork taken.
+pub(crate) fn reporting_contention<R>(on_contention: &ContentionFn, philosopher: &str, fork: usize, acquire: impl FnOnce() -> R) -> R {
+    ON_CONTENTION.with(|hook| *hook.borrow_mut() = Some((on_contention.clone(), philosopher.to_string(), fork)));
+    let result = acquire();
+    ON_CONTENTION.with(|hook| *hook.borrow_mut() = None);
+    result
+}
+
+#[cold]
+fn report_contention() {
+    ON_CONTENTION.with(|hook| {
+        if let Some((on_contention, philosopher, fork)) = &*hook.borrow() {
+            on_contention(philosopher, *fork);
+        }
+    });
+}
+
+/// Sleeps between the retries and calls to `try_lock` of the current thread so far.
+pub(crate) fn poll_counts() -> PollCounts {
+    POLLS.with(Cell::get)
+}
+
+/// Time the current thread slept between the retries to take a lock so far.
+pub(crate) fn retry_sleep_time() -> Duration {
+    RETRY_SLEEP.with(Cell::get)
+}
+
+/// Try to take the lock once, `None` if it is taken.
+#[inline(always)]
+fn attempt<G>(try_lock: &mut impl FnMut() -> TryLockResult<G>) -> Option<TryLockResult<G>> {
+    match counted_try_lock(try_lock) {
+        Err(TryLockError::WouldBlock) => None,
+        result => Some(result),
+    }
+}
+
+#[inline(always)]
+fn is_cancelled(cancel: Option<&AtomicBool>) -> bool {
+    cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+}
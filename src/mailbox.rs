@@ -0,0 +1,65 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Instant;
+
+/// What a philosopher tells its neighbors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// `from` is about to take its forks, it has been hungry since `since`.
+    Hungry { from: String, since: Instant },
+    /// `from` finished its meal, or gave up, and released its forks.
+    Full { from: String },
+}
+
+/// Where a philosopher receives the messages of its neighbors and sends its own, over channels
+/// alongside the shared forks ([See][crate::Strategy::Polite]).
+/// ```rust
+/// use std::time::Instant;
+/// use actividad_12::{Mailbox, Message};
+///
+/// let mailboxes = Mailbox::ring(4);
+/// let hungry = Message::Hungry { from: "Filósofo 1".to_string(), since: Instant::now() };
+/// mailboxes[0].send(&hungry);
+/// // Only the neighbors at both sides receive it
+/// assert_eq!(mailboxes[1].receive(), [hungry.clone()]);
+/// assert_eq!(mailboxes[3].receive(), [hungry]);
+/// assert!(mailboxes[2].receive().is_empty());
+/// assert!(mailboxes[1].receive().is_empty());
+/// ```
+#[derive(Debug)]
+pub struct Mailbox {
+    receiver: Receiver<Message>,
+    neighbors: Vec<Sender<Message>>,
+}
+
+impl Mailbox {
+    /// A mailbox for each of `philosophers` philosophers sitting around a table, each one sending
+    /// to the philosophers at its left and right.
+    pub fn ring(philosophers: usize) -> Vec<Mailbox> {
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..philosophers).map(|_| mpsc::channel()).unzip();
+        receivers
+            .into_iter()
+            .enumerate()
+            .map(|(i, receiver)| {
+                let mut neighbors = vec![(i + philosophers - 1) % philosophers, (i + 1) % philosophers];
+                neighbors.dedup();
+                neighbors.retain(|&neighbor| neighbor != i);
+                Mailbox {
+                    receiver,
+                    neighbors: neighbors.into_iter().map(|neighbor| senders[neighbor].clone()).collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Send `message` to every neighbor, the ones that left the table do not get it.
+    pub fn send(&self, message: &Message) {
+        for neighbor in &self.neighbors {
+            let _ = neighbor.send(message.clone());
+        }
+    }
+
+    /// Every message received since the last call, in the order they were sent by each neighbor.
+    pub fn receive(&self) -> Vec<Message> {
+        self.receiver.try_iter().collect()
+    }
+}
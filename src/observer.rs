@@ -0,0 +1,335 @@
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use crate::SimulationResult;
+
+const RESET: &str = "\x1b[0m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const BLUE: &str = "\x1b[34m";
+
+/// Width of the longest bar of [ConsoleObserver::with_meal_bars].
+const MEAL_BAR_WIDTH: usize = 40;
+
+/// What a philosopher is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Thinking,
+    Eating,
+    /// The philosopher finished a meal, `meals` is the number of meals eaten so far.
+    Ate { meals: u32 },
+    DoneEating,
+    /// The philosopher could not take both forks.
+    Failed,
+    /// The thread of the philosopher panicked.
+    Panicked,
+    /// The philosopher tasted the food with one fork, which is not a meal.
+    Tasted,
+}
+
+/// Logical clock shared by every philosopher, ticked by each event.
+static LOGICAL_CLOCK: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// Name of the philosopher.
+    pub philosopher: String,
+    pub kind: EventKind,
+    /// Lamport timestamp of the event, a total order of the events of every thread that does not
+    /// depend on their clocks. An event that happened before another one, such as a meal and the
+    /// next meal of a neighbor with the same fork, has a lower timestamp.
+    pub logical_time: u64,
+}
+
+impl Event {
+    /// An event of `philosopher` with the next tick of the logical clock.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Event, EventKind, Observer, SimulationConfig};
+    ///
+    /// #[derive(Default)]
+    /// struct Events(Mutex<Vec<Event>>);
+    ///
+    /// impl Observer for Events {
+    ///     fn on_event(&self, event: &Event) {
+    ///         self.0.lock().unwrap().push(event.clone());
+    ///     }
+    /// }
+    ///
+    /// let events = Arc::new(Events::default());
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(3)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .observer(events.clone())
+    ///     .build()
+    ///     .unwrap();
+    /// run_simulation(&config).unwrap();
+    ///
+    /// let events = events.0.lock().unwrap();
+    /// let mut times: Vec<u64> = events.iter().map(|event| event.logical_time).collect();
+    /// // The events of each philosopher are in order and no two events share a timestamp
+    /// for name in ["Filósofo 1", "Filósofo 2", "Filósofo 3"] {
+    ///     let own: Vec<u64> = events.iter().filter(|event| event.philosopher == name).map(|event| event.logical_time).collect();
+    ///     assert!(own.windows(2).all(|pair| pair[0] < pair[1]));
+    /// }
+    /// times.sort_unstable();
+    /// assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+    /// assert_eq!(times.len(), events.len());
+    /// ```
+    pub fn new(philosopher: &str, kind: EventKind) -> Self {
+        Self {
+            philosopher: philosopher.to_string(),
+            kind,
+            logical_time: LOGICAL_CLOCK.fetch_add(1, Ordering::SeqCst),
+        }
+    }
+}
+
+/// Receives what happens during a simulation. It is shared by the threads of every philosopher.
+pub trait Observer: Send + Sync {
+    fn on_event(&self, event: &Event);
+
+    /// Called each time `philosopher` takes a fork after waiting `waited` for it.
+    fn on_fork_acquired(&self, _philosopher: &str, _waited: Duration) {}
+
+    /// Called when `philosopher` has been trying to eat for `hungry_for`, longer than the hunger
+    /// deadline ([See][crate::SimulationConfigBuilder::hunger_deadline]). It is called once until
+    /// the philosopher eats again.
+    fn on_starving(&self, _philosopher: &str, _hungry_for: Duration) {}
+
+    /// Called once every philosopher left the table.
+    fn on_finish(&self, _result: &SimulationResult) {}
+}
+
+/// Ignore every event.
+impl Observer for () {
+    fn on_event(&self, _event: &Event) {}
+}
+
+/// Print every event to the terminal, or to another sink.
+#[derive(Clone, Default)]
+pub struct ConsoleObserver {
+    colors: bool,
+    leaderboard: bool,
+    meal_bars: bool,
+    /// Where every line is written instead of stdout and stderr, shared by the clones.
+    sink: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
+}
+
+impl ConsoleObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show each kind of event with a different color. The colors are only used when stdout is a
+    /// terminal so a captured output has no escape codes.
+    /// ```rust
+    /// use actividad_12::{ConsoleObserver, Event, EventKind};
+    ///
+    /// let event = Event::new("Filósofo 1", EventKind::Eating);
+    /// let line = ConsoleObserver::new().with_colors(false).format_event(&event);
+    /// assert_eq!(line, "Filósofo 1 está comiendo.");
+    /// assert!(!line.contains('\x1b'));
+    /// ```
+    pub fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors && io::stdout().is_terminal();
+        self
+    }
+
+    /// Rank the philosophers by their meals when they finish ([See][SimulationResult::leaderboard]).
+    pub fn with_leaderboard(mut self, leaderboard: bool) -> Self {
+        self.leaderboard = leaderboard;
+        self
+    }
+
+    /// Draw a bar of the meals of each philosopher when they finish, to see at a glance how
+    /// fairly they ate ([See][ConsoleObserver::format_meal_bars]).
+    pub fn with_meal_bars(mut self, meal_bars: bool) -> Self {
+        self.meal_bars = meal_bars;
+        self
+    }
+
+    /// Write every line to `sink`, such as a file or a buffer, instead of printing the events to
+    /// stdout and the errors to stderr.
+    /// ```rust
+    /// use std::io::{self, Write};
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, ConsoleObserver, SimulationConfig};
+    ///
+    /// // A buffer that can still be read once the observer owns it
+    /// #[derive(Clone, Default)]
+    /// struct Buffer(Arc<Mutex<Vec<u8>>>);
+    ///
+    /// impl Write for Buffer {
+    ///     fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+    ///         self.0.lock().unwrap().write(bytes)
+    ///     }
+    ///
+    ///     fn flush(&mut self) -> io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let buffer = Buffer::default();
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(2)
+    ///     .meals(1)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .observer(Arc::new(ConsoleObserver::new().with_colors(false).with_sink(Box::new(buffer.clone()))))
+    ///     .build()
+    ///     .unwrap();
+    /// run_simulation(&config).unwrap();
+    ///
+    /// let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    /// let lines: Vec<&str> = output.lines().collect();
+    /// for name in ["Filósofo 1", "Filósofo 2"] {
+    ///     assert!(lines.contains(&format!("{name} está comiendo.").as_str()));
+    ///     assert!(lines.contains(&format!("{name} comidas: 1.").as_str()));
+    /// }
+    /// assert!(lines.contains(&"Los filósofos han terminado de comer."));
+    /// assert!(lines.iter().any(|line| line.starts_with("Tiempo transcurrido: ")));
+    /// assert!(!output.contains('\x1b'));
+    /// ```
+    pub fn with_sink(mut self, sink: Box<dyn Write + Send>) -> Self {
+        self.sink = Some(Arc::new(Mutex::new(sink)));
+        self
+    }
+
+    /// Write `line` to the sink, or print it to stderr if it is an `error` and to stdout otherwise.
+    fn write_line(&self, line: &str, error: bool) {
+        match &self.sink {
+            // A sink that can not be written to loses the line, like a closed stdout would
+            Some(sink) => {
+                let _ = writeln!(sink.lock().unwrap_or_else(PoisonError::into_inner), "{line}");
+            }
+            None if error => eprintln!("{line}"),
+            None => println!("{line}"),
+        }
+    }
+
+    /// A line for each philosopher of `result`, in the order they sit, with its meals as a bar of
+    /// `#`. The bars are scaled so the most meals take `max_width` characters, and a philosopher
+    /// that did not eat has an empty bar.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{ConsoleObserver, PhilosopherStats, RetryHistogram, SampleStats, SimulationResult};
+    ///
+    /// let result = SimulationResult {
+    ///     philosophers: [("Filósofo 1", 10), ("Filósofo 2", 5), ("Filósofo 3", 0), ("Filósofo 4", 2)]
+    ///         .into_iter()
+    ///         .map(|(name, meals)| PhilosopherStats { name: name.to_string(), meals, panicked: false, satiated: false })
+    ///         .collect(),
+    ///     meals_target: 10,
+    ///     elapsed: Duration::ZERO,
+    ///     fork_waits: Vec::new(),
+    ///     single_fork_holds: Vec::new(),
+    ///     fork_acquisitions: Vec::new(),
+    ///     retry_sleeps: Vec::new(),
+    ///     failures: Vec::new(),
+    ///     polls: Vec::new(),
+    ///     first_meals: Vec::new(),
+    ///     samples: Vec::new(),
+    ///     sample_stats: SampleStats::default(),
+    ///     hand_offs: Vec::new(),
+    ///     peak_forks_held: 0,
+    ///     timelines: Vec::new(),
+    ///     cpu_time: None,
+    ///     fork_stats: Vec::new(),
+    ///     second_fork_retries: RetryHistogram::default(),
+    ///     round_progress: Vec::new(),
+    ///     tastes: Vec::new(),
+    /// };
+    /// let bars = ConsoleObserver::format_meal_bars(&result, 20);
+    /// let lengths: Vec<usize> = bars.iter().map(|bar| bar.matches('#').count()).collect();
+    /// assert_eq!(lengths, [20, 10, 0, 4]);
+    /// assert_eq!(bars[2], "Filósofo 3 | 0");
+    /// ```
+    pub fn format_meal_bars(result: &SimulationResult, max_width: usize) -> Vec<String> {
+        let most = result.philosophers.iter().map(|philosopher| philosopher.meals).max().unwrap_or(0);
+        let name_width = result.philosophers.iter().map(|philosopher| philosopher.name.chars().count()).max().unwrap_or(0);
+        result
+            .philosophers
+            .iter()
+            .map(|philosopher| {
+                // Rounded to the nearest character, nobody ate when the most meals are 0
+                let width = if most == 0 { 0 } else { (u64::from(philosopher.meals) * max_width as u64 * 2 + u64::from(most)) / (u64::from(most) * 2) };
+                format!("{:<name_width$} |{} {}", philosopher.name, "#".repeat(width as usize), philosopher.meals)
+            })
+            .collect()
+    }
+
+    /// The line printed for `event`.
+    pub fn format_event(&self, event: &Event) -> String {
+        let name = &event.philosopher;
+        let (line, color) = match event.kind {
+            EventKind::Thinking => (format!("{name} está pensando."), BLUE),
+            EventKind::Eating => (format!("{name} está comiendo."), GREEN),
+            EventKind::Ate { meals } => (format!("{name} comidas: {meals}."), GREEN),
+            EventKind::DoneEating => (format!("{name} terminó de comer y está pensando nuevamente."), BLUE),
+            EventKind::Failed => (format!("{name} no puede comer ya que no pudo tomar ambos tenedores."), RED),
+            EventKind::Panicked => (format!("{name} ha tenido un error al comer"), RED),
+            EventKind::Tasted => (format!("{name} probó la comida con un solo tenedor."), GREEN),
+        };
+        if self.colors {
+            format!("{color}{line}{RESET}")
+        } else {
+            line
+        }
+    }
+}
+
+impl fmt::Debug for ConsoleObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsoleObserver")
+            .field("colors", &self.colors)
+            .field("leaderboard", &self.leaderboard)
+            .field("meal_bars", &self.meal_bars)
+            .field("sink", &self.sink.is_some())
+            .finish()
+    }
+}
+
+impl Observer for ConsoleObserver {
+    fn on_event(&self, event: &Event) {
+        self.write_line(&self.format_event(event), event.kind == EventKind::Panicked);
+    }
+
+    fn on_starving(&self, philosopher: &str, hungry_for: Duration) {
+        let line = format!("{philosopher} lleva {hungry_for:.2?} sin poder comer.");
+        if self.colors {
+            self.write_line(&format!("{RED}{line}{RESET}"), true);
+        } else {
+            self.write_line(&line, true);
+        }
+    }
+
+    fn on_finish(&self, result: &SimulationResult) {
+        self.write_line("Los filósofos han terminado de comer.", false);
+        if self.leaderboard {
+            for (position, (name, meals)) in result.leaderboard().into_iter().enumerate() {
+                self.write_line(&format!("{}. {name}: {meals} comidas", position + 1), false);
+            }
+        }
+        if self.meal_bars {
+            for line in Self::format_meal_bars(result, MEAL_BAR_WIDTH) {
+                self.write_line(&line, false);
+            }
+        }
+        self.write_line(&format!("Tiempo transcurrido: {:.2?}", result.elapsed), false);
+        if cfg!(feature = "no-timeouts") {
+            self.write_line(&format!("Tiempo activo: {:.2?}", result.active_time()), false);
+        }
+        if !result.polls.is_empty() {
+            let polls = result.total_polls();
+            self.write_line(&format!("Esperas entre intentos: {}, intentos de tomar un tenedor: {}", polls.sleeps, polls.try_locks), false);
+        }
+    }
+}
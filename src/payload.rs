@@ -0,0 +1,62 @@
+/// State kept in a fork, updated under the lock of the fork each time a philosopher eats with it.
+pub trait ForkPayload {
+    fn on_use(&mut self);
+}
+
+/// Forks without state.
+impl ForkPayload for () {
+    fn on_use(&mut self) {}
+}
+
+/// Count how many meals were eaten with a fork.
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use std::thread;
+/// use actividad_12::{Fork, Philosopher, SimulationConfig, UsageCounter};
+/// # use std::time::Duration;
+///
+/// let config = SimulationConfig::builder()
+///     .think_time(Duration::from_millis(1))
+///     .eat_time(Duration::from_millis(1))
+///     .build()
+///     .unwrap();
+/// let n = 3;
+/// let forks: Vec<Arc<Mutex<UsageCounter>>> = (0..n).map(|_| Arc::default()).collect();
+/// let handles: Vec<_> = (0..n)
+///     .map(|i| {
+///         let (left, right): (Arc<dyn Fork<UsageCounter>>, Arc<dyn Fork<UsageCounter>>) = (forks[i].clone(), forks[(i + 1) % n].clone());
+///         let mut philosopher = Philosopher::new(&format!("Filósofo {}", i + 1), left, right, i == n - 1)
+///             .unwrap()
+///             .with_config(&config);
+///         thread::spawn(move || {
+///             while philosopher.meals() < 4 {
+///                 philosopher.dine();
+///             }
+///             philosopher.meals()
+///         })
+///     })
+///     .collect();
+/// let meals: Vec<u32> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+///
+/// // Fork `i` is used by the philosophers at `i - 1` and `i`
+/// for i in 0..n {
+///     let expected = meals[(i + n - 1) % n] + meals[i];
+///     assert_eq!(forks[i].lock().unwrap().uses(), u64::from(expected));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageCounter {
+    uses: u64,
+}
+
+impl UsageCounter {
+    pub fn uses(&self) -> u64 {
+        self.uses
+    }
+}
+
+impl ForkPayload for UsageCounter {
+    fn on_use(&mut self) {
+        self.uses += 1;
+    }
+}
@@ -0,0 +1,1263 @@
+use std::cmp;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, TryLockError, TryLockResult};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::{BiteFn, ContentionFn, ForkTimeoutFn};
+use crate::contention::ContentionWindow;
+use crate::intent::Announcement;
+use crate::lock::{counted_try_lock, poll_counts, reporting_contention, retry_sleep_time, sleep_before_retry};
+use crate::snapshot::LiveCounters;
+#[cfg(feature = "verify-exclusion")]
+use crate::ExclusionMonitor;
+use crate::{
+    AcquisitionOrder, AcquisitionStyle, Event, EventKind, FailureCounts, Fork, ForkGuard, ForkPayload, ForkPool, IntentBoard, Mailbox, Message, Observer, Phase, PollCounts, RetryHistogram, SampleKind, SampleStats, SeededSource, SimulationConfig, Strategy, TimeDistribution,
+    TimeoutLock, WaitForGraph,
+};
+
+pub struct Philosopher<T> {
+    name: String,
+    left_fork: Arc<dyn Fork<T>>,
+    right_fork: Arc<dyn Fork<T>>,
+    left_fork_id: usize,
+    right_fork_id: usize,
+    is_left_handed: bool,
+    counter: u32,
+    /// Seconds eaten so far.
+    satiation: f64,
+    /// Seconds of eating after which the philosopher is full.
+    satiation_target: Option<f64>,
+    /// Probability of tasting with one fork instead of trying to eat, how long it takes and
+    /// how many tastes there were.
+    taste_probability: f64,
+    taste_time: Duration,
+    tastes: u32,
+    /// Bites each meal is split into and what is checked between them.
+    bites: u32,
+    between_bites: Option<BiteFn>,
+    /// Time waited for each fork acquired.
+    fork_waits: Vec<Duration>,
+    /// Times each fork was acquired, by id.
+    fork_acquisitions: BTreeMap<usize, u64>,
+    /// Times the left and right forks were tried with a timeout and how many of those they were
+    /// acquired, to order them with [Strategy::AdaptiveOrder].
+    fork_successes: [(u64, u64); 2],
+    /// Every fork wait and meal with when it began, only collected when it is `Some`.
+    samples: Option<Vec<(SampleKind, Instant, Duration)>>,
+    /// Most samples kept, the later ones are only aggregated in `sample_stats`.
+    sample_limit: usize,
+    sample_stats: SampleStats,
+    /// Every phase that ended with when it began and ended, only collected when it is `Some`.
+    timeline: Option<Vec<(Phase, Instant, Instant)>>,
+    /// The current phase and when it began, while the timeline is collected.
+    phase: Option<(Phase, Instant)>,
+    fork_timeout: Duration,
+    /// Meals to eat and the fork timeout by meals left, replacing `fork_timeout` before each
+    /// attempt to eat.
+    fork_timeout_fn: Option<(u32, ForkTimeoutFn)>,
+    fork_interval: Duration,
+    /// Called each time a fork is found taken.
+    on_contention: Option<ContentionFn>,
+    /// Longest retry interval, the interval doubles after each failed attempt to eat up to it.
+    fork_backoff: Option<Duration>,
+    /// Retry interval of the next attempt to eat.
+    retry_interval: Duration,
+    think_time: TimeDistribution,
+    eat_time: TimeDistribution,
+    random: SeededSource,
+    cooldown: Duration,
+    /// When the first meal since the stats were reset began.
+    first_meal: Option<Instant>,
+    /// When the last meal ended.
+    last_meal: Option<Instant>,
+    hunger_deadline: Option<Duration>,
+    /// Longest time from beginning to think to the end of the meal, a round that would take longer
+    /// is abandoned.
+    round_budget: Option<Duration>,
+    /// When the current round runs out of its budget.
+    round_deadline: Option<Instant>,
+    /// When the philosopher began trying to eat, `None` while it is not hungry.
+    hungry_since: Option<Instant>,
+    /// The observer was told the philosopher is starving since it last ate.
+    starving: bool,
+    priority: u32,
+    /// Highest priority waiting for the left and right forks, shared with the neighbors when
+    /// priority inheritance is used.
+    waiting_priorities: Option<(Arc<AtomicU32>, Arc<AtomicU32>)>,
+    strategy: Strategy,
+    /// Held from taking the forks to releasing them with [Strategy::Serialized], shared by the
+    /// whole table.
+    table_lock: Arc<Mutex<()>>,
+    /// Meals of the philosopher and of its left and right neighbors, shared with them for
+    /// [Strategy::MinMakespan].
+    meal_counts: Option<(Arc<AtomicU32>, [Arc<AtomicU32>; 2])>,
+    /// Where the neighbors are told when the philosopher gets hungry and when it ate, if anywhere.
+    mailbox: Option<Mailbox>,
+    /// Forks of the whole table taken two at a time with [Strategy::SharedPool], if shared.
+    fork_pool: Option<Arc<ForkPool<T>>>,
+    /// Where the forks wanted are announced with [Strategy::Reservation], shared by the whole
+    /// table.
+    intent_board: Option<Arc<IntentBoard>>,
+    /// Where each attempt to take the forks is recorded, if anywhere.
+    contention_window: Option<Arc<ContentionWindow>>,
+    /// Where the meals, failures and phase are published for other threads, if anywhere.
+    live_counters: Option<Arc<LiveCounters>>,
+    /// Since when each neighbor that announced it is hungry has been hungry, until it ate.
+    hungry_neighbors: HashMap<String, Instant>,
+    acquisition_order: AcquisitionOrder,
+    /// Id of the fork taken first whatever the [AcquisitionOrder], if the order was given.
+    first_fork_id: Option<usize>,
+    acquisition_style: AcquisitionStyle,
+    /// How long the first fork was held while waiting for the second one, on each attempt that
+    /// got the first one.
+    single_fork_holds: Vec<Duration>,
+    /// Time slept between the retries to take the forks.
+    retry_sleep: Duration,
+    failures: FailureCounts,
+    polls: PollCounts,
+    /// Retries needed to take the second fork, on each attempt that got it.
+    second_fork_retries: RetryHistogram,
+    observer: Arc<dyn Observer>,
+    /// Where the forks held and waited for are published, if anywhere.
+    wait_for_graph: Option<Arc<WaitForGraph>>,
+    #[cfg(feature = "verify-exclusion")]
+    exclusion_monitor: Option<Arc<ExclusionMonitor>>,
+}
+
+impl<T> Philosopher<T> {
+    /// Create a philosopher using the timings of the default [SimulationConfig] and ignoring its events.
+    ///
+    /// Both forks must be different, a [Mutex][std::sync::Mutex] is not reentrant so a philosopher holding the same
+    /// fork twice would wait for itself until timing out.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use actividad_12::{Philosopher, PhilosopherError};
+    ///
+    /// let fork = Arc::new(Mutex::new(()));
+    /// let philosopher = Philosopher::new("Filósofo 1", fork.clone(), fork, false);
+    /// assert!(matches!(philosopher, Err(PhilosopherError::SameFork)));
+    /// ```
+    pub fn new(name: &str, left_fork: Arc<dyn Fork<T>>, right_fork: Arc<dyn Fork<T>>, is_left_handed: bool) -> Result<Self, PhilosopherError> {
+        if Arc::ptr_eq(&left_fork, &right_fork) {
+            return Err(PhilosopherError::SameFork);
+        }
+        let config = SimulationConfig::default();
+        Ok(Self {
+            name: name.to_string(),
+            left_fork,
+            right_fork,
+            left_fork_id: 0,
+            right_fork_id: 1,
+            is_left_handed,
+            counter: 0,
+            satiation: 0.0,
+            satiation_target: None,
+            taste_probability: config.taste_probability(),
+            taste_time: config.taste_time(),
+            tastes: 0,
+            bites: config.bites(),
+            between_bites: None,
+            fork_waits: Vec::new(),
+            fork_acquisitions: BTreeMap::new(),
+            fork_successes: [(0, 0); 2],
+            samples: None,
+            sample_limit: usize::MAX,
+            sample_stats: SampleStats::default(),
+            timeline: None,
+            phase: None,
+            fork_timeout: config.fork_timeout(),
+            fork_timeout_fn: None,
+            fork_interval: config.fork_interval(),
+            on_contention: None,
+            fork_backoff: config.fork_backoff(),
+            retry_interval: config.fork_interval(),
+            think_time: config.think_time(),
+            eat_time: config.eat_time(),
+            random: SeededSource::new(config.seed()),
+            cooldown: config.cooldown(),
+            first_meal: None,
+            last_meal: None,
+            hunger_deadline: config.hunger_deadline(),
+            round_budget: config.round_budget(),
+            round_deadline: None,
+            hungry_since: None,
+            starving: false,
+            priority: 0,
+            waiting_priorities: None,
+            strategy: config.strategy(),
+            table_lock: Arc::default(),
+            meal_counts: None,
+            mailbox: None,
+            fork_pool: None,
+            intent_board: None,
+            contention_window: None,
+            live_counters: None,
+            hungry_neighbors: HashMap::new(),
+            acquisition_order: config.acquisition_order(),
+            first_fork_id: None,
+            acquisition_style: config.acquisition_style(),
+            single_fork_holds: Vec::new(),
+            retry_sleep: Duration::ZERO,
+            failures: FailureCounts::default(),
+            polls: PollCounts::default(),
+            second_fork_retries: RetryHistogram::default(),
+            observer: config.observer().clone(),
+            wait_for_graph: None,
+            #[cfg(feature = "verify-exclusion")]
+            exclusion_monitor: None,
+        })
+    }
+
+    /// Use the fork timeout, also by meals left, retry interval and backoff, contention callback, think/eat times and
+    /// seed, cooldown, hunger deadline, round budget, satiation target, tastes, bites, strategy, acquisition order and
+    /// style, observer, wait-for graph and, with the `verify-exclusion` feature, exclusion monitor of
+    /// `config`.
+    pub fn with_config(mut self, config: &SimulationConfig) -> Self {
+        self.fork_timeout = config.fork_timeout();
+        self.fork_timeout_fn = config.fork_timeout_fn().map(|timeout| (config.meals(), timeout.clone()));
+        self.fork_interval = config.fork_interval();
+        self.on_contention = config.on_contention().cloned();
+        self.fork_backoff = config.fork_backoff();
+        self.retry_interval = config.fork_interval();
+        self.think_time = config.think_time();
+        self.eat_time = config.eat_time();
+        self.random = SeededSource::new(config.seed());
+        self.cooldown = config.cooldown();
+        self.hunger_deadline = config.hunger_deadline();
+        self.round_budget = config.round_budget();
+        self.satiation_target = config.satiation_target();
+        self.taste_probability = config.taste_probability();
+        self.taste_time = config.taste_time();
+        self.bites = config.bites();
+        self.between_bites = config.between_bites().cloned();
+        self.strategy = config.strategy();
+        self.acquisition_order = config.acquisition_order();
+        self.acquisition_style = config.acquisition_style();
+        self.observer = config.observer().clone();
+        self.wait_for_graph = config.wait_for_graph().cloned();
+        #[cfg(feature = "verify-exclusion")]
+        {
+            self.exclusion_monitor = config.exclusion_monitor().cloned();
+        }
+        self
+    }
+
+    /// Draw the think and eat times from other distributions than the ones of the config.
+    pub fn with_times(mut self, think_time: TimeDistribution, eat_time: TimeDistribution) -> Self {
+        self.think_time = think_time;
+        self.eat_time = eat_time;
+        self
+    }
+
+    /// Seed the random think and eat times.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.random = SeededSource::new(seed);
+        self
+    }
+
+    /// Draw the random think and eat times from `random`, to record or replay them.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use actividad_12::{Philosopher, SeededSource, TimeDistribution};
+    ///
+    /// fn dine(random: SeededSource) -> Vec<u64> {
+    ///     let times = TimeDistribution::Exponential { mean: Duration::from_millis(1) };
+    ///     let mut philosopher = Philosopher::new("Filósofo 1", Arc::new(Mutex::new(())), Arc::new(Mutex::new(())), false)
+    ///         .unwrap()
+    ///         .with_times(times, times)
+    ///         .with_random(random.recording());
+    ///     for _ in 0..5 {
+    ///         philosopher.dine();
+    ///     }
+    ///     philosopher.random().draws().unwrap().iter().map(|draw| draw.value).collect()
+    /// }
+    ///
+    /// let recorded = dine(SeededSource::new(7));
+    /// assert_eq!(recorded.len(), 10);
+    /// // Another philosopher replaying the draws thinks and eats for the same times
+    /// assert_eq!(dine(SeededSource::replaying(recorded.clone())), recorded);
+    /// assert_ne!(dine(SeededSource::new(8)), recorded);
+    /// ```
+    pub fn with_random(mut self, random: SeededSource) -> Self {
+        self.random = random;
+        self
+    }
+
+    /// Source of the random think and eat times.
+    pub fn random(&self) -> &SeededSource {
+        &self.random
+    }
+
+    /// The higher the priority the more important the philosopher, it only matters with
+    /// [Philosopher::with_priority_inheritance].
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Share with the neighbors the highest priority waiting for the left and right forks. The
+    /// philosopher announces its priority before taking the forks and finishes its meal early
+    /// when someone with a higher priority waits for one of the forks it holds.
+    /// ```rust
+    /// use std::sync::atomic::AtomicU32;
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{Fork, Philosopher, SimulationConfig};
+    ///
+    /// // Time the important philosopher waits while the other one eats for 300ms
+    /// fn wait_of_important(inheritance: bool) -> Duration {
+    ///     let config = SimulationConfig::builder()
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(300))
+    ///         .fork_timeout(Duration::from_secs(1))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .build()
+    ///         .unwrap();
+    ///     let forks: Vec<Arc<dyn Fork<()>>> = vec![Arc::new(Mutex::new(())), Arc::new(Mutex::new(()))];
+    ///     let waiting: Vec<_> = (0..2).map(|_| Arc::new(AtomicU32::new(0))).collect();
+    ///     let philosopher = |name: &str, priority| {
+    ///         let philosopher = Philosopher::new(name, forks[0].clone(), forks[1].clone(), false)
+    ///             .unwrap()
+    ///             .with_config(&config)
+    ///             .with_priority(priority);
+    ///         if inheritance {
+    ///             philosopher.with_priority_inheritance(waiting[0].clone(), waiting[1].clone())
+    ///         } else {
+    ///             philosopher
+    ///         }
+    ///     };
+    ///     let mut holder = philosopher("Filósofo 1", 0);
+    ///     let mut important = philosopher("Filósofo 2", 1);
+    ///     let holder = thread::spawn(move || holder.dine());
+    ///     thread::sleep(Duration::from_millis(50));
+    ///     important.dine();
+    ///     holder.join().unwrap();
+    ///     important.fork_waits().iter().sum()
+    /// }
+    ///
+    /// let with_inheritance = wait_of_important(true);
+    /// let without_inheritance = wait_of_important(false);
+    /// assert!(with_inheritance < Duration::from_millis(100));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(with_inheritance < without_inheritance);
+    /// ```
+    pub fn with_priority_inheritance(mut self, left_waiting: Arc<AtomicU32>, right_waiting: Arc<AtomicU32>) -> Self {
+        self.waiting_priorities = Some((left_waiting, right_waiting));
+        self
+    }
+
+    /// Share `table_lock` with the rest of the table, so only one philosopher at a time takes its
+    /// forks and eats with [Strategy::Serialized]. By default the philosopher has a lock of its
+    /// own.
+    pub fn with_table_lock(mut self, table_lock: Arc<Mutex<()>>) -> Self {
+        self.table_lock = table_lock;
+        self
+    }
+
+    /// Publish the meals of the philosopher in `own` and read the ones of its neighbors in
+    /// `neighbors`, so with [Strategy::MinMakespan] it lets the neighbors that ate less go first.
+    pub fn with_meal_counts(mut self, own: Arc<AtomicU32>, neighbors: [Arc<AtomicU32>; 2]) -> Self {
+        self.meal_counts = Some((own, neighbors));
+        self
+    }
+
+    /// Stop publishing the meals once the philosopher left the table, so the neighbors do not
+    /// wait for it.
+    pub(crate) fn leave_meal_counts(&self) {
+        if let Some((own, _)) = &self.meal_counts {
+            own.store(u32::MAX, Ordering::SeqCst);
+        }
+    }
+
+    /// Tell the neighbors through `mailbox` when the philosopher gets hungry and when it ate, and
+    /// listen to theirs to defer to them with [Strategy::Polite].
+    pub fn with_mailbox(mut self, mailbox: Mailbox) -> Self {
+        self.mailbox = Some(mailbox);
+        self
+    }
+
+    /// Take any two free forks of `fork_pool` with [Strategy::SharedPool] instead of the left and
+    /// right forks, which are still taken with the other strategies. Without a pool the
+    /// philosopher takes its own forks like with [Strategy::Timeout].
+    pub fn with_fork_pool(mut self, fork_pool: Arc<ForkPool<T>>) -> Self {
+        self.fork_pool = Some(fork_pool);
+        self
+    }
+
+    /// Announce the forks on `intent_board` and wait for their reservation before taking them
+    /// with [Strategy::Reservation]. Without a board the philosopher takes its forks like with
+    /// [Strategy::Timeout].
+    pub fn with_intent_board(mut self, intent_board: Arc<IntentBoard>) -> Self {
+        self.intent_board = Some(intent_board);
+        self
+    }
+
+    /// Record in `contention_window` each attempt to take the forks and whether it failed.
+    pub(crate) fn with_contention_window(mut self, contention_window: Arc<ContentionWindow>) -> Self {
+        self.contention_window = Some(contention_window);
+        self
+    }
+
+    /// Publish the meals, failures and phase in `live_counters` as they change, beginning with
+    /// the current meals and failures.
+    pub(crate) fn with_live_counters(mut self, live_counters: Arc<LiveCounters>) -> Self {
+        live_counters.publish(self.counter, &self.failures);
+        self.live_counters = Some(live_counters);
+        self
+    }
+
+    /// Begin with `meals` meals already eaten, to continue where a previous simulation left off.
+    pub fn with_meals(mut self, meals: u32) -> Self {
+        self.counter = meals;
+        self
+    }
+
+    /// Begin with the failed attempts of `failures` already counted.
+    pub(crate) fn with_failures(mut self, failures: FailureCounts) -> Self {
+        self.failures = failures;
+        self
+    }
+
+    /// Keep thinking for at least `cooldown` after each meal before trying to take the forks
+    /// again, so the philosopher does not hog the table.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::{Fork, Philosopher, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let forks: Vec<Arc<dyn Fork<()>>> = (0..3).map(|_| Arc::new(Mutex::new(())) as Arc<dyn Fork<()>>).collect();
+    /// // Both share the middle fork, the first one rests after each meal
+    /// let patient = Philosopher::new("Filósofo 1", forks[0].clone(), forks[1].clone(), false).unwrap().with_config(&config).with_cooldown(Duration::from_millis(20));
+    /// let eager = Philosopher::new("Filósofo 2", forks[1].clone(), forks[2].clone(), false).unwrap().with_config(&config);
+    /// let deadline = Instant::now() + Duration::from_millis(200);
+    /// let [patient, eager] = [patient, eager].map(|mut philosopher| {
+    ///     thread::spawn(move || {
+    ///         while Instant::now() < deadline {
+    ///             philosopher.dine();
+    ///         }
+    ///         philosopher.meals()
+    ///     })
+    /// });
+    /// let (patient, eager) = (patient.join().unwrap(), eager.join().unwrap());
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(patient < eager, "{patient} {eager}");
+    /// ```
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Identify the forks, by default the left fork is `0` and the right one is `1`. The ids are
+    /// used by [AcquisitionOrder].
+    pub fn with_fork_ids(mut self, left_fork_id: usize, right_fork_id: usize) -> Self {
+        self.left_fork_id = left_fork_id;
+        self.right_fork_id = right_fork_id;
+        self
+    }
+
+    /// Take the forks in the order of their ids in `order` instead of the [AcquisitionOrder]
+    /// ([See][crate::SimulationConfigBuilder::fork_order]). `order` must have the ids of both
+    /// forks once each.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use actividad_12::{Philosopher, PhilosopherError};
+    ///
+    /// let philosopher = || Philosopher::new("Filósofo 1", Arc::new(Mutex::new(())), Arc::new(Mutex::new(())), false).unwrap().with_fork_ids(3, 4);
+    /// assert!(philosopher().with_fork_order(&[4, 3]).is_ok());
+    /// assert_eq!(philosopher().with_fork_order(&[3, 3]).err(), Some(PhilosopherError::InvalidForkOrder(vec![3, 3])));
+    /// ```
+    pub fn with_fork_order(mut self, order: &[usize]) -> Result<Self, PhilosopherError> {
+        let (left, right) = (self.left_fork_id, self.right_fork_id);
+        if order != [left, right] && order != [right, left] {
+            return Err(PhilosopherError::InvalidForkOrder(order.to_vec()));
+        }
+        self.first_fork_id = Some(order[0]);
+        Ok(self)
+    }
+
+    /// Use `fork`, with id `fork_id`, as the right fork from now on, taking the forks in the
+    /// [AcquisitionOrder] again. It must not be called while the philosopher is dining.
+    pub(crate) fn replace_right_fork(&mut self, fork: Arc<dyn Fork<T>>, fork_id: usize) {
+        self.right_fork = fork;
+        self.right_fork_id = fork_id;
+        self.first_fork_id = None;
+    }
+
+    /// Whether both forks are free right now, for a hint of who is likely to eat next. Each fork
+    /// is taken and released in turn so both are never held, and a neighbor may take one right
+    /// after the check.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use actividad_12::Philosopher;
+    ///
+    /// let (left, right) = (Arc::new(Mutex::new(())), Arc::new(Mutex::new(())));
+    /// let philosopher = Philosopher::new("Filósofo 1", left.clone(), right.clone(), false).unwrap();
+    /// assert!(philosopher.could_eat_now());
+    /// // Checking does not keep the forks
+    /// assert!(left.try_lock().is_ok() && right.try_lock().is_ok());
+    ///
+    /// let guard = right.lock().unwrap();
+    /// assert!(!philosopher.could_eat_now());
+    /// drop(guard);
+    /// let guard = left.lock().unwrap();
+    /// assert!(!philosopher.could_eat_now());
+    /// drop(guard);
+    /// assert!(philosopher.could_eat_now());
+    /// ```
+    pub fn could_eat_now(&self) -> bool {
+        is_free(&*self.left_fork) && is_free(&*self.right_fork)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of meals eaten so far.
+    pub fn meals(&self) -> u32 {
+        self.counter
+    }
+
+    /// Number of times the philosopher tasted with one fork so far
+    /// ([See][crate::SimulationConfigBuilder::taste]).
+    pub fn tastes(&self) -> u32 {
+        self.tastes
+    }
+
+    /// Seconds spent eating so far, how full the philosopher is.
+    pub fn satiation(&self) -> f64 {
+        self.satiation
+    }
+
+    /// Whether the philosopher ate for the satiation target of its config, if any, so it does not
+    /// need more meals.
+    pub fn is_satiated(&self) -> bool {
+        self.satiation_target.is_some_and(|target| self.satiation >= target)
+    }
+
+    /// Time waited for each fork acquired so far.
+    pub fn fork_waits(&self) -> &[Duration] {
+        &self.fork_waits
+    }
+
+    /// Keep the first `limit` fork waits and meals ([See][crate::Sample]) and aggregate all of them.
+    pub(crate) fn collect_samples(mut self, limit: usize) -> Self {
+        self.samples = Some(Vec::new());
+        self.sample_limit = limit;
+        self
+    }
+
+    /// The samples collected so far, empty unless they are collected.
+    pub(crate) fn samples(&self) -> &[(SampleKind, Instant, Duration)] {
+        self.samples.as_deref().unwrap_or_default()
+    }
+
+    /// Keep every phase the philosopher goes through ([See][crate::PhaseSpan]).
+    pub(crate) fn collect_timeline(mut self) -> Self {
+        self.timeline = Some(Vec::new());
+        self
+    }
+
+    /// The phases that ended so far, empty unless the timeline is collected.
+    pub(crate) fn timeline(&self) -> &[(Phase, Instant, Instant)] {
+        self.timeline.as_deref().unwrap_or_default()
+    }
+
+    /// End the current phase, once the philosopher leaves the table.
+    pub(crate) fn end_timeline(&mut self) {
+        if let (Some(timeline), Some((phase, since))) = (&mut self.timeline, self.phase.take()) {
+            timeline.push((phase, since, Instant::now()));
+        }
+    }
+
+    /// Aggregates of every sample collected so far, including the ones over the limit.
+    pub(crate) fn sample_stats(&self) -> &SampleStats {
+        &self.sample_stats
+    }
+
+    /// How long the first fork was held while waiting for the second one, on each attempt that
+    /// got the first one.
+    pub fn single_fork_holds(&self) -> &[Duration] {
+        &self.single_fork_holds
+    }
+
+    /// Time slept between the retries to take the forks so far.
+    pub fn retry_sleep(&self) -> Duration {
+        self.retry_sleep
+    }
+
+    /// Why the attempts to eat failed so far.
+    pub fn failures(&self) -> FailureCounts {
+        self.failures
+    }
+
+    /// How often the philosopher slept and called `try_lock` while taking its forks.
+    pub fn polls(&self) -> PollCounts {
+        self.polls
+    }
+
+    /// Retries the philosopher needed to take its second fork, on each attempt that got it.
+    pub fn second_fork_retries(&self) -> &RetryHistogram {
+        &self.second_fork_retries
+    }
+
+    /// When the philosopher began its first meal, `None` if it has not eaten yet.
+    pub fn first_meal(&self) -> Option<Instant> {
+        self.first_meal
+    }
+
+    /// Ids of the forks acquired so far, the left and right ones or those of a [ForkPool], with
+    /// the times each one was acquired.
+    pub fn fork_acquisitions(&self) -> Vec<(usize, u64)> {
+        self.fork_acquisitions.iter().map(|(&fork, &acquisitions)| (fork, acquisitions)).collect()
+    }
+
+    /// Forget the meals eaten and the fork waits so far.
+    pub(crate) fn reset_stats(&mut self) {
+        self.counter = 0;
+        self.satiation = 0.0;
+        self.tastes = 0;
+        self.fork_waits.clear();
+        self.fork_acquisitions.clear();
+        self.single_fork_holds.clear();
+        self.retry_sleep = Duration::ZERO;
+        self.failures = FailureCounts::default();
+        self.polls = PollCounts::default();
+        self.second_fork_retries = RetryHistogram::default();
+        self.first_meal = None;
+        if let Some(samples) = &mut self.samples {
+            samples.clear();
+        }
+        self.sample_stats = SampleStats::default();
+        self.publish_counters();
+        if let Some(timeline) = &mut self.timeline {
+            timeline.clear();
+            // The current phase goes on but the earlier part of it is forgotten too
+            if let Some((_, since)) = &mut self.phase {
+                *since = Instant::now();
+            }
+        }
+    }
+
+    /// Try to dine taking the forks according to the strategy ([See][Strategy]). If the
+    /// philosopher could not get both forks the one it holds is released. While eating
+    /// [ForkPayload::on_use] is called on the values of both forks.
+    ///
+    /// A philosopher that keeps failing for longer than the hunger deadline is reported to
+    /// [Observer::on_starving].
+    /// ```rust
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use actividad_12::{Event, Observer, Philosopher, SimulationConfig};
+    ///
+    /// #[derive(Default)]
+    /// struct Starvation(AtomicUsize);
+    ///
+    /// impl Observer for Starvation {
+    ///     fn on_event(&self, _event: &Event) {}
+    ///
+    ///     fn on_starving(&self, _philosopher: &str, hungry_for: Duration) {
+    ///         assert!(hungry_for > Duration::from_millis(20));
+    ///         self.0.fetch_add(1, Ordering::SeqCst);
+    ///     }
+    /// }
+    ///
+    /// let starvation = Arc::new(Starvation::default());
+    /// let config = SimulationConfig::builder()
+    ///     .fork_timeout(Duration::from_millis(10))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .think_time(Duration::ZERO)
+    ///     .hunger_deadline(Duration::from_millis(20))
+    ///     .observer(starvation.clone())
+    ///     .build()
+    ///     .unwrap();
+    /// let (left, right) = (Arc::new(Mutex::new(())), Arc::new(Mutex::new(())));
+    /// let mut philosopher = Philosopher::new("Filósofo 1", left.clone(), right, false).unwrap().with_config(&config);
+    ///
+    /// // Someone else holds the left fork for the whole time, without the timeouts the
+    /// // philosopher would wait for it forever
+    /// let _held = left.lock().unwrap();
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// # {
+    /// for _ in 0..5 {
+    ///     philosopher.dine();
+    /// }
+    /// assert_eq!(philosopher.meals(), 0);
+    /// assert_eq!(starvation.0.load(Ordering::SeqCst), 1);
+    /// # }
+    /// ```
+    /// A first fork that is never released makes the attempt fail once its timeout runs out:
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::{Philosopher, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .think_time(Duration::ZERO)
+    ///     .fork_timeout(Duration::from_millis(50))
+    ///     .fork_interval(Duration::from_millis(5))
+    ///     .build()
+    ///     .unwrap();
+    /// let (left, right) = (Arc::new(Mutex::new(())), Arc::new(Mutex::new(())));
+    /// // Right handed, the right fork is taken first
+    /// let mut philosopher = Philosopher::new("Filósofo 1", left, right.clone(), false).unwrap().with_config(&config);
+    /// let _held = right.lock().unwrap();
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// # {
+    /// let start = Instant::now();
+    /// philosopher.dine();
+    /// assert!(start.elapsed() < Duration::from_millis(500), "{:?}", start.elapsed());
+    /// assert_eq!(philosopher.meals(), 0);
+    /// assert_eq!(philosopher.failures().first_fork, 1);
+    /// # }
+    /// ```
+    pub fn dine(&mut self)
+    where
+        T: ForkPayload,
+    {
+        self.round_deadline = self.round_budget.map(|budget| Instant::now() + budget);
+        self.enter_phase(Phase::Thinking);
+        self.notify(EventKind::Thinking);
+        pause(self.think_time.sample(&mut self.random));
+        if let Some(last_meal) = self.last_meal {
+            pause(self.cooldown.saturating_sub(last_meal.elapsed()));
+        }
+        // No number is drawn without tastes so the times stay the same for a seed
+        if self.taste_probability > 0.0 && self.random.next_f64() < self.taste_probability {
+            self.taste();
+            return;
+        }
+
+        self.enter_phase(Phase::Hungry);
+        if self.hungry_since.is_none() {
+            let hungry_since = *self.hungry_since.insert(Instant::now());
+            self.tell_neighbors(Message::Hungry { from: self.name.clone(), since: hungry_since });
+        }
+        let hungry_since = self.hungry_since.expect("set above when the philosopher got hungry");
+        self.read_mailbox();
+        if self.strategy == Strategy::Polite && cfg!(not(feature = "no-timeouts")) {
+            self.defer_to_neighbors(hungry_since);
+        }
+        if let Some((own, _)) = &self.meal_counts {
+            own.store(self.counter, Ordering::SeqCst);
+        }
+        if self.strategy == Strategy::MinMakespan && cfg!(not(feature = "no-timeouts")) {
+            self.defer_to_lagging();
+        }
+        if let Some((meals, timeout)) = &self.fork_timeout_fn {
+            self.fork_timeout = timeout(meals.saturating_sub(self.counter));
+        }
+        if let Some((left_waiting, right_waiting)) = &self.waiting_priorities {
+            left_waiting.fetch_max(self.priority, Ordering::SeqCst);
+            right_waiting.fetch_max(self.priority, Ordering::SeqCst);
+        }
+        debug_assert!(!Arc::ptr_eq(&self.left_fork, &self.right_fork), "{} has the same fork on both sides", self.name);
+        let (left_fork, right_fork) = (self.left_fork.clone(), self.right_fork.clone());
+        let table_lock = self.table_lock.clone();
+        let fork_pool = self.fork_pool.clone();
+        // Ids of the forks eaten with, other than the left and right ones when taken from the pool
+        let mut eating_ids = [self.left_fork_id, self.right_fork_id];
+        // Withdrawn once dropped, after the forks are released at the end of the meal
+        let announcement = match &self.intent_board {
+            Some(intent_board) if self.strategy == Strategy::Reservation && cfg!(not(feature = "no-timeouts")) => {
+                Some(Announcement::new(intent_board.clone(), &self.name, eating_ids, self.priority))
+            }
+            _ => None,
+        };
+        let reserved = announcement.as_ref().is_none_or(|announcement| self.wait_for_reservation(announcement));
+        // Held until the forks are released at the end of the meal
+        let _turn = (self.strategy == Strategy::Serialized).then(|| table_lock.lock().unwrap_or_else(PoisonError::into_inner));
+        let (slept, polls) = (retry_sleep_time(), poll_counts());
+        let forks = if self.round_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.failures.over_budget += 1;
+            None
+        } else if !reserved {
+            self.failures.not_reserved += 1;
+            None
+        } else if cfg!(feature = "no-timeouts") {
+            self.take_forks_blocking(&*left_fork, &*right_fork)
+        } else {
+            match self.strategy {
+                Strategy::Timeout | Strategy::AdaptiveOrder | Strategy::Serialized | Strategy::Polite | Strategy::MinMakespan | Strategy::Reservation => {
+                    self.take_forks_in_order(&*left_fork, &*right_fork)
+                }
+                Strategy::OpportunisticWithMaxHold(max_hold) => self.take_forks_opportunistically(&*left_fork, &*right_fork, max_hold),
+                Strategy::SharedPool => match &fork_pool {
+                    Some(fork_pool) => self.take_pooled_forks(fork_pool).map(|[(first, first_id), (second, second_id)]| {
+                        eating_ids = [first_id, second_id];
+                        (first, second)
+                    }),
+                    None => self.take_forks_in_order(&*left_fork, &*right_fork),
+                },
+            }
+        };
+        if let Some(window) = &self.contention_window {
+            window.record(forks.is_none());
+        }
+        self.retry_sleep += retry_sleep_time().saturating_sub(slept);
+        self.polls = self.polls + (poll_counts() - polls);
+        let meal = forks.and_then(|forks| {
+            let eat_time = self.eat_time.sample(&mut self.random);
+            // A meal that would end after the deadline is useless, the forks are released at once
+            let eats_for = if cfg!(feature = "no-timeouts") { Duration::ZERO } else { eat_time };
+            if self.round_deadline.is_some_and(|deadline| Instant::now() + eats_for > deadline) {
+                self.failures.over_budget += 1;
+                return None;
+            }
+            Some((forks, eat_time))
+        });
+
+        if let Some(((mut first, mut second), eat_time)) = meal {
+            self.publish_waits(&eating_ids, None);
+            first.on_use();
+            second.on_use();
+            self.record_sample(SampleKind::Meal, hungry_since, hungry_since.elapsed());
+            self.first_meal.get_or_insert_with(Instant::now);
+            self.enter_phase(Phase::Eating);
+            self.notify(EventKind::Eating);
+            #[cfg(feature = "verify-exclusion")]
+            if let Some(monitor) = &self.exclusion_monitor {
+                monitor.begin_eating(&self.name, eating_ids);
+            }
+            let eating_since = Instant::now();
+            self.eat(eat_time);
+            self.satiation += eating_since.elapsed().as_secs_f64();
+            #[cfg(feature = "verify-exclusion")]
+            if let Some(monitor) = &self.exclusion_monitor {
+                monitor.end_eating(&self.name, eating_ids);
+            }
+
+            self.counter += 1;
+            if let Some((own, _)) = &self.meal_counts {
+                own.store(self.counter, Ordering::SeqCst);
+            }
+            self.publish_counters();
+            self.retry_interval = self.fork_interval;
+            self.last_meal = Some(Instant::now());
+            self.hungry_since = None;
+            self.starving = false;
+            self.notify(EventKind::Ate { meals: self.counter });
+            self.publish_waits(&[], None);
+            self.tell_neighbors(Message::Full { from: self.name.clone() });
+            self.enter_phase(Phase::Thinking);
+            self.notify(EventKind::DoneEating);
+        } else {
+            self.publish_waits(&[], None);
+            self.publish_counters();
+            self.enter_phase(Phase::Thinking);
+            self.notify(EventKind::Failed);
+            if let Some(max_interval) = self.fork_backoff {
+                self.retry_interval = self.retry_interval.saturating_mul(2).min(max_interval.max(self.fork_interval));
+            }
+            let hungry_for = hungry_since.elapsed();
+            if !self.starving && self.hunger_deadline.is_some_and(|deadline| hungry_for > deadline) {
+                self.starving = true;
+                self.observer.on_starving(&self.name, hungry_for);
+            }
+        }
+    }
+
+    /// Take only the first fork in the acquisition order, waiting at most the fork timeout, and
+    /// hold it for the taste time. A taste that could not get the fork is simply skipped.
+    fn taste(&mut self) {
+        let (left_fork, right_fork) = (self.left_fork.clone(), self.right_fork.clone());
+        let ((fork, fork_id), _) = self.order_forks(&*left_fork, &*right_fork);
+        let start = Instant::now();
+        self.publish_waits(&[], Some(fork_id));
+        let taken = if cfg!(feature = "no-timeouts") {
+            counted_try_lock(|| fork.lock_blocking())
+        } else {
+            self.contending(fork_id, || fork.try_lock_for(self.within_round(self.fork_timeout), self.retry_interval))
+        };
+        if let Ok(_guard) = taken {
+            self.publish_waits(&[fork_id], None);
+            self.record_fork_wait(fork_id, start);
+            pause(self.taste_time);
+            self.tastes += 1;
+            self.notify(EventKind::Tasted);
+        }
+        self.publish_waits(&[], None);
+    }
+
+    /// Publish the meals and failures, if they are published anywhere.
+    fn publish_counters(&self) {
+        if let Some(live_counters) = &self.live_counters {
+            live_counters.publish(self.counter, &self.failures);
+        }
+    }
+
+    /// Send `message` to the neighbors, if the philosopher has a mailbox.
+    fn tell_neighbors(&self, message: Message) {
+        if let Some(mailbox) = &self.mailbox {
+            mailbox.send(&message);
+        }
+    }
+
+    /// Update which neighbors are hungry with the messages received since the last time.
+    fn read_mailbox(&mut self) {
+        let Some(mailbox) = &self.mailbox else {
+            return;
+        };
+        for message in mailbox.receive() {
+            match message {
+                Message::Hungry { from, since } => {
+                    self.hungry_neighbors.insert(from, since);
+                }
+                Message::Full { from } => {
+                    self.hungry_neighbors.remove(&from);
+                }
+            }
+        }
+    }
+
+    /// Wait, at most the fork timeout, while a neighbor that got hungry before the philosopher
+    /// has not eaten yet, ties broken by name.
+    fn defer_to_neighbors(&mut self, hungry_since: Instant) {
+        let start = Instant::now();
+        while start.elapsed() < self.fork_timeout && self.hungry_neighbors.iter().any(|(name, since)| (*since, name) < (hungry_since, &self.name)) {
+            sleep_before_retry(self.retry_interval);
+            self.read_mailbox();
+        }
+    }
+
+    /// Wait, at most the fork timeout, until the forks of `announcement` are reserved and tell
+    /// whether they were.
+    fn wait_for_reservation(&self, announcement: &Announcement) -> bool {
+        let start = Instant::now();
+        while !announcement.try_reserve() {
+            if start.elapsed() >= self.fork_timeout {
+                return false;
+            }
+            sleep_before_retry(self.retry_interval);
+        }
+        true
+    }
+
+    /// Wait, at most the fork timeout, while a neighbor ate fewer meals than the philosopher.
+    fn defer_to_lagging(&mut self) {
+        let Some((_, neighbors)) = &self.meal_counts else {
+            return;
+        };
+        let start = Instant::now();
+        while start.elapsed() < self.fork_timeout && neighbors.iter().any(|meals| meals.load(Ordering::SeqCst) < self.counter) {
+            sleep_before_retry(self.retry_interval);
+        }
+    }
+
+    /// Eat for `eat_time` in bites, or less if someone more important waits for one of the forks
+    /// or the check between the bites ends the meal.
+    fn eat(&self, eat_time: Duration) {
+        for bite in 1..=self.bites {
+            // The bites end at even fractions of the meal so they add up to all of it
+            let bite_time = eat_time * bite / self.bites - eat_time * (bite - 1) / self.bites;
+            if self.bite(bite_time) {
+                break;
+            }
+            if bite < self.bites && self.between_bites.as_ref().is_some_and(|between_bites| between_bites(&self.name, bite)) {
+                break;
+            }
+        }
+        if let Some((left_waiting, right_waiting)) = &self.waiting_priorities {
+            // The waiters are served once the forks are released
+            left_waiting.store(0, Ordering::SeqCst);
+            right_waiting.store(0, Ordering::SeqCst);
+        }
+    }
+
+    /// Eat a bite for `bite_time` and tell whether it was cut short by someone more important
+    /// waiting for one of the forks.
+    fn bite(&self, bite_time: Duration) -> bool {
+        let Some((left_waiting, right_waiting)) = &self.waiting_priorities else {
+            pause(bite_time);
+            return false;
+        };
+        let expedited = || left_waiting.load(Ordering::SeqCst).max(right_waiting.load(Ordering::SeqCst)) > self.priority;
+        let start = Instant::now();
+        while cfg!(not(feature = "no-timeouts")) && !expedited() {
+            let left = bite_time.saturating_sub(start.elapsed());
+            if left.is_zero() {
+                return false;
+            }
+            pause(left.min(self.fork_interval));
+        }
+        cfg!(not(feature = "no-timeouts"))
+    }
+
+    /// Sort the forks, with their ids, in the order they are tried ([See][AcquisitionOrder]). With
+    /// [Strategy::AdaptiveOrder] the fork acquired on the larger share of its tries goes first
+    /// and the [AcquisitionOrder] only breaks ties. An explicit fork order comes before both.
+    fn order_forks<'a>(&self, left_fork: &'a dyn Fork<T>, right_fork: &'a dyn Fork<T>) -> (ForkWithId<'a, T>, ForkWithId<'a, T>) {
+        let fixed_left_first = || match self.acquisition_order {
+            AcquisitionOrder::Handedness => self.is_left_handed,
+            AcquisitionOrder::LowerIdFirst => self.left_fork_id < self.right_fork_id,
+            AcquisitionOrder::HigherIdFirst => self.left_fork_id > self.right_fork_id,
+        };
+        let left_first = if let Some(first_fork_id) = self.first_fork_id {
+            first_fork_id == self.left_fork_id
+        } else if self.strategy == Strategy::AdaptiveOrder {
+            // Compare (acquired + 1) / (tried + 2) of both forks so untried forks count as half free
+            let [(left_tried, left_acquired), (right_tried, right_acquired)] = self.fork_successes;
+            match ((left_acquired + 1) * (right_tried + 2)).cmp(&((right_acquired + 1) * (left_tried + 2))) {
+                cmp::Ordering::Greater => true,
+                cmp::Ordering::Less => false,
+                cmp::Ordering::Equal => fixed_left_first(),
+            }
+        } else {
+            fixed_left_first()
+        };
+        let (left, right) = ((left_fork, self.left_fork_id), (right_fork, self.right_fork_id));
+        if left_first {
+            (left, right)
+        } else {
+            (right, left)
+        }
+    }
+
+    /// Wait for each fork for the fork timeout trying every fork interval to get the forks
+    /// ([See][TimeoutLock::try_lock_for]). If the philosopher is left handed begin with the left
+    /// fork instead of the right fork, unless a different [AcquisitionOrder] is used. With
+    /// [AcquisitionStyle::BlockingFirst] the first fork is waited for without a timeout.
+    fn take_forks_in_order<'a>(&mut self, left_fork: &'a dyn Fork<T>, right_fork: &'a dyn Fork<T>) -> Option<(ForkGuard<'a, T>, ForkGuard<'a, T>)> {
+        let ((first_fork, first_id), (second_fork, second_id)) = self.order_forks(left_fork, right_fork);
+        let first_timeout = self.within_round(match self.acquisition_style {
+            AcquisitionStyle::TimeoutFirst => self.fork_timeout,
+            AcquisitionStyle::BlockingFirst => Duration::MAX,
+        });
+        let start = Instant::now();
+        self.publish_waits(&[], Some(first_id));
+        let first = self.contending(first_id, || first_fork.try_lock_for(first_timeout, self.retry_interval));
+        self.record_fork_try(first_id, first.is_ok());
+        // Do not wait for the second fork if the first one could not be acquired
+        let first = self.taken(first, true)?;
+        self.publish_waits(&[first_id], Some(second_id));
+        self.record_fork_wait(first_id, start);
+        let (start, sleeps) = (Instant::now(), poll_counts().sleeps);
+        let second = self.contending(second_id, || second_fork.try_lock_for(self.within_round(self.fork_timeout), self.retry_interval));
+        self.single_fork_holds.push(start.elapsed());
+        self.record_fork_try(second_id, second.is_ok());
+        let second = self.taken(second, false)?;
+        self.record_fork_wait(second_id, start);
+        self.second_fork_retries.record(poll_counts().sleeps - sleeps);
+        Some((first, second))
+    }
+
+    /// Wait for both forks as long as it takes without sleeping ([See][Fork::lock_blocking]), the
+    /// one with the lower id first so the table can not deadlock. The philosophers take their
+    /// forks this way with the `no-timeouts` feature, whatever the strategy, so only the locks
+    /// are measured and not the polling.
+    fn take_forks_blocking<'a>(&mut self, left_fork: &'a dyn Fork<T>, right_fork: &'a dyn Fork<T>) -> Option<(ForkGuard<'a, T>, ForkGuard<'a, T>)> {
+        let (left, right) = ((left_fork, self.left_fork_id), (right_fork, self.right_fork_id));
+        let ((first_fork, first_id), (second_fork, second_id)) = if self.left_fork_id < self.right_fork_id { (left, right) } else { (right, left) };
+        let start = Instant::now();
+        self.publish_waits(&[], Some(first_id));
+        let first = counted_try_lock(|| first_fork.lock_blocking());
+        let first = self.taken(first, true)?;
+        self.publish_waits(&[first_id], Some(second_id));
+        self.record_fork_wait(first_id, start);
+        let start = Instant::now();
+        let second = counted_try_lock(|| second_fork.lock_blocking());
+        self.single_fork_holds.push(start.elapsed());
+        let second = self.taken(second, false)?;
+        self.record_fork_wait(second_id, start);
+        // Waited for without retrying
+        self.second_fork_retries.record(0);
+        Some((first, second))
+    }
+
+    /// Take whichever fork is free first, waiting at most the fork timeout, and hold it at most
+    /// `max_hold` while waiting for the other one. When both are free the [AcquisitionOrder]
+    /// decides.
+    fn take_forks_opportunistically<'a>(&mut self, left_fork: &'a dyn Fork<T>, right_fork: &'a dyn Fork<T>, max_hold: Duration) -> Option<(ForkGuard<'a, T>, ForkGuard<'a, T>)> {
+        let (preferred, other) = self.order_forks(left_fork, right_fork);
+        let fork_timeout = self.within_round(self.fork_timeout);
+        let start = Instant::now();
+        let (first, first_id, (second_fork, second_id)) = loop {
+            if let Ok(guard) = self.contending(preferred.1, || counted_try_lock(|| preferred.0.try_lock())) {
+                break (guard, preferred.1, other);
+            }
+            if let Ok(guard) = self.contending(other.1, || counted_try_lock(|| other.0.try_lock())) {
+                break (guard, other.1, preferred);
+            }
+            if start.elapsed() >= fork_timeout {
+                self.failures.first_fork += 1;
+                return None;
+            }
+            sleep_before_retry(self.retry_interval);
+        };
+        self.publish_waits(&[first_id], Some(second_id));
+        self.record_fork_wait(first_id, start);
+        let (start, sleeps) = (Instant::now(), poll_counts().sleeps);
+        let second = self.contending(second_id, || second_fork.try_lock_for(self.within_round(max_hold), self.retry_interval));
+        self.single_fork_holds.push(start.elapsed());
+        let second = self.taken(second, false)?;
+        self.record_fork_wait(second_id, start);
+        self.second_fork_retries.record(poll_counts().sleeps - sleeps);
+        Some((first, second))
+    }
+
+    /// Check out two forks of `fork_pool` at once, trying every retry interval for at most the
+    /// fork timeout. Nothing is held between the tries so a failure always counts as one on the
+    /// first fork.
+    fn take_pooled_forks<'a>(&mut self, fork_pool: &'a ForkPool<T>) -> Option<[(ForkGuard<'a, T>, usize); 2]> {
+        let fork_timeout = self.within_round(self.fork_timeout);
+        let (start, mut retries) = (Instant::now(), 0);
+        let forks = loop {
+            if let Some(forks) = fork_pool.checkout() {
+                break forks;
+            }
+            if start.elapsed() >= fork_timeout {
+                self.failures.first_fork += 1;
+                return None;
+            }
+            sleep_before_retry(self.retry_interval);
+            retries += 1;
+        };
+        for (_, fork_id) in &forks {
+            self.record_fork_wait(*fork_id, start);
+        }
+        self.second_fork_retries.record(retries);
+        Some(forks)
+    }
+
+    /// Run `acquire`, which tries to take the fork with the id `fork`, reporting each time it
+    /// finds the fork taken to the contention callback if there is one.
+    fn contending<R>(&self, fork: usize, acquire: impl FnOnce() -> R) -> R {
+        match &self.on_contention {
+            Some(on_contention) => reporting_contention(on_contention, &self.name, fork, acquire),
+            None => acquire(),
+        }
+    }
+
+    /// `timeout` shortened to what is left of the budget of the round, if it has one.
+    fn within_round(&self, timeout: Duration) -> Duration {
+        self.round_deadline.map_or(timeout, |deadline| timeout.min(deadline.saturating_duration_since(Instant::now())))
+    }
+
+    /// The guard of a fork that was taken, or `None` after recording why the attempt to eat
+    /// failed on the first or second fork.
+    fn taken<G>(&mut self, result: TryLockResult<G>, first_fork: bool) -> Option<G> {
+        match result {
+            Ok(guard) => Some(guard),
+            Err(TryLockError::Poisoned(_)) => {
+                self.failures.poisoned += 1;
+                None
+            }
+            Err(TryLockError::WouldBlock) if first_fork => {
+                self.failures.first_fork += 1;
+                None
+            }
+            Err(TryLockError::WouldBlock) => {
+                self.failures.second_fork += 1;
+                None
+            }
+        }
+    }
+
+    /// Record the fork `fork_id` was tried with a timeout and whether it was `acquired`.
+    fn record_fork_try(&mut self, fork_id: usize, acquired: bool) {
+        let (tried, successes) = &mut self.fork_successes[usize::from(fork_id != self.left_fork_id)];
+        *tried += 1;
+        *successes += u64::from(acquired);
+    }
+
+    /// Record the fork `fork_id` was acquired after waiting since `start`.
+    fn record_fork_wait(&mut self, fork_id: usize, start: Instant) {
+        let waited = start.elapsed();
+        self.fork_waits.push(waited);
+        *self.fork_acquisitions.entry(fork_id).or_default() += 1;
+        self.record_sample(SampleKind::ForkWait { fork: fork_id }, start, waited);
+        self.observer.on_fork_acquired(&self.name, waited);
+    }
+
+    /// Publish the forks held and the one waited for to the wait-for graph, if any.
+    fn publish_waits(&self, holding: &[usize], waiting: Option<usize>) {
+        if let Some(graph) = &self.wait_for_graph {
+            graph.publish(&self.name, holding, waiting);
+        }
+    }
+
+    /// End the current phase and begin `phase`, if it is another one and the timeline is
+    /// collected.
+    fn enter_phase(&mut self, phase: Phase) {
+        if let Some(live_counters) = &self.live_counters {
+            live_counters.enter(phase);
+        }
+        let Some(timeline) = &mut self.timeline else {
+            return;
+        };
+        let now = Instant::now();
+        match self.phase.replace((phase, now)) {
+            Some((current, since)) if current == phase => self.phase = Some((current, since)),
+            Some((current, since)) => timeline.push((current, since, now)),
+            None => {}
+        }
+    }
+
+    /// Keep the sample while under the limit and aggregate it, if the samples are collected.
+    fn record_sample(&mut self, kind: SampleKind, start: Instant, duration: Duration) {
+        let Some(samples) = &mut self.samples else {
+            return;
+        };
+        if samples.len() < self.sample_limit {
+            samples.push((kind, start, duration));
+        }
+        let stats = match kind {
+            SampleKind::ForkWait { .. } => &mut self.sample_stats.fork_waits,
+            SampleKind::Meal => &mut self.sample_stats.meals,
+        };
+        stats.record(duration);
+    }
+
+    fn notify(&self, kind: EventKind) {
+        self.observer.on_event(&Event::new(&self.name, kind));
+    }
+}
+
+/// A fork of a philosopher and its id.
+type ForkWithId<'a, T> = (&'a dyn Fork<T>, usize);
+
+/// Whether `fork` could be taken, the guard is released before returning so two calls never
+/// hold two forks.
+fn is_free<T>(fork: &dyn Fork<T>) -> bool {
+    fork.try_lock().is_ok()
+}
+
+/// Sleep the current thread unless the `no-timeouts` feature is enabled.
+pub(crate) fn pause(duration: Duration) {
+    if cfg!(not(feature = "no-timeouts")) {
+        thread::sleep(duration);
+    }
+}
+
+/// Reason why a [Philosopher] could not be created or set up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhilosopherError {
+    /// The left and right forks are the same.
+    SameFork,
+    /// The fork order does not have the ids of both forks once each
+    /// ([See][Philosopher::with_fork_order]).
+    InvalidForkOrder(Vec<usize>),
+}
+
+impl fmt::Display for PhilosopherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SameFork => write!(f, "the left and right forks of a philosopher must be different"),
+            Self::InvalidForkOrder(order) => write!(f, "{order:?} is not an order of the forks of the philosopher"),
+        }
+    }
+}
+
+impl Error for PhilosopherError {}
@@ -0,0 +1,315 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::fork::{CountedFork, HeldForks};
+use crate::{
+    AcquisitionStyle, Event, EventKind, FailureCounts, Fork, ForkGuard, ForkStats, PhilosopherStats, RetryHistogram, SampleStats, SeededSource, SimulationConfig, SimulationError, SimulationResult,
+    TimeDistribution,
+};
+
+/// Sit `config.philosophers()` philosophers around a table like [run_simulation][crate::run_simulation]
+/// but share `pool_size` threads between them instead of giving each one its own thread. Each
+/// thread steps through the dine cycle of its philosophers without blocking, so tables much
+/// larger than the number of threads the system can run are possible.
+///
+/// The forks are always taken with a timeout ([Strategy::Timeout][crate::Strategy::Timeout]), in
+/// the acquisition order, or fork order, and style of `config`, and each philosopher eats
+/// `config.meals()` meals. The warmup, time budget, cooldown, hunger deadline, priorities and
+/// satiation target of `config` are not used.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{run_on_pool, SimulationConfig};
+///
+/// let config = SimulationConfig::builder()
+///     .philosophers(100)
+///     .meals(2)
+///     .think_time(Duration::from_millis(1))
+///     .eat_time(Duration::from_millis(1))
+///     .build()
+///     .unwrap();
+/// let result = run_on_pool(&config, 4).unwrap();
+/// assert!(result.is_complete());
+/// assert_eq!(result.total_meals(), 200);
+/// ```
+pub fn run_on_pool(config: &SimulationConfig, pool_size: usize) -> Result<SimulationResult, SimulationError> {
+    let n = config.philosophers();
+    let pool_size = pool_size.clamp(1, n.max(1));
+    let held_forks = Arc::new(HeldForks::default());
+    let forks: Vec<Arc<dyn Fork<()>>> = (0..n).map(|i| Arc::new(CountedFork::new(config.fork_backend().create((), i), i, held_forks.clone())) as _).collect();
+    let start = Instant::now();
+    // Set when a thread could not be spawned so the others stop
+    let abort = AtomicBool::new(false);
+
+    let outcome = thread::scope(|scope| {
+        let mut handles = Vec::with_capacity(pool_size);
+        for thread_index in 0..pool_size {
+            let (forks, abort) = (&forks, &abort);
+            let spawned = thread::Builder::new()
+                .name(format!("Mesa {}", thread_index + 1))
+                .spawn_scoped(scope, move || {
+                    let philosophers = (thread_index..n).step_by(pool_size).map(|i| StepPhilosopher::new(i, config, forks)).collect();
+                    serve(philosophers, config, abort)
+                });
+            match spawned {
+                Ok(handle) => handles.push(handle),
+                Err(error) => {
+                    abort.store(true, Ordering::SeqCst);
+                    return Err(SimulationError::SpawnFailed(error));
+                }
+            }
+        }
+        let mut stats = Vec::with_capacity(n);
+        for (thread_index, handle) in handles.into_iter().enumerate() {
+            match handle.join() {
+                Ok(philosophers) => stats.extend(philosophers),
+                Err(_) => {
+                    // Every philosopher stepped by the thread is lost
+                    for i in (thread_index..n).step_by(pool_size) {
+                        let name = philosopher_name(i);
+                        config.observer().on_event(&Event::new(&name, EventKind::Panicked));
+                        stats.push(Seat {
+                            index: i,
+                            stats: PhilosopherStats { name, meals: 0, panicked: true, satiated: false },
+                            fork_waits: Vec::new(),
+                            single_fork_holds: Vec::new(),
+                            fork_acquisitions: Vec::new(),
+                            failures: FailureCounts::default(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(stats)
+    });
+    let mut seats = outcome?;
+    seats.sort_unstable_by_key(|seat| seat.index);
+
+    let mut fork_waits: Vec<_> = seats.iter().flat_map(|seat| seat.fork_waits.iter().copied()).collect();
+    fork_waits.sort_unstable();
+    let mut single_fork_holds: Vec<_> = seats.iter().flat_map(|seat| seat.single_fork_holds.iter().copied()).collect();
+    single_fork_holds.sort_unstable();
+    let failures = seats.iter().map(|seat| seat.failures).collect();
+    let mut fork_acquisitions = vec![0; n];
+    for &(fork, acquisitions) in seats.iter().flat_map(|seat| &seat.fork_acquisitions) {
+        fork_acquisitions[fork] += acquisitions;
+    }
+    let elapsed = start.elapsed();
+    let result = SimulationResult {
+        philosophers: seats.into_iter().map(|seat| seat.stats).collect(),
+        meals_target: config.meals(),
+        elapsed,
+        fork_waits,
+        single_fork_holds,
+        fork_acquisitions,
+        retry_sleeps: Vec::new(),
+        failures,
+        polls: Vec::new(),
+        first_meals: Vec::new(),
+        samples: Vec::new(),
+        sample_stats: SampleStats::default(),
+        hand_offs: Vec::new(),
+        peak_forks_held: held_forks.peak(),
+        timelines: Vec::new(),
+        cpu_time: None,
+        fork_stats: held_forks.held_times(n).into_iter().map(|held| ForkStats::held_for(held, elapsed)).collect(),
+        second_fork_retries: RetryHistogram::default(),
+        round_progress: Vec::new(),
+        tastes: Vec::new(),
+    };
+    config.observer().on_finish(&result);
+    Ok(result)
+}
+
+/// Step `philosophers` until all of them ate their meals, sleeping while none of them can advance.
+fn serve(mut philosophers: Vec<StepPhilosopher<'_>>, config: &SimulationConfig, abort: &AtomicBool) -> Vec<Seat> {
+    while !abort.load(Ordering::SeqCst) {
+        let now = Instant::now();
+        let Some(next) = philosophers.iter_mut().filter_map(|philosopher| philosopher.step(now, config)).min() else {
+            break;
+        };
+        let wait = next.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            thread::sleep(wait.min(config.fork_interval()));
+        }
+    }
+    philosophers
+        .into_iter()
+        .map(|philosopher| Seat {
+            index: philosopher.index,
+            stats: PhilosopherStats {
+                name: philosopher.name,
+                meals: philosopher.meals,
+                panicked: false,
+                satiated: false,
+            },
+            fork_waits: philosopher.fork_waits,
+            single_fork_holds: philosopher.single_fork_holds,
+            fork_acquisitions: philosopher.fork_ids.into_iter().zip(philosopher.fork_acquisitions).collect(),
+            failures: philosopher.failures,
+        })
+        .collect()
+}
+
+/// What a pool thread reports of each of its philosophers.
+struct Seat {
+    index: usize,
+    stats: PhilosopherStats,
+    fork_waits: Vec<Duration>,
+    single_fork_holds: Vec<Duration>,
+    /// Ids of the forks of the philosopher with the times each one was acquired.
+    fork_acquisitions: Vec<(usize, u64)>,
+    failures: FailureCounts,
+}
+
+fn philosopher_name(index: usize) -> String {
+    format!("Filósofo {}", index + 1)
+}
+
+/// Where a [StepPhilosopher] is in its dine cycle.
+enum Phase<'f> {
+    Thinking { until: Instant },
+    /// Waiting for the first fork, or for the second one while holding the first.
+    Hungry { first: Option<ForkGuard<'f, ()>>, waiting_since: Instant },
+    Eating { until: Instant, _forks: (ForkGuard<'f, ()>, ForkGuard<'f, ()>) },
+    Done,
+}
+
+/// A philosopher whose dine cycle advances one step at a time without blocking.
+struct StepPhilosopher<'f> {
+    index: usize,
+    name: String,
+    /// The fork tried first and the other one.
+    forks: [&'f dyn Fork<()>; 2],
+    fork_ids: [usize; 2],
+    /// Times each of the forks was acquired.
+    fork_acquisitions: [u64; 2],
+    phase: Phase<'f>,
+    meals: u32,
+    fork_waits: Vec<Duration>,
+    /// How long the first fork was held while waiting for the second one.
+    single_fork_holds: Vec<Duration>,
+    failures: FailureCounts,
+    think_time: TimeDistribution,
+    eat_time: TimeDistribution,
+    random: SeededSource,
+}
+
+impl<'f> StepPhilosopher<'f> {
+    fn new(index: usize, config: &SimulationConfig, forks: &'f [Arc<dyn Fork<()>>]) -> Self {
+        let n = forks.len();
+        let (left_id, right_id) = (config.fork_at(index), config.fork_at((index + 1) % n));
+        let (left, right) = (&*forks[left_id], &*forks[right_id]);
+        let left_first = config.takes_left_first(index);
+        let (think_time, eat_time) = config.times_of(index);
+        let mut philosopher = Self {
+            index,
+            name: philosopher_name(index),
+            forks: if left_first { [left, right] } else { [right, left] },
+            fork_ids: if left_first { [left_id, right_id] } else { [right_id, left_id] },
+            fork_acquisitions: [0; 2],
+            phase: Phase::Done,
+            meals: config.initial_meals(index),
+            fork_waits: Vec::new(),
+            single_fork_holds: Vec::new(),
+            failures: FailureCounts::default(),
+            think_time,
+            eat_time,
+            random: SeededSource::new(config.seed().wrapping_add(index as u64)),
+        };
+        if philosopher.meals < config.meals() {
+            philosopher.think(Instant::now(), config);
+        }
+        philosopher
+    }
+
+    /// Advance as far as possible at `now` and return when the philosopher has to be stepped
+    /// again, `None` once it ate all its meals.
+    fn step(&mut self, now: Instant, config: &SimulationConfig) -> Option<Instant> {
+        match &mut self.phase {
+            Phase::Thinking { until } if now < *until => return Some(*until),
+            Phase::Thinking { .. } => {
+                self.phase = Phase::Hungry { first: None, waiting_since: now };
+                return self.step(now, config);
+            }
+            Phase::Hungry { first, waiting_since } => {
+                let position = usize::from(first.is_some());
+                let fork = self.forks[position];
+                let timeout = match config.acquisition_style() {
+                    AcquisitionStyle::BlockingFirst if first.is_none() => Duration::MAX,
+                    _ => config.fork_timeout_for(config.meals().saturating_sub(self.meals)),
+                };
+                let waited = now.saturating_duration_since(*waiting_since);
+                match fork.try_lock() {
+                    Ok(guard) => {
+                        if first.is_some() {
+                            self.single_fork_holds.push(waited);
+                        }
+                        self.fork_waits.push(waited);
+                        self.fork_acquisitions[position] += 1;
+                        config.observer().on_fork_acquired(&self.name, waited);
+                        match first.take() {
+                            None => {
+                                self.phase = Phase::Hungry { first: Some(guard), waiting_since: now };
+                                return self.step(now, config);
+                            }
+                            Some(first) => {
+                                self.notify(config, EventKind::Eating);
+                                let until = now + sleep_time(self.eat_time.sample(&mut self.random));
+                                self.phase = Phase::Eating { until, _forks: (first, guard) };
+                            }
+                        }
+                    }
+                    Err(error) if waited >= timeout => {
+                        match error {
+                            TryLockError::Poisoned(_) => self.failures.poisoned += 1,
+                            TryLockError::WouldBlock if first.is_none() => self.failures.first_fork += 1,
+                            TryLockError::WouldBlock => self.failures.second_fork += 1,
+                        }
+                        if first.is_some() {
+                            self.single_fork_holds.push(waited);
+                        }
+                        // Release the fork held, if any, and think before trying again
+                        self.phase = Phase::Done;
+                        self.notify(config, EventKind::Failed);
+                        self.think(now, config);
+                    }
+                    Err(_) => return Some(now + config.fork_interval()),
+                }
+            }
+            Phase::Eating { until, .. } if now < *until => return Some(*until),
+            Phase::Eating { .. } => {
+                self.phase = Phase::Done;
+                self.meals += 1;
+                self.notify(config, EventKind::Ate { meals: self.meals });
+                self.notify(config, EventKind::DoneEating);
+                if self.meals >= config.meals() {
+                    return None;
+                }
+                self.think(now, config);
+            }
+            Phase::Done => return None,
+        }
+        Some(now)
+    }
+
+    fn think(&mut self, now: Instant, config: &SimulationConfig) {
+        self.notify(config, EventKind::Thinking);
+        let until = now + sleep_time(self.think_time.sample(&mut self.random));
+        self.phase = Phase::Thinking { until };
+    }
+
+    fn notify(&self, config: &SimulationConfig, kind: EventKind) {
+        config.observer().on_event(&Event::new(&self.name, kind));
+    }
+}
+
+/// How long a think or eat phase lasts, zero with the `no-timeouts` feature.
+fn sleep_time(duration: Duration) -> Duration {
+    if cfg!(feature = "no-timeouts") {
+        Duration::ZERO
+    } else {
+        duration
+    }
+}
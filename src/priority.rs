@@ -0,0 +1,85 @@
+use std::io;
+
+/// Set the nice value of the current thread, from `-20`, the most favored by the scheduler, to
+/// `19`, the least.
+///
+/// Only Linux is supported, where each thread has its own nice value. Raising the nice value is
+/// always allowed but lowering it below the one of the process needs the `CAP_SYS_NICE`
+/// capability, and the effect on the meals depends on the scheduler: with fewer philosophers
+/// than CPUs every thread runs anyway. Elsewhere it fails with [io::ErrorKind::Unsupported] and
+/// nothing changes.
+/// ```rust
+/// use std::thread;
+/// use actividad_12::{set_thread_niceness, thread_niceness};
+///
+/// thread::spawn(|| {
+///     let nice = thread_niceness().map_or(1, |nice| (nice + 1).min(19));
+///     match set_thread_niceness(nice) {
+///         Ok(()) => assert_eq!(thread_niceness(), Some(nice)),
+///         // Nothing changes where it is unsupported
+///         Err(error) => assert_eq!(error.kind(), std::io::ErrorKind::Unsupported),
+///     }
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+pub fn set_thread_niceness(nice: i32) -> io::Result<()> {
+    sys::set_niceness(nice)
+}
+
+/// Nice value of the current thread, `None` where it is unsupported.
+pub fn thread_niceness() -> Option<i32> {
+    sys::niceness()
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::ffi::{c_int, c_uint};
+    use std::io;
+
+    const PRIO_PROCESS: c_int = 0;
+
+    extern "C" {
+        fn gettid() -> c_int;
+        fn getpriority(which: c_int, who: c_uint) -> c_int;
+        fn setpriority(which: c_int, who: c_uint, prio: c_int) -> c_int;
+        fn __errno_location() -> *mut c_int;
+    }
+
+    /// With `PRIO_PROCESS` and the id of a thread the priority is the one of that thread alone.
+    fn thread_id() -> c_uint {
+        // SAFETY: gettid has no preconditions and always succeeds
+        unsafe { gettid() as c_uint }
+    }
+
+    pub(super) fn set_niceness(nice: i32) -> io::Result<()> {
+        // SAFETY: setpriority only reads its arguments
+        if unsafe { setpriority(PRIO_PROCESS, thread_id(), nice) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub(super) fn niceness() -> Option<i32> {
+        // -1 is also a valid nice value, so errno tells apart the failures
+        // SAFETY: errno is thread local and __errno_location always returns a valid pointer to it
+        unsafe {
+            *__errno_location() = 0;
+            let nice = getpriority(PRIO_PROCESS, thread_id());
+            (nice != -1 || *__errno_location() == 0).then_some(nice)
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::io;
+
+    pub(super) fn set_niceness(_nice: i32) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "the priority of a thread can only be set on Linux"))
+    }
+
+    pub(super) fn niceness() -> Option<i32> {
+        None
+    }
+}
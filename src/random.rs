@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+use std::panic::Location;
+
+/// Small seeded pseudo random number generator (SplitMix64), the same seed always produces the
+/// same sequence of numbers.
+///
+/// To debug a run it can keep every number drawn with where it was drawn
+/// ([See][SeededSource::recording]) and a later source can replay them.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{SeededSource, TimeDistribution};
+///
+/// let think_time = TimeDistribution::Exponential { mean: Duration::from_millis(10) };
+/// let eat_time = TimeDistribution::Uniform { min: Duration::from_millis(5), max: Duration::from_millis(15) };
+/// let mut random = SeededSource::new(42).recording();
+/// let times: Vec<_> = (0..10).map(|_| (think_time.sample(&mut random), eat_time.sample(&mut random))).collect();
+/// let draws = random.draws().unwrap();
+/// assert_eq!(draws.len(), 20);
+/// assert!(draws[0].location.file().ends_with(".rs"));
+///
+/// // The draws replayed give the same times whatever the seed
+/// let mut replay = SeededSource::replaying(draws.iter().map(|draw| draw.value));
+/// let replayed: Vec<_> = (0..10).map(|_| (think_time.sample(&mut replay), eat_time.sample(&mut replay))).collect();
+/// assert_eq!(replayed, times);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SeededSource {
+    state: u64,
+    /// Every number drawn, only kept when it is `Some`.
+    draws: Option<Vec<Draw>>,
+    /// Numbers returned before the generator is used.
+    replay: VecDeque<u64>,
+}
+
+/// A number drawn from a [SeededSource] and the code that drew it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Draw {
+    pub value: u64,
+    pub location: &'static Location<'static>,
+}
+
+impl SeededSource {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            draws: None,
+            replay: VecDeque::new(),
+        }
+    }
+
+    /// Return `values` before drawing from the generator, to reproduce a run with the draws of
+    /// another source ([See][SeededSource::draws]). Once they are used up it continues as a
+    /// source with seed `0`.
+    pub fn replaying(values: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            replay: values.into_iter().collect(),
+            ..Self::new(0)
+        }
+    }
+
+    /// Keep every number drawn from now on ([See][SeededSource::draws]).
+    pub fn recording(mut self) -> Self {
+        self.draws = Some(Vec::new());
+        self
+    }
+
+    /// The numbers drawn since [SeededSource::recording] was called, `None` if it was not.
+    pub fn draws(&self) -> Option<&[Draw]> {
+        self.draws.as_deref()
+    }
+
+    #[track_caller]
+    pub fn next_u64(&mut self) -> u64 {
+        let value = self.replay.pop_front().unwrap_or_else(|| self.generate());
+        if let Some(draws) = &mut self.draws {
+            draws.push(Draw {
+                value,
+                location: Location::caller(),
+            });
+        }
+        value
+    }
+
+    /// A number in `[0, 1)`.
+    #[track_caller]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn generate(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,297 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::Duration;
+
+use crate::{
+    AcquisitionStyle, Event, EventKind, FailureCounts, ForkStats, PhilosopherStats, RetryHistogram, SampleStats, SeededSource, SimulationConfig, SimulationResult, TimeDistribution,
+};
+
+/// Run `config` in a single thread on a virtual clock, with the times of philosopher `i` drawn
+/// from `seed + i` instead of the seed of `config`, so the same config and seed always give the
+/// same result and the same events in the same order, on any machine. Nothing sleeps, the clock
+/// jumps to the next moment someone has something to do and the philosophers due at the same
+/// moment go in the order they sit at the table.
+///
+/// The forks are taken like [run_on_pool][crate::run_on_pool] takes them, polling every fork
+/// interval for at most the fork timeout, in the acquisition order, or fork order, and style of
+/// `config`. Its times, meals, initial meals, fork ids and time budget, measured on the virtual
+/// clock, are used, the rest of the config is not. The [Event::logical_time] of the events
+/// counts from zero on each run.
+///
+/// Philosophers that keep taking their first forks at the same moments may never eat, a time
+/// budget ends such a run with the partial result.
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+/// use actividad_12::{run_reproducible, Event, Observer, SimulationConfig, TimeDistribution};
+///
+/// #[derive(Default)]
+/// struct Events(Mutex<Vec<Event>>);
+///
+/// impl Observer for Events {
+///     fn on_event(&self, event: &Event) {
+///         self.0.lock().unwrap().push(event.clone());
+///     }
+/// }
+///
+/// let run = |seed| {
+///     let events = Arc::new(Events::default());
+///     let config = SimulationConfig::builder()
+///         .philosophers(5)
+///         .meals(10)
+///         .think_distribution(TimeDistribution::Exponential { mean: Duration::from_millis(5) })
+///         .eat_distribution(TimeDistribution::Uniform { min: Duration::from_millis(1), max: Duration::from_millis(10) })
+///         .observer(events.clone())
+///         .build()
+///         .unwrap();
+///     let result = run_reproducible(&config, seed);
+///     assert!(result.is_complete());
+///     let events = events.0.lock().unwrap().clone();
+///     (format!("{result:?}"), events)
+/// };
+/// let (result, events) = run(7);
+/// assert_eq!(run(7), (result.clone(), events.clone()));
+/// assert_eq!(events[0].logical_time, 0);
+/// assert_ne!(run(8).0, result);
+/// ```
+pub fn run_reproducible(config: &SimulationConfig, seed: u64) -> SimulationResult {
+    let n = config.philosophers();
+    let mut table = VirtualTable {
+        config,
+        now: Duration::ZERO,
+        taken: vec![None; n],
+        held: vec![Duration::ZERO; n],
+        held_now: 0,
+        peak_held: 0,
+        tick: 0,
+    };
+    let mut philosophers: Vec<_> = (0..n).map(|i| VirtualPhilosopher::new(i, config, seed)).collect();
+    // Each philosopher has one wake up pending, the earliest first and then by position
+    let mut wakes = BinaryHeap::new();
+    for (i, philosopher) in philosophers.iter_mut().enumerate() {
+        if philosopher.meals < config.meals() {
+            wakes.push(Reverse((philosopher.think(&mut table), i)));
+        }
+    }
+    while let Some(Reverse((at, i))) = wakes.pop() {
+        if config.time_budget().is_some_and(|budget| at > budget) {
+            break;
+        }
+        table.now = at;
+        if let Some(next) = philosophers[i].step(&mut table) {
+            wakes.push(Reverse((next, i)));
+        }
+    }
+
+    let elapsed = table.now;
+    let mut fork_waits: Vec<_> = philosophers.iter().flat_map(|philosopher| philosopher.fork_waits.iter().copied()).collect();
+    fork_waits.sort_unstable();
+    let mut single_fork_holds: Vec<_> = philosophers.iter().flat_map(|philosopher| philosopher.single_fork_holds.iter().copied()).collect();
+    single_fork_holds.sort_unstable();
+    let mut fork_acquisitions = vec![0; n];
+    let mut second_fork_retries = RetryHistogram::default();
+    for philosopher in &philosophers {
+        for (fork, acquisitions) in philosopher.fork_ids.into_iter().zip(philosopher.fork_acquisitions) {
+            fork_acquisitions[fork] += acquisitions;
+        }
+        second_fork_retries.merge(&philosopher.second_fork_retries);
+    }
+    let result = SimulationResult {
+        philosophers: philosophers
+            .iter()
+            .map(|philosopher| PhilosopherStats {
+                name: philosopher.name.clone(),
+                meals: philosopher.meals,
+                panicked: false,
+                satiated: false,
+            })
+            .collect(),
+        meals_target: config.meals(),
+        elapsed,
+        fork_waits,
+        single_fork_holds,
+        fork_acquisitions,
+        retry_sleeps: philosophers.iter().map(|philosopher| philosopher.retry_sleep).collect(),
+        failures: philosophers.iter().map(|philosopher| philosopher.failures).collect(),
+        polls: Vec::new(),
+        first_meals: philosophers.iter().map(|philosopher| philosopher.first_meal).collect(),
+        samples: Vec::new(),
+        sample_stats: SampleStats::default(),
+        hand_offs: Vec::new(),
+        peak_forks_held: table.peak_held,
+        timelines: Vec::new(),
+        cpu_time: None,
+        fork_stats: table.held.iter().map(|&held| ForkStats::held_for(held, elapsed)).collect(),
+        second_fork_retries,
+        round_progress: Vec::new(),
+        tastes: Vec::new(),
+    };
+    config.observer().on_finish(&result);
+    result
+}
+
+/// The forks of a [run_reproducible] and its virtual clock.
+struct VirtualTable<'c> {
+    config: &'c SimulationConfig,
+    now: Duration,
+    /// When each fork was taken, `None` while it is free.
+    taken: Vec<Option<Duration>>,
+    /// Time each fork was held so far.
+    held: Vec<Duration>,
+    /// Forks held right now and the most held at once.
+    held_now: usize,
+    peak_held: usize,
+    /// Logical time of the next event.
+    tick: u64,
+}
+
+impl VirtualTable<'_> {
+    /// Take `fork` if it is free.
+    fn take(&mut self, fork: usize) -> bool {
+        if self.taken[fork].is_some() {
+            return false;
+        }
+        self.taken[fork] = Some(self.now);
+        self.held_now += 1;
+        self.peak_held = self.peak_held.max(self.held_now);
+        true
+    }
+
+    fn release(&mut self, fork: usize) {
+        if let Some(since) = self.taken[fork].take() {
+            self.held[fork] += self.now - since;
+            self.held_now -= 1;
+        }
+    }
+
+    fn notify(&mut self, philosopher: &str, kind: EventKind) {
+        let event = Event { philosopher: philosopher.to_string(), kind, logical_time: self.tick };
+        self.tick += 1;
+        self.config.observer().on_event(&event);
+    }
+}
+
+/// Where a [VirtualPhilosopher] is in its dine cycle.
+enum VirtualPhase {
+    Thinking,
+    /// Waiting for the first fork, or for the second one while holding the first, after
+    /// `retries` retries.
+    Hungry { holding_first: bool, waiting_since: Duration, retries: u64 },
+    Eating,
+    Done,
+}
+
+/// A philosopher of [run_reproducible], stepped each time its wake up is due.
+struct VirtualPhilosopher {
+    name: String,
+    /// The fork tried first and the other one.
+    fork_ids: [usize; 2],
+    /// Times each of the forks was acquired.
+    fork_acquisitions: [u64; 2],
+    phase: VirtualPhase,
+    meals: u32,
+    fork_waits: Vec<Duration>,
+    /// How long the first fork was held while waiting for the second one.
+    single_fork_holds: Vec<Duration>,
+    retry_sleep: Duration,
+    failures: FailureCounts,
+    first_meal: Option<Duration>,
+    second_fork_retries: RetryHistogram,
+    think_time: TimeDistribution,
+    eat_time: TimeDistribution,
+    random: SeededSource,
+}
+
+impl VirtualPhilosopher {
+    fn new(index: usize, config: &SimulationConfig, seed: u64) -> Self {
+        let n = config.philosophers();
+        let (left_id, right_id) = (config.fork_at(index), config.fork_at((index + 1) % n));
+        let (think_time, eat_time) = config.times_of(index);
+        Self {
+            name: format!("Filósofo {}", index + 1),
+            fork_ids: if config.takes_left_first(index) { [left_id, right_id] } else { [right_id, left_id] },
+            fork_acquisitions: [0; 2],
+            phase: VirtualPhase::Done,
+            meals: config.initial_meals(index),
+            fork_waits: Vec::new(),
+            single_fork_holds: Vec::new(),
+            retry_sleep: Duration::ZERO,
+            failures: FailureCounts::default(),
+            first_meal: None,
+            second_fork_retries: RetryHistogram::default(),
+            think_time,
+            eat_time,
+            random: SeededSource::new(seed.wrapping_add(index as u64)),
+        }
+    }
+
+    /// Advance as far as possible at the time of `table` and return when the philosopher has to
+    /// be stepped again, `None` once it ate all its meals.
+    fn step(&mut self, table: &mut VirtualTable<'_>) -> Option<Duration> {
+        let config = table.config;
+        let now = table.now;
+        match &mut self.phase {
+            VirtualPhase::Thinking => {
+                self.phase = VirtualPhase::Hungry { holding_first: false, waiting_since: now, retries: 0 };
+                self.step(table)
+            }
+            VirtualPhase::Hungry { holding_first, waiting_since, retries } => {
+                let (holding_first, waited, retries_so_far) = (*holding_first, now - *waiting_since, *retries);
+                let position = usize::from(holding_first);
+                let timeout = match config.acquisition_style() {
+                    AcquisitionStyle::BlockingFirst if !holding_first => Duration::MAX,
+                    _ => config.fork_timeout_for(config.meals().saturating_sub(self.meals)),
+                };
+                if table.take(self.fork_ids[position]) {
+                    self.fork_waits.push(waited);
+                    self.fork_acquisitions[position] += 1;
+                    config.observer().on_fork_acquired(&self.name, waited);
+                    if !holding_first {
+                        self.phase = VirtualPhase::Hungry { holding_first: true, waiting_since: now, retries: 0 };
+                        return self.step(table);
+                    }
+                    self.single_fork_holds.push(waited);
+                    self.second_fork_retries.record(retries_so_far);
+                    self.first_meal.get_or_insert(now);
+                    table.notify(&self.name, EventKind::Eating);
+                    self.phase = VirtualPhase::Eating;
+                    Some(now + self.eat_time.sample(&mut self.random))
+                } else if waited >= timeout {
+                    if holding_first {
+                        self.failures.second_fork += 1;
+                        self.single_fork_holds.push(waited);
+                        table.release(self.fork_ids[0]);
+                    } else {
+                        self.failures.first_fork += 1;
+                    }
+                    table.notify(&self.name, EventKind::Failed);
+                    Some(self.think(table))
+                } else {
+                    *retries += 1;
+                    self.retry_sleep += config.fork_interval();
+                    Some(now + config.fork_interval())
+                }
+            }
+            VirtualPhase::Eating => {
+                table.release(self.fork_ids[0]);
+                table.release(self.fork_ids[1]);
+                self.meals += 1;
+                table.notify(&self.name, EventKind::Ate { meals: self.meals });
+                table.notify(&self.name, EventKind::DoneEating);
+                if self.meals >= config.meals() {
+                    self.phase = VirtualPhase::Done;
+                    return None;
+                }
+                Some(self.think(table))
+            }
+            VirtualPhase::Done => None,
+        }
+    }
+
+    /// Begin thinking and return when the philosopher gets hungry.
+    fn think(&mut self, table: &mut VirtualTable<'_>) -> Duration {
+        table.notify(&self.name, EventKind::Thinking);
+        self.phase = VirtualPhase::Thinking;
+        table.now + self.think_time.sample(&mut self.random)
+    }
+}
@@ -0,0 +1,802 @@
+use std::iter::Sum;
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+use crate::{RetryHistogram, RunningStats, SampleStats};
+
+/// Meals eaten by one philosopher during a simulation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhilosopherStats {
+    pub name: String,
+    pub meals: u32,
+    /// The thread of the philosopher panicked, so its meals are unknown.
+    pub panicked: bool,
+    /// The philosopher reached the satiation target before eating all its meals
+    /// ([See][crate::SimulationConfigBuilder::satiation_target]).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub satiated: bool,
+}
+
+/// Why the failed attempts of a philosopher to eat failed.
+/// ```rust
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use actividad_12::{run_simulation, Event, EventKind, Observer, SimulationConfig};
+///
+/// #[derive(Default)]
+/// struct Failures(AtomicU64);
+///
+/// impl Observer for Failures {
+///     fn on_event(&self, event: &Event) {
+///         if event.kind == EventKind::Failed {
+///             self.0.fetch_add(1, Ordering::SeqCst);
+///         }
+///     }
+/// }
+///
+/// let failures = Arc::new(Failures::default());
+/// // The forks are waited for much less than a meal lasts
+/// let config = SimulationConfig::builder()
+///     .philosophers(5)
+///     .meals(3)
+///     .think_time(Duration::ZERO)
+///     .eat_time(Duration::from_millis(10))
+///     .fork_timeout(Duration::from_millis(2))
+///     .fork_interval(Duration::from_millis(1))
+///     .observer(failures.clone())
+///     .build()
+///     .unwrap();
+/// let result = run_simulation(&config).unwrap();
+/// let total: u64 = result.failures.iter().map(|failures| failures.total()).sum();
+/// assert_eq!(total, failures.0.load(Ordering::SeqCst));
+/// # #[cfg(not(feature = "no-timeouts"))]
+/// assert!(total > 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FailureCounts {
+    /// The first fork could not be taken.
+    pub first_fork: u64,
+    /// The first fork was taken but the wait for the second one timed out.
+    pub second_fork: u64,
+    /// A fork was poisoned, its previous holder panicked.
+    pub poisoned: u64,
+    /// The round ran out of its budget before the meal could end
+    /// ([See][crate::SimulationConfigBuilder::round_budget]).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub over_budget: u64,
+    /// The forks could not be reserved within the fork timeout
+    /// ([See][crate::Strategy::Reservation]).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub not_reserved: u64,
+}
+
+impl FailureCounts {
+    pub fn total(&self) -> u64 {
+        self.first_fork + self.second_fork + self.poisoned + self.over_budget + self.not_reserved
+    }
+}
+
+/// How often a philosopher polled its forks, the overhead of taking them with a timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PollCounts {
+    /// Sleeps between the retries to take a fork.
+    pub sleeps: u64,
+    /// Calls to `try_lock` on the forks, including the ones that took them.
+    pub try_locks: u64,
+}
+
+impl Add for PollCounts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            sleeps: self.sleeps + other.sleeps,
+            try_locks: self.try_locks + other.try_locks,
+        }
+    }
+}
+
+impl Sub for PollCounts {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            sleeps: self.sleeps - other.sleeps,
+            try_locks: self.try_locks - other.try_locks,
+        }
+    }
+}
+
+impl Sum for PollCounts {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// How long a fork was held by the philosophers and how long it sat idle during a simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForkStats {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub held: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub idle: Duration,
+}
+
+impl ForkStats {
+    /// A fork held for `held` during a simulation that took `elapsed`, idle the rest of the time.
+    pub(crate) fn held_for(held: Duration, elapsed: Duration) -> Self {
+        Self { held, idle: elapsed.saturating_sub(held) }
+    }
+}
+
+/// Outcome of a [Simulation][crate::Simulation]. With the `serde` feature it can be serialized,
+/// every [Duration] is written as a number of milliseconds.
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use std::time::Duration;
+/// use actividad_12::{run_simulation, SimulationConfig, SimulationResult};
+///
+/// let config = SimulationConfig::builder()
+///     .philosophers(3)
+///     .meals(2)
+///     .think_time(Duration::from_millis(1))
+///     .eat_time(Duration::from_millis(1))
+///     .collect_samples(true)
+///     .build()
+///     .unwrap();
+/// let result = run_simulation(&config).unwrap();
+/// let json = serde_json::to_string(&result).unwrap();
+/// let restored: SimulationResult = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, result);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationResult {
+    /// Stats of each philosopher in the order they sit at the table.
+    pub philosophers: Vec<PhilosopherStats>,
+    /// Meals each philosopher had to eat.
+    pub meals_target: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub elapsed: Duration,
+    /// Time waited for every fork acquired by any philosopher, from the shortest to the longest.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis::vec"))]
+    pub fork_waits: Vec<Duration>,
+    /// How long a philosopher held one fork while waiting for the other one, on each attempt
+    /// that got the first fork. [ConflictGraph][crate::ConflictGraph] does not measure it.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_millis::vec"))]
+    pub single_fork_holds: Vec<Duration>,
+    /// Times each fork was acquired, the position is the id of the fork.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fork_acquisitions: Vec<u64>,
+    /// Time each philosopher slept between the retries to take its forks, in the order they sit
+    /// at the table. [run_on_pool][crate::run_on_pool] does not measure it since its threads sleep
+    /// for many philosophers at once.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_millis::vec"))]
+    pub retry_sleeps: Vec<Duration>,
+    /// Why the attempts to eat of each philosopher failed, in the order they sit at the table.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub failures: Vec<FailureCounts>,
+    /// How often each philosopher polled its forks, in the order they sit at the table. Only
+    /// counted by [run_simulation][crate::run_simulation] with
+    /// [SimulationConfigBuilder::profile][crate::SimulationConfigBuilder::profile].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub polls: Vec<PollCounts>,
+    /// When each philosopher began its first meal since the start of the simulation, `None` if
+    /// it never ate, in the order they sit at the table. A long time reveals a philosopher that
+    /// lost the first fights for the forks. [ConflictGraph][crate::ConflictGraph] and
+    /// [run_on_pool][crate::run_on_pool] do not measure it.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(5)
+    ///     .meals(2)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert_eq!(result.first_meals.len(), 5);
+    /// for (stats, first_meal) in result.philosophers.iter().zip(&result.first_meals) {
+    ///     assert_eq!(stats.meals, 2);
+    ///     let first_meal = first_meal.unwrap();
+    ///     assert!(first_meal < result.elapsed);
+    ///     # #[cfg(not(feature = "no-timeouts"))]
+    ///     assert!(first_meal > Duration::ZERO);
+    /// }
+    /// ```
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_millis::option_vec"))]
+    pub first_meals: Vec<Option<Duration>>,
+    /// Every fork wait and meal in the order they began, only collected with
+    /// [SimulationConfigBuilder::collect_samples][crate::SimulationConfigBuilder::collect_samples]
+    /// and up to [SimulationConfigBuilder::max_samples][crate::SimulationConfigBuilder::max_samples].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub samples: Vec<Sample>,
+    /// Aggregates of every sample collected, including the ones over the limit.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sample_stats: SampleStats,
+    /// Hand-off latencies of each fork ([See][crate::HandOffFork]), the position is the id of
+    /// the fork. Only measured by [run_simulation][crate::run_simulation] with
+    /// [SimulationConfigBuilder::measure_hand_offs][crate::SimulationConfigBuilder::measure_hand_offs].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hand_offs: Vec<RunningStats>,
+    /// Most forks held at the same time, never more than the forks of the table. Eating takes
+    /// two forks so it is at least `2` once anyone ate.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(5)
+    ///     .meals(5)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(2 <= result.peak_forks_held && result.peak_forks_held <= 5);
+    /// ```
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub peak_forks_held: usize,
+    /// Phases each philosopher went through, by position at the table, only collected with
+    /// [SimulationConfigBuilder::collect_timeline][crate::SimulationConfigBuilder::collect_timeline].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub timelines: Vec<Vec<PhaseSpan>>,
+    /// CPU time the threads of the philosophers consumed together, from when they were spawned
+    /// and including the warmup, while [SimulationResult::elapsed] is the wall time. Only measured
+    /// by [run_simulation][crate::run_simulation] with the `cpu-time` feature where it is
+    /// supported (see `thread_cpu_time`), `None` otherwise.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(20)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// # #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+    /// # {
+    /// assert!(result.cpu_time.unwrap() > Duration::ZERO);
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// // Most of the time is spent sleeping
+    /// assert!(result.wall_cpu_ratio().unwrap() > 1.0);
+    /// # }
+    /// # #[cfg(not(feature = "cpu-time"))]
+    /// assert_eq!(result.cpu_time, None);
+    /// ```
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_millis::option"))]
+    pub cpu_time: Option<Duration>,
+    /// Time each fork was held and sat idle, the position is the id of the fork. The time held
+    /// includes the warmup.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fork_stats: Vec<ForkStats>,
+    /// Retries the philosophers needed to take their second fork, on each attempt that got it,
+    /// how hard they worked to eat. [ConflictGraph][crate::ConflictGraph] and
+    /// [run_on_pool][crate::run_on_pool] do not measure it.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig, TimeDistribution};
+    ///
+    /// let retries = |think_time, eat_time| {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(5)
+    ///         .meals(10)
+    ///         .think_distribution(think_time)
+    ///         .eat_time(eat_time)
+    ///         .fork_timeout(Duration::from_millis(50))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .build()
+    ///         .unwrap();
+    ///     let result = run_simulation(&config).unwrap();
+    ///     assert_eq!(result.second_fork_retries.total(), u64::from(result.total_meals()));
+    ///     result.second_fork_retries
+    /// };
+    /// // Philosophers that rarely meet find their second fork free at once
+    /// let low = retries(TimeDistribution::Uniform { min: Duration::ZERO, max: Duration::from_millis(40) }, Duration::from_micros(100));
+    /// assert!(low.share_without_retries() > 0.8, "{low:?}");
+    /// // Hungry ones sometimes wait for it to be released
+    /// let high = retries(TimeDistribution::Constant(Duration::ZERO), Duration::from_millis(3));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(high.mean() > low.mean() && high.counts().len() > 2, "{high:?}");
+    /// ```
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub second_fork_retries: RetryHistogram,
+    /// Whether anyone ate in each round, only with
+    /// [SimulationConfigBuilder::rounds][crate::SimulationConfigBuilder::rounds] and
+    /// [run_simulation][crate::run_simulation].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub round_progress: Vec<bool>,
+    /// Times each philosopher tasted with one fork, in the order they sit at the table, apart
+    /// from its meals ([See][crate::SimulationConfigBuilder::taste]). Only counted by
+    /// [run_simulation][crate::run_simulation] and [Table][crate::Table].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tastes: Vec<u32>,
+}
+
+impl SimulationResult {
+    pub fn total_meals(&self) -> u32 {
+        self.philosophers.iter().map(|stats| stats.meals).sum()
+    }
+
+    /// Check in debug builds that the meals of the result add up to the meal counters of the
+    /// philosophers it was built from, to catch a meal lost or counted twice on the way.
+    pub(crate) fn debug_assert_meals(&self, counters: &[u32]) {
+        debug_assert_eq!(
+            self.total_meals(),
+            counters.iter().sum::<u32>(),
+            "the meals of the result do not add up to the meal counters of the philosophers"
+        );
+    }
+
+    /// Whether every philosopher ate all its meals, or was satiated, without panicking.
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Event, EventKind, Observer, SimulationConfig, SimulationError};
+    ///
+    /// struct PanicWhileThinking;
+    ///
+    /// impl Observer for PanicWhileThinking {
+    ///     fn on_event(&self, event: &Event) {
+    ///         if event.philosopher == "Filósofo 1" && event.kind == EventKind::Thinking {
+    ///             panic!("se le cayó el tenedor");
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let table = || SimulationConfig::builder().philosophers(3).meals(3).think_time(Duration::ZERO).eat_time(Duration::from_millis(5));
+    /// assert!(run_simulation(&table().build().unwrap()).unwrap().is_complete());
+    ///
+    /// let result = run_simulation(&table().observer(Arc::new(PanicWhileThinking)).build().unwrap()).unwrap();
+    /// assert!(!result.is_complete());
+    ///
+    /// // The time budget runs out long before the meals are eaten
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// # {
+    /// let config = table().meals(1000).time_budget(Duration::from_millis(50)).build().unwrap();
+    /// let Err(SimulationError::BudgetExceeded(result)) = run_simulation(&config) else {
+    ///     panic!("the time budget should run out");
+    /// };
+    /// assert!(!result.is_complete());
+    /// # }
+    /// ```
+    pub fn is_complete(&self) -> bool {
+        self.philosophers
+            .iter()
+            .all(|stats| !stats.panicked && (stats.meals >= self.meals_target || stats.satiated))
+    }
+
+    /// Gini coefficient of the meals of the philosophers, `0.0` when all of them ate the same and
+    /// `(n - 1) / n` when one of the `n` philosophers ate everything.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{PhilosopherStats, RetryHistogram, SampleStats, SimulationResult};
+    ///
+    /// let result = |meals: &[u32]| SimulationResult {
+    ///     philosophers: meals.iter().map(|&meals| PhilosopherStats { name: String::new(), meals, panicked: false, satiated: false }).collect(),
+    ///     meals_target: 0,
+    ///     elapsed: Duration::ZERO,
+    ///     fork_waits: Vec::new(),
+    ///     single_fork_holds: Vec::new(),
+    ///     fork_acquisitions: Vec::new(),
+    ///     retry_sleeps: Vec::new(),
+    ///     failures: Vec::new(),
+    ///     polls: Vec::new(),
+    ///     first_meals: Vec::new(),
+    ///     samples: Vec::new(),
+    ///     sample_stats: SampleStats::default(),
+    ///     hand_offs: Vec::new(),
+    ///     peak_forks_held: 0,
+    ///     timelines: Vec::new(),
+    ///     cpu_time: None,
+    ///     fork_stats: Vec::new(),
+    ///     second_fork_retries: RetryHistogram::default(),
+    ///     round_progress: Vec::new(),
+    ///     tastes: Vec::new(),
+    /// };
+    /// assert_eq!(result(&[3, 3, 3, 3]).gini(), 0.0);
+    /// assert!((result(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 10]).gini() - 0.9).abs() < 1e-9);
+    /// assert!((result(&[1, 2, 3, 4]).gini() - 0.25).abs() < 1e-9);
+    /// ```
+    pub fn gini(&self) -> f64 {
+        let mut meals: Vec<u32> = self.philosophers.iter().map(|stats| stats.meals).collect();
+        meals.sort_unstable();
+        let n = meals.len() as f64;
+        let total: f64 = meals.iter().map(|&meals| f64::from(meals)).sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        // Each count weighted by its rank, from 1 for the smallest
+        let weighted: f64 = meals.iter().zip(1..).map(|(&meals, rank)| f64::from(rank) * f64::from(meals)).sum();
+        2.0 * weighted / (n * total) - (n + 1.0) / n
+    }
+
+    /// Jain's fairness index of the meals, `1.0` when every philosopher ate the same and `1 / n`
+    /// when one of the `n` philosophers ate everything.
+    pub fn fairness(&self) -> f64 {
+        let meals: Vec<f64> = self.philosophers.iter().map(|stats| f64::from(stats.meals)).collect();
+        let squares: f64 = meals.iter().map(|meals| meals * meals).sum();
+        if squares == 0.0 {
+            return 1.0;
+        }
+        meals.iter().sum::<f64>().powi(2) / (meals.len() as f64 * squares)
+    }
+
+    /// Meals eaten per second of elapsed time, `0.0` if no time elapsed.
+    pub fn throughput(&self) -> f64 {
+        let elapsed = self.elapsed.as_secs_f64();
+        if elapsed == 0.0 {
+            return 0.0;
+        }
+        f64::from(self.total_meals()) / elapsed
+    }
+
+    /// One line of `key=value` pairs separated by spaces, to track the performance from a
+    /// script: the meals, the elapsed milliseconds, the meals per second and the
+    /// [fairness][SimulationResult::fairness]. It is what `--bench-output` prints.
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(10)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let line = run_simulation(&config).unwrap().bench_line();
+    /// let values: HashMap<&str, f64> = line
+    ///     .split(' ')
+    ///     .map(|pair| {
+    ///         let (key, value) = pair.split_once('=').unwrap();
+    ///         (key, value.parse().unwrap())
+    ///     })
+    ///     .collect();
+    /// for key in ["meals", "elapsed_ms", "throughput", "fairness"] {
+    ///     assert!(values.contains_key(key), "{key} is missing from {line}");
+    /// }
+    /// assert_eq!(values.len(), 4);
+    /// assert_eq!(values["meals"], 30.0);
+    /// assert_eq!(values["fairness"], 1.0);
+    /// ```
+    pub fn bench_line(&self) -> String {
+        format!(
+            "meals={} elapsed_ms={} throughput={:.0} fairness={:.2}",
+            self.total_meals(),
+            self.elapsed.as_millis(),
+            self.throughput(),
+            self.fairness()
+        )
+    }
+
+    /// Name and meals of each philosopher, from the one that ate the most to the one that ate
+    /// the least. Philosophers with the same meals are sorted by name.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{PhilosopherStats, RetryHistogram, SampleStats, SimulationResult};
+    ///
+    /// let result = SimulationResult {
+    ///     philosophers: [("Filósofo 1", 2), ("Filósofo 2", 5), ("Filósofo 3", 1), ("Filósofo 4", 5)]
+    ///         .into_iter()
+    ///         .map(|(name, meals)| PhilosopherStats { name: name.to_string(), meals, panicked: false, satiated: false })
+    ///         .collect(),
+    ///     meals_target: 5,
+    ///     elapsed: Duration::ZERO,
+    ///     fork_waits: Vec::new(),
+    ///     single_fork_holds: Vec::new(),
+    ///     fork_acquisitions: Vec::new(),
+    ///     retry_sleeps: Vec::new(),
+    ///     failures: Vec::new(),
+    ///     polls: Vec::new(),
+    ///     first_meals: Vec::new(),
+    ///     samples: Vec::new(),
+    ///     sample_stats: SampleStats::default(),
+    ///     hand_offs: Vec::new(),
+    ///     peak_forks_held: 0,
+    ///     timelines: Vec::new(),
+    ///     cpu_time: None,
+    ///     fork_stats: Vec::new(),
+    ///     second_fork_retries: RetryHistogram::default(),
+    ///     round_progress: Vec::new(),
+    ///     tastes: Vec::new(),
+    /// };
+    /// let leaderboard = result.leaderboard();
+    /// let expected = [("Filósofo 2", 5), ("Filósofo 4", 5), ("Filósofo 1", 2), ("Filósofo 3", 1)];
+    /// assert_eq!(leaderboard, expected.map(|(name, meals)| (name.to_string(), meals)));
+    /// ```
+    pub fn leaderboard(&self) -> Vec<(String, u32)> {
+        let mut leaderboard: Vec<_> = self.philosophers.iter().map(|stats| (stats.name.clone(), stats.meals)).collect();
+        leaderboard.sort_by(|(a_name, a_meals), (b_name, b_meals)| b_meals.cmp(a_meals).then_with(|| a_name.cmp(b_name)));
+        leaderboard
+    }
+
+    /// Elapsed time without the time the philosophers slept between the retries to take their
+    /// forks, on average, so with the `no-timeouts` feature it only measures the contention and
+    /// the scheduling.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(3)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(5))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.active_time() <= result.elapsed);
+    /// // Only one philosopher eats at a time so the others retry
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(result.active_time() < result.elapsed);
+    /// ```
+    pub fn active_time(&self) -> Duration {
+        if self.retry_sleeps.is_empty() {
+            return self.elapsed;
+        }
+        let mean_sleep = self.retry_sleeps.iter().sum::<Duration>() / self.retry_sleeps.len() as u32;
+        self.elapsed.saturating_sub(mean_sleep)
+    }
+
+    /// Wall time divided by CPU time, the higher the more the philosophers waited instead of
+    /// computing. `None` when the CPU time was not measured or is zero.
+    pub fn wall_cpu_ratio(&self) -> Option<f64> {
+        self.cpu_time
+            .filter(|cpu_time| !cpu_time.is_zero())
+            .map(|cpu_time| self.elapsed.as_secs_f64() / cpu_time.as_secs_f64())
+    }
+
+    /// Ids of the forks nobody acquired, which usually means the table was set up wrong.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, PhilosopherStats, RetryHistogram, SampleStats, SimulationConfig, SimulationResult};
+    ///
+    /// // The philosophers at positions 0 and 1, the only ones using fork 1, already ate all their meals
+    /// let saved = SimulationResult {
+    ///     philosophers: [2, 2, 0, 0].into_iter().map(|meals| PhilosopherStats { name: String::new(), meals, panicked: false, satiated: false }).collect(),
+    ///     meals_target: 2,
+    ///     elapsed: Duration::ZERO,
+    ///     fork_waits: Vec::new(),
+    ///     single_fork_holds: Vec::new(),
+    ///     fork_acquisitions: Vec::new(),
+    ///     retry_sleeps: Vec::new(),
+    ///     failures: Vec::new(),
+    ///     polls: Vec::new(),
+    ///     first_meals: Vec::new(),
+    ///     samples: Vec::new(),
+    ///     sample_stats: SampleStats::default(),
+    ///     hand_offs: Vec::new(),
+    ///     peak_forks_held: 0,
+    ///     timelines: Vec::new(),
+    ///     cpu_time: None,
+    ///     fork_stats: Vec::new(),
+    ///     second_fork_retries: RetryHistogram::default(),
+    ///     round_progress: Vec::new(),
+    ///     tastes: Vec::new(),
+    /// };
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(4)
+    ///     .meals(2)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .resume_from(&saved)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert_eq!(result.unused_forks(), [1]);
+    /// ```
+    pub fn unused_forks(&self) -> Vec<usize> {
+        self.fork_acquisitions
+            .iter()
+            .enumerate()
+            .filter(|(_, &acquisitions)| acquisitions == 0)
+            .map(|(fork, _)| fork)
+            .collect()
+    }
+
+    /// Mean time a fork sat idle, the total idle time of the forks divided by their number. Close
+    /// to [SimulationResult::elapsed] the forks were mostly unused, close to zero they were the
+    /// bottleneck. Zero without [SimulationResult::fork_stats].
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, ForkStats, RetryHistogram, SampleStats, SimulationConfig, SimulationResult};
+    ///
+    /// let stats = |held, idle| ForkStats { held: Duration::from_millis(held), idle: Duration::from_millis(idle) };
+    /// let result = SimulationResult {
+    ///     philosophers: Vec::new(),
+    ///     meals_target: 0,
+    ///     elapsed: Duration::from_millis(100),
+    ///     fork_waits: Vec::new(),
+    ///     single_fork_holds: Vec::new(),
+    ///     fork_acquisitions: Vec::new(),
+    ///     retry_sleeps: Vec::new(),
+    ///     failures: Vec::new(),
+    ///     polls: Vec::new(),
+    ///     first_meals: Vec::new(),
+    ///     samples: Vec::new(),
+    ///     sample_stats: SampleStats::default(),
+    ///     hand_offs: Vec::new(),
+    ///     peak_forks_held: 0,
+    ///     timelines: Vec::new(),
+    ///     cpu_time: None,
+    ///     fork_stats: vec![stats(90, 10), stats(40, 60), stats(100, 0), stats(0, 100)],
+    ///     second_fork_retries: RetryHistogram::default(),
+    ///     round_progress: Vec::new(),
+    ///     tastes: Vec::new(),
+    /// };
+    /// assert_eq!(result.avg_fork_idle_time(), Duration::from_millis(170) / 4);
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(5)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert_eq!(result.fork_stats.len(), 3);
+    /// for stats in &result.fork_stats {
+    ///     assert_eq!(stats.held + stats.idle, result.elapsed);
+    ///     // Two philosophers ate 5 meals of 2ms with it
+    ///     # #[cfg(not(feature = "no-timeouts"))]
+    ///     assert!(stats.held >= Duration::from_millis(20));
+    /// }
+    /// assert!(result.avg_fork_idle_time() < result.elapsed);
+    /// ```
+    pub fn avg_fork_idle_time(&self) -> Duration {
+        if self.fork_stats.is_empty() {
+            return Duration::ZERO;
+        }
+        self.fork_stats.iter().map(|stats| stats.idle).sum::<Duration>() / self.fork_stats.len() as u32
+    }
+
+    /// Most consecutive rounds in which someone ate, a round where every attempt to eat failed
+    /// ends the streak. It is `0` without rounds ([See][SimulationResult::round_progress]).
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, ForkBackend, SimulationConfig};
+    ///
+    /// let run = |fork_backend| {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(3)
+    ///         .rounds(30, Duration::ZERO)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(1))
+    ///         .fork_timeout(Duration::from_millis(2))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .fork_backend(fork_backend)
+    ///         .build()
+    ///         .unwrap();
+    ///     run_simulation(&config).unwrap()
+    /// };
+    /// let reliable = run(ForkBackend::Mutex);
+    /// assert_eq!(reliable.round_progress, [true; 30]);
+    /// assert_eq!(reliable.longest_progress_streak(), 30);
+    ///
+    /// // Forks that break now and then make whole rounds fail
+    /// let mut faulty = run(ForkBackend::Faulty { failure_probability: 0.5, downtime: Duration::from_millis(3) });
+    /// assert_eq!(faulty.round_progress.len(), 30);
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(faulty.round_progress.contains(&false) && faulty.longest_progress_streak() < 30);
+    ///
+    /// // The streak starts over after each round where nobody ate
+    /// faulty.round_progress = vec![true, true, false, true, true, true, false, false, true];
+    /// assert_eq!(faulty.longest_progress_streak(), 3);
+    /// ```
+    pub fn longest_progress_streak(&self) -> usize {
+        self.round_progress.split(|&ate| !ate).map(<[bool]>::len).max().unwrap_or(0)
+    }
+
+    /// Sleeps and calls to `try_lock` of every philosopher together, zero when they were not
+    /// counted.
+    pub fn total_polls(&self) -> PollCounts {
+        self.polls.iter().copied().sum()
+    }
+
+    /// Percentiles of the time waited for the forks, `None` if no fork was acquired.
+    pub fn wait_percentiles(&self) -> Option<WaitPercentiles> {
+        WaitPercentiles::from_sorted(&self.fork_waits)
+    }
+}
+
+/// One timing measured during a simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sample {
+    /// Position of the philosopher at the table.
+    pub philosopher: usize,
+    pub kind: SampleKind,
+    /// When the measured wait began, since the start of the simulation.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub at: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub duration: Duration,
+}
+
+/// What a philosopher is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    /// Thinking, or waiting to retry after failing to take the forks.
+    Thinking,
+    /// Trying to take the forks.
+    Hungry,
+    Eating,
+}
+
+/// A phase of a philosopher from when it began to when the next one began, since the start of
+/// the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhaseSpan {
+    pub phase: Phase,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub start: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub end: Duration,
+}
+
+/// What a [Sample] measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SampleKind {
+    /// Time waited to take the fork with the id `fork`.
+    ForkWait { fork: usize },
+    /// Time from the first attempt to eat to the meal.
+    Meal,
+}
+
+/// Percentiles of the time waited for the forks, using the nearest rank method.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::WaitPercentiles;
+///
+/// let waits: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+/// let percentiles = WaitPercentiles::from_sorted(&waits).unwrap();
+/// assert_eq!(percentiles.p50, Duration::from_millis(50));
+/// assert_eq!(percentiles.p95, Duration::from_millis(95));
+/// assert_eq!(percentiles.p99, Duration::from_millis(99));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaitPercentiles {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub p50: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub p95: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub p99: Duration,
+}
+
+impl WaitPercentiles {
+    /// Compute the percentiles of `waits`, which must be sorted from the shortest to the longest.
+    pub fn from_sorted(waits: &[Duration]) -> Option<Self> {
+        if waits.is_empty() {
+            return None;
+        }
+        let percentile = |p: usize| {
+            let rank = (p * waits.len()).div_ceil(100);
+            waits[rank.max(1) - 1]
+        };
+        Some(Self {
+            p50: percentile(50),
+            p95: percentile(95),
+            p99: percentile(99),
+        })
+    }
+}
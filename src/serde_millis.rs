@@ -0,0 +1,67 @@
+//! Serialize a [Duration] as a number of milliseconds, with the fraction for the sub millisecond
+//! part, so the results are readable and keep nanosecond precision when deserialized.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    to_millis(duration).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    f64::deserialize(deserializer).map(from_millis)
+}
+
+fn to_millis(duration: &Duration) -> f64 {
+    duration.as_nanos() as f64 / 1e6
+}
+
+fn from_millis(millis: f64) -> Duration {
+    Duration::from_nanos((millis * 1e6).round() as u64)
+}
+
+/// Same as the parent module for a list of durations.
+pub mod vec {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(durations: &[Duration], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(durations.iter().map(super::to_millis))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Duration>, D::Error> {
+        Ok(Vec::<f64>::deserialize(deserializer)?.into_iter().map(super::from_millis).collect())
+    }
+}
+
+/// Same as the parent module for a duration that may be missing.
+pub mod option {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        duration.as_ref().map(super::to_millis).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<f64>::deserialize(deserializer)?.map(super::from_millis))
+    }
+}
+
+/// Same as the parent module for a list of durations that may be missing.
+pub mod option_vec {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(durations: &[Option<Duration>], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(durations.iter().map(|duration| duration.as_ref().map(super::to_millis)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Option<Duration>>, D::Error> {
+        Ok(Vec::<Option<f64>>::deserialize(deserializer)?.into_iter().map(|millis| millis.map(super::from_millis)).collect())
+    }
+}
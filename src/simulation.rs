@@ -0,0 +1,1452 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::contention::ContentionWindow;
+use crate::fork::{CountedFork, HeldForks};
+use crate::philosopher::pause;
+use crate::snapshot::LiveCounters;
+use crate::{run_on_pool, Checkpoint, Event, EventKind, FailureCounts, Fork, ForkPool, ForkStats, HandOffFork, IntentBoard, JoinErrorPolicy, LiveMetrics, Mailbox, Oversubscription, PhaseSpan, Philosopher, PhilosopherStats, PollCounts, RetryHistogram, Sample, SampleStats, SimulationConfig, SimulationResult, SnapshotStream, Snapshots, StopCondition, Strategy, TableSnapshot};
+
+/// How often [Simulation::wait] looks for threads that finished with [JoinErrorPolicy::RetrySpawn].
+const SUPERVISION_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Sit `config.philosophers()` philosophers around a table and wait for them to eat
+/// ([See][Simulation::start]). With too many philosophers for the CPUs they may share a pool of
+/// threads instead ([See][crate::SimulationConfigBuilder::oversubscription]).
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{run_simulation, SimulationConfig};
+///
+/// let config = SimulationConfig::builder()
+///     .philosophers(3)
+///     .meals(2)
+///     .think_time(Duration::from_millis(1))
+///     .eat_time(Duration::from_millis(1))
+///     .build()
+///     .unwrap();
+/// let result = run_simulation(&config).unwrap();
+/// assert!(result.is_complete());
+/// assert_eq!(result.total_meals(), 6);
+/// ```
+pub fn run_simulation(config: &SimulationConfig) -> Result<SimulationResult, SimulationError> {
+    let parallelism = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    if let Some(pool_size) = config.pool_size_for(parallelism) {
+        return run_on_pool(config, pool_size);
+    }
+    if config.oversubscription() == Oversubscription::Warn && config.is_oversubscribed(parallelism) {
+        eprintln!(
+            "Hay {} filósofos para {parallelism} procesadores, los cambios de contexto pueden dominar los tiempos.",
+            config.philosophers()
+        );
+    }
+    Simulation::start(config)?.wait()
+}
+
+/// A running simulation.
+pub struct Simulation {
+    config: SimulationConfig,
+    handles: Vec<(String, JoinHandle<Philosopher<()>>)>,
+    /// The fork at the right of each philosopher, shared with the next one.
+    forks: Vec<Arc<dyn Fork<()>>>,
+    /// The forks again when their hand-offs are measured.
+    hand_offs: Vec<Arc<HandOffFork<()>>>,
+    held_forks: Arc<HeldForks>,
+    /// Shared by the philosophers for [Strategy::Serialized][crate::Strategy::Serialized].
+    table_lock: Arc<Mutex<()>>,
+    /// Shared by the philosophers for [Strategy::Reservation][crate::Strategy::Reservation].
+    intent_board: Option<Arc<IntentBoard>>,
+    shared: Arc<Shared>,
+    /// Philosopher whose meals are reported off by one, to test the consistency checks.
+    #[cfg(feature = "test-util")]
+    miscounted: Option<usize>,
+}
+
+/// State shared by the philosophers of a simulation and its handle.
+struct Shared {
+    start: Mutex<Instant>,
+    /// When the time budget runs out.
+    deadline: Option<Instant>,
+    pause: PauseGate,
+    /// Philosophers that did not eat all their meals yet.
+    remaining: AtomicUsize,
+    /// Whether each philosopher ate all its meals since the last reset, so it is taken out of
+    /// `remaining` only once.
+    reached: Mutex<Vec<bool>>,
+    /// Where the philosophers wait for each other at the end of the warmup.
+    warmup: TableBarrier,
+    /// Where the philosophers wait for each other at the end of each round.
+    round: TableBarrier,
+    /// Set between rounds when the time budget ran out, so the whole table stops together.
+    rounds_over: AtomicBool,
+    /// Set when someone ate in the current round, and whether anyone did in each past round.
+    round_ate: AtomicBool,
+    round_progress: Mutex<Vec<bool>>,
+    /// Opened once every philosopher has a thread, with `false` if a thread could not be spawned.
+    seated: Latch,
+    /// Number of times [Simulation::reset_stats] was called.
+    resets: AtomicUsize,
+    /// Gate of each philosopher, closed while it is suspended.
+    suspensions: RwLock<Vec<Arc<PauseGate>>>,
+    seating: Mutex<Seating>,
+    /// Philosophers waiting at the gates of [Shared::wait_turn], where a checkpoint can be taken.
+    parked: AtomicUsize,
+    /// Whether the table stops as soon as a philosopher panics, and whether one did.
+    fail_fast: bool,
+    aborted: AtomicBool,
+    /// Attempts of the whole table to take the forks over the last contention window.
+    contention: Arc<ContentionWindow>,
+    /// Where each philosopher publishes its counters for [Simulation::metrics].
+    live: RwLock<Vec<Arc<LiveCounters>>>,
+    /// CPU time of the threads that left the table, `None` once one could not be measured.
+    #[cfg(feature = "cpu-time")]
+    cpu_time: Mutex<Option<Duration>>,
+}
+
+/// Right forks handed to the philosophers when someone sits next to them.
+#[derive(Default)]
+struct Seating {
+    /// Whether each philosopher left the table.
+    left: Vec<bool>,
+    /// Meals of each philosopher as of its last attempt to eat.
+    meals: Vec<u32>,
+    /// Failed attempts of each philosopher as of its last attempt to eat.
+    failures: Vec<FailureCounts>,
+    /// Whether each philosopher is still in the warmup, whose meals and failures are not kept.
+    warming_up: Vec<bool>,
+    /// New right fork of each philosopher that has one waiting.
+    new_right_forks: HashMap<usize, NewRightFork>,
+}
+
+struct NewRightFork {
+    fork: Arc<dyn Fork<()>>,
+    id: usize,
+    /// Opened once the philosopher uses the fork.
+    taken: Arc<Latch>,
+}
+
+impl Shared {
+    /// Wait until neither the simulation nor the `philosopher` at `index` are paused, before it
+    /// tries to eat.
+    fn wait_turn(&self, philosopher: &mut Philosopher<()>, index: usize) {
+        // Its counters are up to date while it waits, for a checkpoint
+        self.update_seat(philosopher, index, false);
+        self.parked.fetch_add(1, Ordering::SeqCst);
+        self.pause.wait_while_paused();
+        let suspension = self.suspensions.read().unwrap()[index].clone();
+        suspension.wait_while_paused();
+        self.parked.fetch_sub(1, Ordering::SeqCst);
+        self.update_seat(philosopher, index, false);
+    }
+
+    /// Give `philosopher` its new right fork if someone sat at its right, or mark it as gone when
+    /// it `leaves` so nobody waits for it to take one.
+    fn update_seat(&self, philosopher: &mut Philosopher<()>, index: usize, leaves: bool) {
+        let mut seating = self.seating.lock().unwrap();
+        if let Some(new_right_fork) = seating.new_right_forks.remove(&index) {
+            philosopher.replace_right_fork(new_right_fork.fork, new_right_fork.id);
+            new_right_fork.taken.open(true);
+        }
+        if !seating.warming_up[index] {
+            seating.meals[index] = philosopher.meals();
+            seating.failures[index] = philosopher.failures();
+        }
+        if leaves {
+            seating.left[index] = true;
+        }
+    }
+
+    /// Wait at `barrier` with the counters of the `philosopher` at `index` up to date, counted as
+    /// parked like at [Shared::wait_turn] so a checkpoint does not wait for it to go on.
+    fn wait_at(&self, barrier: &TableBarrier, philosopher: &mut Philosopher<()>, index: usize) -> bool {
+        self.update_seat(philosopher, index, false);
+        self.parked.fetch_add(1, Ordering::SeqCst);
+        let result = barrier.wait();
+        self.parked.fetch_sub(1, Ordering::SeqCst);
+        result
+    }
+
+    /// Mark the philosopher at `index` as in the warmup or out of it.
+    fn set_warming_up(&self, index: usize, warming_up: bool) {
+        self.seating.lock().unwrap().warming_up[index] = warming_up;
+    }
+
+    fn budget_exceeded(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Take the philosopher at `index` out of the remaining ones, unless it already was since the
+    /// last reset.
+    fn reach(&self, index: usize) {
+        let mut reached = self.reached.lock().unwrap();
+        if !reached[index] {
+            reached[index] = true;
+            self.remaining.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Whether the philosophers have to leave before their next attempt to eat, because the time
+    /// budget ran out or someone panicked with [JoinErrorPolicy::FailFast].
+    fn must_stop(&self) -> bool {
+        self.budget_exceeded() || self.aborted.load(Ordering::SeqCst)
+    }
+}
+
+/// Stops the table when dropped by a philosopher's thread that panics, if it has to fail fast,
+/// and lets the rest of the table go on without it at the end of the warmup and of the rounds.
+struct AbortOnPanic<'a>(&'a Shared);
+
+impl Drop for AbortOnPanic<'_> {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            return;
+        }
+        if self.0.fail_fast {
+            self.0.aborted.store(true, Ordering::SeqCst);
+        }
+        self.0.warmup.leave();
+        self.0.round.leave();
+    }
+}
+
+/// Let `philosopher` dine at the table ([See][dine]) and leave it.
+fn dine_at_table(philosopher: Philosopher<()>, index: usize, shared: &Shared, start_delay: Duration, stop_condition: StopCondition, warmup_meals: u32, rounds: Option<(u32, Duration)>) -> Philosopher<()> {
+    let _abort = AbortOnPanic(shared);
+    let mut philosopher = dine(philosopher, index, shared, start_delay, stop_condition, warmup_meals, rounds);
+    philosopher.end_timeline();
+    philosopher.leave_meal_counts();
+    #[cfg(feature = "cpu-time")]
+    {
+        let mut cpu_time = shared.cpu_time.lock().unwrap();
+        *cpu_time = cpu_time.zip(crate::thread_cpu_time()).map(|(total, thread)| total + thread);
+    }
+    shared.update_seat(&mut philosopher, index, true);
+    philosopher
+}
+
+/// Let `philosopher` dine, once it arrives after `start_delay`, until `stop_condition` is met, or
+/// for the given rounds, or until the time budget runs out.
+fn dine(mut philosopher: Philosopher<()>, index: usize, shared: &Shared, start_delay: Duration, stop_condition: StopCondition, warmup_meals: u32, rounds: Option<(u32, Duration)>) -> Philosopher<()> {
+    if !shared.seated.wait() {
+        return philosopher;
+    }
+    pause(start_delay);
+    if warmup_meals > 0 {
+        // The meals of a resumed simulation are set aside during the warmup
+        let resumed = philosopher.meals();
+        shared.set_warming_up(index, true);
+        philosopher.reset_stats();
+        while philosopher.meals() < warmup_meals && !shared.must_stop() {
+            shared.wait_turn(&mut philosopher, index);
+            philosopher.dine();
+        }
+        // The measurement begins once the whole table finished the warmup
+        if shared.wait_at(&shared.warmup, &mut philosopher, index) {
+            *shared.start.lock().unwrap() = Instant::now();
+            shared.pause.reset_paused_time();
+        }
+        philosopher.reset_stats();
+        philosopher = philosopher.with_meals(resumed);
+        shared.set_warming_up(index, false);
+    }
+    if let Some((rounds, round_pause)) = rounds {
+        for round in 0..rounds {
+            shared.wait_turn(&mut philosopher, index);
+            let meals = philosopher.meals();
+            philosopher.dine();
+            if philosopher.meals() > meals {
+                shared.round_ate.store(true, Ordering::SeqCst);
+            }
+            // One philosopher decides for the whole table whether there is another round
+            if shared.wait_at(&shared.round, &mut philosopher, index) {
+                shared.rounds_over.store(shared.must_stop(), Ordering::SeqCst);
+                let ate = shared.round_ate.swap(false, Ordering::SeqCst);
+                shared.round_progress.lock().unwrap().push(ate);
+            }
+            shared.wait_at(&shared.round, &mut philosopher, index);
+            if shared.rounds_over.load(Ordering::SeqCst) {
+                break;
+            }
+            if round + 1 < rounds {
+                pause(round_pause);
+            }
+        }
+        return philosopher;
+    }
+    let mut resets = 0;
+    // Whether the stats were reset since the last meal, forgetting them if so
+    let mut catch_up = |philosopher: &mut Philosopher<()>| {
+        let current = shared.resets.load(Ordering::SeqCst);
+        let reset = current != resets;
+        if reset {
+            resets = current;
+            philosopher.reset_stats();
+        }
+        reset
+    };
+    match stop_condition {
+        StopCondition::EachReaches(meals) => {
+            while !shared.must_stop() {
+                catch_up(&mut philosopher);
+                if philosopher.meals() >= meals || philosopher.is_satiated() {
+                    break;
+                }
+                shared.wait_turn(&mut philosopher, index);
+                philosopher.dine();
+            }
+        }
+        StopCondition::AllReach(meals) => {
+            let mut reached = false;
+            loop {
+                if catch_up(&mut philosopher) {
+                    reached = false;
+                }
+                if !reached && (philosopher.meals() >= meals || philosopher.is_satiated()) {
+                    reached = true;
+                    shared.reach(index);
+                }
+                if shared.remaining.load(Ordering::SeqCst) == 0 || shared.must_stop() {
+                    break;
+                }
+                shared.wait_turn(&mut philosopher, index);
+                philosopher.dine();
+            }
+        }
+    }
+    philosopher
+}
+
+impl Simulation {
+    /// Sit `config.philosophers()` philosophers around a table with a fork between each pair of
+    /// them and let each one dine in its own thread until `config.stop_condition()` is met.
+    ///
+    /// Every philosopher is right handed except the one at `config.left_handed_index()`, the last
+    /// one by default, which breaks the symmetry of the table. Other patterns can be chosen with
+    /// `config.handedness()`.
+    ///
+    /// When `config.warmup_meals()` is not zero each philosopher first eats the warmup meals and
+    /// waits for the rest of the table to do the same, then the meal counters are reset and the
+    /// elapsed time starts to be measured.
+    ///
+    /// With `config.rounds()` the philosophers dine in rounds instead and leave the table after
+    /// the last one ([See][crate::SimulationConfigBuilder::rounds]).
+    ///
+    /// If the thread of a philosopher can not be spawned the philosophers already sitting leave the
+    /// table and [SimulationError::SpawnFailed] is returned.
+    pub fn start(config: &SimulationConfig) -> Result<Self, SimulationError> {
+        Self::start_from(config, None)
+    }
+
+    /// Start the simulation of `config` where `checkpoint` left off, with the meals, failures and
+    /// elapsed time of each philosopher taken back from it ([See][Simulation::checkpoint]). The
+    /// meals of the checkpoint take the place of the initial meals of `config`, and a philosopher
+    /// missing from it begins from scratch.
+    pub fn restore(config: &SimulationConfig, checkpoint: &Checkpoint) -> Result<Self, SimulationError> {
+        Self::start_from(config, Some(checkpoint))
+    }
+
+    fn start_from(config: &SimulationConfig, checkpoint: Option<&Checkpoint>) -> Result<Self, SimulationError> {
+        let n = config.philosophers();
+        let initial_meals = |i: usize| checkpoint.map_or_else(|| config.initial_meals(i), |checkpoint| checkpoint.meals.get(i).copied().unwrap_or(0));
+        let initial_failures = |i: usize| checkpoint.and_then(|checkpoint| checkpoint.failures.get(i).copied()).unwrap_or_default();
+        // Create the forks as empty tuples
+        let mut hand_offs = Vec::new();
+        let held_forks = Arc::new(HeldForks::default());
+        let forks: Vec<Arc<dyn Fork<()>>> = (0..n).map(|i| create_fork(config, i, &held_forks, &mut hand_offs)).collect();
+
+        // Highest priority waiting for each fork
+        let waiting_priorities: Vec<_> = (0..n).map(|_| Arc::new(AtomicU32::new(0))).collect();
+        let table_lock = Arc::new(Mutex::new(()));
+        let mut mailboxes = (config.strategy() == Strategy::Polite).then(|| Mailbox::ring(n).into_iter());
+        let meal_counts: Vec<_> = (0..n).map(|_| Arc::new(AtomicU32::new(0))).collect();
+        let fork_pool = (config.strategy() == Strategy::SharedPool).then(|| Arc::new(ForkPool::new(forks.clone())));
+        let intent_board = (config.strategy() == Strategy::Reservation).then(|| Arc::new(IntentBoard::new()));
+        let contention = Arc::new(ContentionWindow::new(config.contention_window()));
+        let live: Vec<Arc<LiveCounters>> = (0..n).map(|_| Arc::default()).collect();
+        let philosophers = (0..n).map(|i| {
+            let (think_time, eat_time) = config.times_of(i);
+            let (left_id, right_id) = (config.fork_at(i), config.fork_at((i + 1) % n));
+            let philosopher = Philosopher::new(&format!("Filósofo {}", i + 1), forks[left_id].clone(), forks[right_id].clone(), config.is_left_handed(i))
+                .expect("a validated config with philosophers has at least 2 so each one has different forks")
+                .with_fork_ids(left_id, right_id)
+                .with_config(config)
+                .with_times(think_time, eat_time)
+                .with_seed(config.seed().wrapping_add(i as u64))
+                .with_meals(initial_meals(i))
+                .with_failures(initial_failures(i))
+                .with_priority(config.priority_of(i))
+                .with_table_lock(table_lock.clone())
+                .with_contention_window(contention.clone())
+                .with_live_counters(live[i].clone());
+            let philosopher = match config.fork_order_of(i) {
+                Some(order) => philosopher.with_fork_order(order).expect("a validated config has the ids of the forks of each philosopher in its fork order"),
+                None => philosopher,
+            };
+            let philosopher = if config.collect_samples() { philosopher.collect_samples(config.sample_limit_of(i)) } else { philosopher };
+            let philosopher = if config.collect_timeline() { philosopher.collect_timeline() } else { philosopher };
+            let philosopher = match mailboxes.as_mut().and_then(Iterator::next) {
+                Some(mailbox) => philosopher.with_mailbox(mailbox),
+                None => philosopher,
+            };
+            let philosopher = if config.strategy() == Strategy::MinMakespan {
+                philosopher.with_meal_counts(meal_counts[i].clone(), [meal_counts[(i + n - 1) % n].clone(), meal_counts[(i + 1) % n].clone()])
+            } else {
+                philosopher
+            };
+            let philosopher = match &fork_pool {
+                Some(fork_pool) => philosopher.with_fork_pool(fork_pool.clone()),
+                None => philosopher,
+            };
+            let philosopher = match &intent_board {
+                Some(intent_board) => philosopher.with_intent_board(intent_board.clone()),
+                None => philosopher,
+            };
+            if config.priority_inheritance() {
+                philosopher.with_priority_inheritance(waiting_priorities[i].clone(), waiting_priorities[(i + 1) % n].clone())
+            } else {
+                philosopher
+            }
+        });
+
+        let now = Instant::now();
+        let start = checkpoint.and_then(|checkpoint| now.checked_sub(checkpoint.elapsed)).unwrap_or(now);
+        let shared = Arc::new(Shared {
+            start: Mutex::new(start),
+            deadline: config.time_budget().map(|budget| start + budget),
+            pause: PauseGate::default(),
+            remaining: AtomicUsize::new(n),
+            reached: Mutex::new(vec![false; n]),
+            warmup: TableBarrier::new(n),
+            round: TableBarrier::new(n),
+            rounds_over: AtomicBool::new(false),
+            round_ate: AtomicBool::new(false),
+            round_progress: Mutex::new(Vec::new()),
+            seated: Latch::default(),
+            resets: AtomicUsize::new(0),
+            suspensions: RwLock::new((0..n).map(|_| Arc::default()).collect()),
+            seating: Mutex::new(Seating {
+                left: vec![false; n],
+                meals: (0..n).map(initial_meals).collect(),
+                failures: (0..n).map(initial_failures).collect(),
+                warming_up: vec![false; n],
+                new_right_forks: HashMap::new(),
+            }),
+            parked: AtomicUsize::new(0),
+            fail_fast: config.join_error_policy() == JoinErrorPolicy::FailFast,
+            aborted: AtomicBool::new(false),
+            contention: contention.clone(),
+            live: RwLock::new(live.clone()),
+            #[cfg(feature = "cpu-time")]
+            cpu_time: Mutex::new(Some(Duration::ZERO)),
+        });
+        // Spawn a thread for each philosopher
+        let mut handles = Vec::with_capacity(n);
+        let start_delays = config.start_delays();
+        for (index, philosopher) in philosophers.enumerate() {
+            let name = philosopher.name().to_string();
+            let thread_shared = shared.clone();
+            let stop_condition = config.stop_condition();
+            let warmup_meals = config.warmup_meals();
+            let rounds = config.rounds().map(|rounds| (rounds, config.round_pause()));
+            let start_delay = start_delays[index];
+            #[cfg(feature = "thread-priority")]
+            let niceness = config.niceness_of(index);
+            let spawn = || {
+                thread::Builder::new().name(name.clone()).spawn(move || {
+                    // Best effort, the philosopher keeps the priority of the process otherwise
+                    #[cfg(feature = "thread-priority")]
+                    if let Some(nice) = niceness {
+                        let _ = crate::set_thread_niceness(nice);
+                    }
+                    dine_at_table(philosopher, index, &thread_shared, start_delay, stop_condition, warmup_meals, rounds)
+                })
+            };
+            #[cfg(feature = "test-util")]
+            let spawned = if config.failed_spawn() == Some(index) { Err(io::Error::other("spawn failed on purpose")) } else { spawn() };
+            #[cfg(not(feature = "test-util"))]
+            let spawned = spawn();
+            match spawned {
+                Ok(handle) => handles.push((name, handle)),
+                Err(error) => {
+                    // The philosophers already sitting leave without eating
+                    shared.seated.open(false);
+                    for (_, handle) in handles {
+                        let _ = handle.join();
+                    }
+                    return Err(SimulationError::SpawnFailed(error));
+                }
+            }
+        }
+        shared.seated.open(true);
+
+        Ok(Self {
+            config: config.clone(),
+            handles,
+            forks,
+            hand_offs,
+            held_forks,
+            table_lock,
+            intent_board,
+            shared,
+            #[cfg(feature = "test-util")]
+            miscounted: None,
+        })
+    }
+
+    /// The meals of each philosopher so far and who already left the table, or panicked.
+    pub fn snapshot(&self) -> TableSnapshot {
+        let elapsed = self.shared.start.lock().unwrap().elapsed();
+        let seating = self.shared.seating.lock().unwrap();
+        TableSnapshot {
+            elapsed,
+            meals: seating.meals.clone(),
+            // A philosopher whose thread panicked never leaves its seat
+            left: seating.left.iter().zip(&self.handles).map(|(&left, (_, handle))| left || handle.is_finished()).collect(),
+        }
+    }
+
+    /// The meals, failures and phase of each philosopher right now, without waiting for anyone
+    /// ([See][LiveMetrics]). Unlike [Simulation::snapshot] the meals are counted as soon as they
+    /// are eaten. It can be called from any thread, as often as needed, while the table eats.
+    /// ```rust
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{Phase, Simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(10)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .build()
+    ///     .unwrap();
+    /// let simulation = Simulation::start(&config).unwrap();
+    /// let totals = thread::scope(|scope| {
+    ///     let monitor = scope.spawn(|| {
+    ///         let mut totals = Vec::new();
+    ///         while !simulation.snapshot().is_finished() {
+    ///             let metrics = simulation.metrics();
+    ///             assert_eq!(metrics.phases.len(), 3);
+    ///             totals.push(metrics.total_meals());
+    ///             thread::sleep(Duration::from_millis(1));
+    ///         }
+    ///         totals
+    ///     });
+    ///     monitor.join().unwrap()
+    /// });
+    /// // The meals only grow
+    /// assert!(totals.windows(2).all(|pair| pair[0] <= pair[1]), "{totals:?}");
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(totals.len() > 2);
+    /// let metrics = simulation.metrics();
+    /// assert_eq!(metrics.total_meals(), 30);
+    /// assert_eq!(metrics.meals, [10, 10, 10]);
+    /// // Everybody thinks once they leave
+    /// assert!(metrics.phases.iter().all(|&phase| phase == Phase::Thinking));
+    /// assert_eq!(simulation.wait().unwrap().total_meals(), 30);
+    /// ```
+    pub fn metrics(&self) -> LiveMetrics {
+        let elapsed = self.shared.start.lock().unwrap().elapsed();
+        let live: Vec<_> = self.shared.live.read().unwrap().iter().map(|counters| counters.read()).collect();
+        LiveMetrics {
+            elapsed,
+            meals: live.iter().map(|&(meals, _, _)| meals).collect(),
+            failures: live.iter().map(|&(_, failures, _)| failures).collect(),
+            phases: live.iter().map(|&(_, _, phase)| phase).collect(),
+        }
+    }
+
+    /// A [TableSnapshot] now and then every `interval` while the table eats, the last one taken
+    /// once every philosopher left ([See][Snapshots]). The simulation is waited for as usual
+    /// once the stream ends.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{Simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(10)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .build()
+    ///     .unwrap();
+    /// let simulation = Simulation::start(&config).unwrap();
+    /// let snapshots: Vec<_> = simulation.snapshots(Duration::from_millis(5)).collect();
+    /// let last = snapshots.last().unwrap();
+    /// assert!(last.is_finished());
+    /// assert_eq!(last.meals, [10, 10, 10]);
+    /// assert!(snapshots[..snapshots.len() - 1].iter().all(|snapshot| !snapshot.is_finished()));
+    /// // The meals only grow
+    /// for pair in snapshots.windows(2) {
+    ///     assert!(pair[0].meals.iter().zip(&pair[1].meals).all(|(before, after)| before <= after));
+    /// }
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(snapshots.len() > 2);
+    /// assert_eq!(simulation.wait().unwrap().total_meals(), 30);
+    /// ```
+    pub fn snapshots(&self, interval: Duration) -> Snapshots<'_> {
+        Snapshots::new(self, interval)
+    }
+
+    /// Same as [Simulation::snapshots] but as an async stream, which waits for the next snapshot
+    /// without blocking the thread that polls it ([See][SnapshotStream]). The stream only uses
+    /// the standard library, instead of depending on tokio or `futures-core`, so the crate stays
+    /// free of an async runtime: a single thread of the stream sleeps until each snapshot is due.
+    /// ```rust
+    /// # use std::future::Future;
+    /// # use std::pin::pin;
+    /// # use std::sync::Arc;
+    /// # use std::task::{Context, Poll, Wake, Waker};
+    /// # use std::thread::{self, Thread};
+    /// use std::time::Duration;
+    /// use actividad_12::{Simulation, SimulationConfig};
+    ///
+    /// # struct Unpark(Thread);
+    /// # impl Wake for Unpark {
+    /// #     fn wake(self: Arc<Self>) {
+    /// #         self.0.unpark();
+    /// #     }
+    /// # }
+    /// # // A minimal executor, a tokio runtime would do the same
+    /// # fn block_on<F: Future>(future: F) -> F::Output {
+    /// #     let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    /// #     let mut future = pin!(future);
+    /// #     loop {
+    /// #         if let Poll::Ready(output) = future.as_mut().poll(&mut Context::from_waker(&waker)) {
+    /// #             return output;
+    /// #         }
+    /// #         thread::park();
+    /// #     }
+    /// # }
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(10)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .build()
+    ///     .unwrap();
+    /// let simulation = Simulation::start(&config).unwrap();
+    /// let snapshots = block_on(async {
+    ///     let mut stream = simulation.snapshot_stream(Duration::from_millis(5));
+    ///     let mut snapshots = Vec::new();
+    ///     while let Some(snapshot) = stream.next().await {
+    ///         snapshots.push(snapshot);
+    ///     }
+    ///     snapshots
+    /// });
+    /// let last = snapshots.last().unwrap();
+    /// assert!(last.is_finished());
+    /// assert_eq!(last.meals, [10, 10, 10]);
+    /// assert!(snapshots[..snapshots.len() - 1].iter().all(|snapshot| !snapshot.is_finished()));
+    /// for pair in snapshots.windows(2) {
+    ///     assert!(pair[0].meals.iter().zip(&pair[1].meals).all(|(before, after)| before <= after));
+    /// }
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(snapshots.len() > 2);
+    /// assert_eq!(simulation.wait().unwrap().total_meals(), 30);
+    /// ```
+    pub fn snapshot_stream(&self, interval: Duration) -> SnapshotStream<'_> {
+        SnapshotStream::new(self, interval)
+    }
+
+    /// Share of the attempts to take the forks that failed over the last
+    /// [contention window][crate::SimulationConfigBuilder::contention_window], from `0.0` when
+    /// every attempt got the forks or there were none to `1.0` when all of them failed. A
+    /// controller can read it while the table eats to adjust the timeouts of the next runs.
+    /// ```rust
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{Simulation, SimulationConfig};
+    ///
+    /// // Two philosophers sharing both forks
+    /// let contention = |think_time, fork_timeout| {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(2)
+    ///         .meals(u32::MAX)
+    ///         .think_time(think_time)
+    ///         .eat_time(Duration::from_millis(10))
+    ///         .fork_timeout(fork_timeout)
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .contention_window(Duration::from_millis(200))
+    ///         .time_budget(Duration::from_millis(300))
+    ///         .build()
+    ///         .unwrap();
+    ///     let simulation = Simulation::start(&config).unwrap();
+    ///     thread::sleep(Duration::from_millis(200));
+    ///     let contention = simulation.current_contention();
+    ///     let _ = simulation.wait();
+    ///     contention
+    /// };
+    /// // Thinking after each meal and waiting as long as a meal takes nobody fails
+    /// assert_eq!(contention(Duration::from_millis(5), Duration::from_millis(100)), 0.0);
+    /// // Without thinking and giving up after 2ms the hungry one fails a few times during each
+    /// // meal of the other one
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!((0.5..1.0).contains(&contention(Duration::ZERO, Duration::from_millis(2))));
+    /// ```
+    pub fn current_contention(&self) -> f64 {
+        self.shared.contention.ratio()
+    }
+
+    /// Stop the philosophers once they finish what they are doing, until [Simulation::resume] is
+    /// called.
+    /// ```rust
+    /// use std::thread;
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::{Simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(10)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(5))
+    ///     .exclude_paused_time(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let started = Instant::now();
+    /// let simulation = Simulation::start(&config).unwrap();
+    /// thread::sleep(Duration::from_millis(20));
+    /// simulation.pause();
+    /// // The meals being eaten end, then nobody eats until the table is resumed
+    /// thread::sleep(Duration::from_millis(50));
+    /// let paused = simulation.metrics().total_meals();
+    /// thread::sleep(Duration::from_millis(100));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert_eq!(simulation.metrics().total_meals(), paused);
+    /// simulation.resume();
+    /// thread::sleep(Duration::from_millis(30));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(simulation.metrics().total_meals() > paused);
+    /// let result = simulation.wait().unwrap();
+    /// assert_eq!(result.total_meals(), 30);
+    /// // Most of the time spent paused is not measured
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(result.elapsed + Duration::from_millis(120) <= started.elapsed());
+    /// ```
+    pub fn pause(&self) {
+        self.shared.pause.set_paused(true);
+    }
+
+    pub fn resume(&self) {
+        self.shared.pause.set_paused(false);
+    }
+
+    /// Pause the simulation and, once every philosopher either waits for its next attempt to eat
+    /// or left the table, record their counters to continue from there with
+    /// [Simulation::restore]. The simulation stays paused, it can be resumed as usual.
+    ///
+    /// It waits for the philosophers still in a round or a start delay to reach their next attempt
+    /// or its barrier, and for the newcomers of [Simulation::add_philosopher] to sit down. The
+    /// meals of a warmup are not recorded, a checkpoint taken during it restores the meals before.
+    /// ```rust
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(20)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let simulation = Simulation::start(&config).unwrap();
+    /// thread::sleep(Duration::from_millis(15));
+    /// let checkpoint = simulation.checkpoint();
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(checkpoint.meals.iter().sum::<u32>() < 60);
+    /// // Nobody eats while the simulation is paused
+    /// thread::sleep(Duration::from_millis(5));
+    /// assert_eq!(simulation.snapshot().meals, checkpoint.meals);
+    /// simulation.resume();
+    /// simulation.wait().unwrap();
+    ///
+    /// let restored = Simulation::restore(&config, &checkpoint).unwrap().wait().unwrap();
+    /// let uninterrupted = run_simulation(&config).unwrap();
+    /// assert!(restored.is_complete());
+    /// let meals = |result: &actividad_12::SimulationResult| result.philosophers.iter().map(|stats| stats.meals).collect::<Vec<_>>();
+    /// assert_eq!(meals(&restored), meals(&uninterrupted));
+    /// assert!(restored.elapsed >= checkpoint.elapsed);
+    /// for (before, after) in checkpoint.failures.iter().zip(&restored.failures) {
+    ///     assert!(before.total() <= after.total());
+    /// }
+    /// ```
+    /// Also while some philosophers wait at the end of the warmup for the slower ones:
+    /// ```rust
+    /// # use std::thread;
+    /// # use std::time::Duration;
+    /// # use actividad_12::{Simulation, SimulationConfig, TimeDistribution};
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(2)
+    ///     .warmup_meals(5)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(5))
+    ///     .philosopher_times(0, TimeDistribution::Constant(Duration::ZERO), TimeDistribution::Constant(Duration::from_millis(20)))
+    ///     .build()
+    ///     .unwrap();
+    /// let simulation = Simulation::start(&config).unwrap();
+    /// thread::sleep(Duration::from_millis(40));
+    /// let checkpoint = simulation.checkpoint();
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert_eq!(checkpoint.meals, [0, 0, 0]);
+    /// simulation.resume();
+    /// assert_eq!(simulation.wait().unwrap().total_meals(), 6);
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.pause();
+        loop {
+            {
+                let seating = self.shared.seating.lock().unwrap();
+                let gone = seating.left.iter().zip(&self.handles).filter(|(&left, (_, handle))| left || handle.is_finished()).count();
+                if gone + self.shared.parked.load(Ordering::SeqCst) >= self.handles.len() {
+                    let mut elapsed = self.shared.start.lock().unwrap().elapsed();
+                    if self.config.exclude_paused_time() {
+                        elapsed = elapsed.saturating_sub(self.shared.pause.paused_time());
+                    }
+                    return Checkpoint {
+                        elapsed,
+                        meals: seating.meals.clone(),
+                        failures: seating.failures.clone(),
+                        left: seating.left.clone(),
+                    };
+                }
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Let the philosopher at `index` take a break once it finishes what it is doing, it stops
+    /// trying to take its forks until [Simulation::resume_philosopher] is called while the rest of
+    /// the table keeps eating.
+    ///
+    /// # Panics
+    ///
+    /// If there is no philosopher at `index`.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{Event, EventKind, Observer, Simulation, SimulationConfig};
+    ///
+    /// /// Meals eaten by each philosopher
+    /// #[derive(Default)]
+    /// struct Meals(Mutex<[u32; 3]>);
+    ///
+    /// impl Observer for Meals {
+    ///     fn on_event(&self, event: &Event) {
+    ///         if let EventKind::Ate { .. } = event.kind {
+    ///             let index: usize = event.philosopher["Filósofo ".len()..].parse().unwrap();
+    ///             self.0.lock().unwrap()[index - 1] += 1;
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let meals = Arc::new(Meals::default());
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(u32::MAX)
+    ///     .think_time(Duration::from_millis(2))
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .time_budget(Duration::from_millis(300))
+    ///     .observer(meals.clone())
+    ///     .build()
+    ///     .unwrap();
+    /// let simulation = Simulation::start(&config).unwrap();
+    /// simulation.suspend(0);
+    /// // Let a meal in progress end
+    /// thread::sleep(Duration::from_millis(50));
+    /// let before = *meals.0.lock().unwrap();
+    /// thread::sleep(Duration::from_millis(100));
+    /// let after = *meals.0.lock().unwrap();
+    /// simulation.resume_philosopher(0);
+    /// let _ = simulation.wait();
+    ///
+    /// assert_eq!(after[0], before[0]);
+    /// assert!(after[1] > before[1] && after[2] > before[2]);
+    /// ```
+    pub fn suspend(&self, index: usize) {
+        self.shared.suspensions.read().unwrap()[index].set_paused(true);
+    }
+
+    /// End the break of the philosopher at `index` ([See][Simulation::suspend]).
+    ///
+    /// # Panics
+    ///
+    /// If there is no philosopher at `index`.
+    pub fn resume_philosopher(&self, index: usize) {
+        self.shared.suspensions.read().unwrap()[index].set_paused(false);
+    }
+
+    /// Sit a new philosopher, with a new fork at its left, between the last philosopher and the
+    /// first one while the table keeps eating, and return its position. The newcomer uses the
+    /// fork timeout, times, strategy and observer of `config` and leaves following the stop
+    /// condition of the simulation, without a warmup, rounds, priority inheritance, mailbox, meal
+    /// counts nor fork pool, so with [Strategy::Polite] or [Strategy::MinMakespan] it neither
+    /// defers to its neighbors nor tells them anything, and with [Strategy::SharedPool] it takes
+    /// its own forks. With [Strategy::Reservation] it announces its forks on the board of the
+    /// table like everyone else.
+    ///
+    /// The last philosopher hands its right fork to the newcomer and takes the new fork before its
+    /// next attempt to eat, the newcomer waits for that so a fork is never shared by three
+    /// philosophers. If the last philosopher already left the table the newcomer begins at once.
+    ///
+    /// A table without philosophers returns [SimulationError::EmptyTable], the newcomer would
+    /// have nobody to share its forks with.
+    /// ```rust
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{Simulation, SimulationConfig, SimulationError};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(3)
+    ///     .think_time(Duration::from_millis(5))
+    ///     .eat_time(Duration::from_millis(5))
+    ///     .build()
+    ///     .unwrap();
+    /// let mut simulation = Simulation::start(&config).unwrap();
+    /// thread::sleep(Duration::from_millis(10));
+    /// assert_eq!(simulation.add_philosopher(&config).unwrap(), 3);
+    /// let result = simulation.wait().unwrap();
+    /// assert_eq!(result.philosophers.len(), 4);
+    /// assert_eq!(result.philosophers[3].name, "Filósofo 4");
+    /// assert_eq!(result.philosophers[3].meals, 3);
+    ///
+    /// let empty = SimulationConfig::builder().philosophers(0).build().unwrap();
+    /// let mut simulation = Simulation::start(&empty).unwrap();
+    /// assert!(matches!(simulation.add_philosopher(&config), Err(SimulationError::EmptyTable)));
+    /// ```
+    pub fn add_philosopher(&mut self, config: &SimulationConfig) -> Result<usize, SimulationError> {
+        let index = self.handles.len();
+        if index == 0 {
+            return Err(SimulationError::EmptyTable);
+        }
+        let fork_id = self.forks.len();
+        let fork = create_fork(&self.config, fork_id, &self.held_forks, &mut self.hand_offs);
+        let name = format!("Filósofo {}", index + 1);
+        let (think_time, eat_time) = config.times_of(index);
+        let first_fork_id = self.config.fork_at(0);
+        let philosopher = Philosopher::new(&name, fork.clone(), self.forks[first_fork_id].clone(), false)
+            .expect("the new fork is not the first one")
+            .with_fork_ids(fork_id, first_fork_id)
+            .with_config(config)
+            .with_times(think_time, eat_time)
+            .with_seed(config.seed().wrapping_add(index as u64))
+            .with_table_lock(self.table_lock.clone())
+            .with_contention_window(self.shared.contention.clone());
+        let philosopher = if self.config.collect_timeline() { philosopher.collect_timeline() } else { philosopher };
+        let philosopher = match &self.intent_board {
+            Some(intent_board) => philosopher.with_intent_board(intent_board.clone()),
+            None => philosopher,
+        };
+        let live = Arc::<LiveCounters>::default();
+        let philosopher = philosopher.with_live_counters(live.clone());
+
+        // The newcomer waits in its thread until it can take its place
+        let seated = Arc::new(Latch::default());
+        let thread_seated = seated.clone();
+        let shared = self.shared.clone();
+        let stop_condition = self.config.stop_condition();
+        let handle = thread::Builder::new()
+            .name(name.clone())
+            .spawn(move || {
+                thread_seated.wait();
+                dine_at_table(philosopher, index, &shared, Duration::ZERO, stop_condition, 0, None)
+            })
+            .map_err(SimulationError::SpawnFailed)?;
+
+        self.shared.suspensions.write().unwrap().push(Arc::default());
+        self.shared.live.write().unwrap().push(live);
+        {
+            let mut reached = self.shared.reached.lock().unwrap();
+            reached.push(false);
+            self.shared.remaining.fetch_add(1, Ordering::SeqCst);
+        }
+        {
+            // The last philosopher moves to the new fork before the newcomer takes its place
+            let mut seating = self.shared.seating.lock().unwrap();
+            let last = index - 1;
+            if seating.left[last] {
+                seated.open(true);
+            } else {
+                seating.new_right_forks.insert(last, NewRightFork { fork: fork.clone(), id: fork_id, taken: seated });
+            }
+            seating.left.push(false);
+            seating.meals.push(0);
+            seating.failures.push(FailureCounts::default());
+            seating.warming_up.push(false);
+        }
+        self.handles.push((name, handle));
+        self.forks.push(fork);
+        Ok(index)
+    }
+
+    /// Forget the meals eaten and the fork waits so far and restart the elapsed time, without
+    /// stopping the philosophers, to measure only what happens from now on. The stop condition
+    /// also counts the meals from now on.
+    ///
+    /// Each philosopher forgets its stats before its next attempt to eat, so a meal that is
+    /// being eaten counts after the reset. Once every philosopher ate all its meals they are
+    /// leaving, and a reset does not bring them back.
+    /// ```rust
+    /// use std::thread;
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::{Simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(3)
+    ///     .think_time(Duration::from_millis(5))
+    ///     .eat_time(Duration::from_millis(5))
+    ///     .build()
+    ///     .unwrap();
+    /// let started = Instant::now();
+    /// let simulation = Simulation::start(&config).unwrap();
+    /// thread::sleep(Duration::from_millis(20));
+    /// simulation.reset_stats();
+    /// let result = simulation.wait().unwrap();
+    /// // The first 20ms are not measured
+    /// assert!(result.elapsed + Duration::from_millis(20) <= started.elapsed());
+    /// assert_eq!(result.total_meals(), 9);
+    /// ```
+    pub fn reset_stats(&self) {
+        *self.shared.start.lock().unwrap() = Instant::now();
+        self.shared.pause.reset_paused_time();
+        {
+            // Once nobody remains the philosophers are leaving, they would never be back to eat
+            let mut reached = self.shared.reached.lock().unwrap();
+            if self.shared.remaining.load(Ordering::SeqCst) > 0 {
+                reached.fill(false);
+                self.shared.remaining.store(reached.len(), Ordering::SeqCst);
+            }
+        }
+        self.shared.resets.fetch_add(1, Ordering::SeqCst);
+        self.shared.round_progress.lock().unwrap().clear();
+    }
+
+    /// Report one meal more than the philosopher at `index` ate, so the check that the meals of
+    /// the result add up to the meal counters of the philosophers fails in debug builds.
+    /// ```rust,should_panic
+    /// use std::time::Duration;
+    /// use actividad_12::{Simulation, SimulationConfig};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(3)
+    ///     .meals(2)
+    ///     .think_time(Duration::from_millis(1))
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let mut simulation = Simulation::start(&config).unwrap();
+    /// simulation.miscount_meals(1);
+    /// let _ = simulation.wait();
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn miscount_meals(&mut self, index: usize) {
+        self.miscounted = Some(index);
+    }
+
+    /// Wait for every philosopher to leave the table. If the time budget ran out the partial
+    /// result is returned inside [SimulationError::BudgetExceeded]. A philosopher whose thread
+    /// panicked is handled following the [JoinErrorPolicy] of the config.
+    pub fn wait(mut self) -> Result<SimulationResult, SimulationError> {
+        let observer = self.config.observer();
+        let mut fork_waits = Vec::new();
+        let mut single_fork_holds = Vec::new();
+        let mut fork_acquisitions = vec![0; self.handles.len()];
+        let mut retry_sleeps = Vec::with_capacity(self.handles.len());
+        let mut tastes = Vec::with_capacity(self.handles.len());
+        let mut failures = Vec::with_capacity(self.handles.len());
+        let mut polls = Vec::new();
+        let mut first_meals = Vec::with_capacity(self.handles.len());
+        let profile = self.config.profile();
+        let mut samples = Vec::new();
+        let mut timelines = Vec::new();
+        let mut sample_stats = SampleStats::default();
+        let mut second_fork_retries = RetryHistogram::default();
+        let mut counters = Vec::with_capacity(self.handles.len());
+        let mut first_panicked = None;
+        let handles = mem::take(&mut self.handles);
+        let joined = match self.config.join_error_policy() {
+            JoinErrorPolicy::RetrySpawn(respawns) => self.join_respawning(handles, respawns),
+            JoinErrorPolicy::Ignore | JoinErrorPolicy::FailFast => handles.into_iter().map(|(name, handle)| (name, handle.join())).collect(),
+        };
+        // Read once everybody left, the end of the warmup moves it
+        let start = *self.shared.start.lock().unwrap();
+        let philosophers: Vec<_> = joined
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, joined))| {
+                match joined {
+                    Ok(philosopher) => {
+                        fork_waits.extend_from_slice(philosopher.fork_waits());
+                        single_fork_holds.extend_from_slice(philosopher.single_fork_holds());
+                        retry_sleeps.push(philosopher.retry_sleep());
+                        tastes.push(philosopher.tastes());
+                        failures.push(philosopher.failures());
+                        first_meals.push(philosopher.first_meal().map(|at| at.saturating_duration_since(start)));
+                        if profile {
+                            polls.push(philosopher.polls());
+                        }
+                        for (fork, acquisitions) in philosopher.fork_acquisitions() {
+                            fork_acquisitions[fork] += acquisitions;
+                        }
+                        sample_stats.merge(philosopher.sample_stats());
+                        second_fork_retries.merge(philosopher.second_fork_retries());
+                        samples.extend(philosopher.samples().iter().map(|&(kind, at, duration)| Sample {
+                            philosopher: i,
+                            kind,
+                            at: at.saturating_duration_since(start),
+                            duration,
+                        }));
+                        if self.config.collect_timeline() {
+                            timelines.push(philosopher.timeline().iter().map(|&(phase, since, until)| PhaseSpan {
+                                phase,
+                                start: since.saturating_duration_since(start),
+                                end: until.saturating_duration_since(start),
+                            }).collect());
+                        }
+                        counters.push(philosopher.meals());
+                        PhilosopherStats { name, meals: philosopher.meals(), panicked: false, satiated: philosopher.is_satiated() }
+                    }
+                    Err(_) => {
+                        retry_sleeps.push(Duration::ZERO);
+                        tastes.push(0);
+                        failures.push(FailureCounts::default());
+                        first_meals.push(None);
+                        if profile {
+                            polls.push(PollCounts::default());
+                        }
+                        if self.config.collect_timeline() {
+                            timelines.push(Vec::new());
+                        }
+                        observer.on_event(&Event::new(&name, EventKind::Panicked));
+                        first_panicked.get_or_insert_with(|| name.clone());
+                        counters.push(0);
+                        PhilosopherStats { name, meals: 0, panicked: true, satiated: false }
+                    }
+                }
+            })
+            .collect();
+        if let Some(name) = first_panicked.filter(|_| self.shared.fail_fast) {
+            return Err(SimulationError::PhilosopherPanicked(name));
+        }
+        #[cfg(feature = "test-util")]
+        let mut philosophers = philosophers;
+        #[cfg(feature = "test-util")]
+        if let Some(stats) = self.miscounted.and_then(|index| philosophers.get_mut(index)) {
+            stats.meals += 1;
+        }
+        fork_waits.sort_unstable();
+        single_fork_holds.sort_unstable();
+        samples.sort_by_key(|sample| sample.at);
+
+        let mut elapsed = start.elapsed();
+        if self.config.exclude_paused_time() {
+            elapsed = elapsed.saturating_sub(self.shared.pause.paused_time());
+        }
+        let result = SimulationResult {
+            philosophers,
+            meals_target: self.config.meals(),
+            elapsed,
+            fork_waits,
+            single_fork_holds,
+            fork_acquisitions,
+            retry_sleeps,
+            failures,
+            polls,
+            first_meals,
+            samples,
+            sample_stats,
+            hand_offs: self.hand_offs.iter().map(|fork| fork.latencies()).collect(),
+            peak_forks_held: self.held_forks.peak(),
+            timelines,
+            #[cfg(feature = "cpu-time")]
+            cpu_time: *self.shared.cpu_time.lock().unwrap(),
+            #[cfg(not(feature = "cpu-time"))]
+            cpu_time: None,
+            fork_stats: self.held_forks.held_times(self.forks.len()).into_iter().map(|held| ForkStats::held_for(held, elapsed)).collect(),
+            second_fork_retries,
+            round_progress: self.shared.round_progress.lock().unwrap().clone(),
+            tastes,
+        };
+        result.debug_assert_meals(&counters);
+        observer.on_finish(&result);
+        if self.shared.budget_exceeded() && !result.is_complete() {
+            return Err(SimulationError::BudgetExceeded(Box::new(result)));
+        }
+        Ok(result)
+    }
+
+    /// Join the threads of `handles` as they finish, sitting each philosopher that panicked again
+    /// in a new thread up to `respawns` times ([See][JoinErrorPolicy::RetrySpawn]).
+    fn join_respawning(&self, handles: Vec<(String, JoinHandle<Philosopher<()>>)>, respawns: u32) -> Vec<(String, thread::Result<Philosopher<()>>)> {
+        let (names, handles): (Vec<_>, Vec<_>) = handles.into_iter().unzip();
+        let mut handles: Vec<_> = handles.into_iter().map(Some).collect();
+        let mut joined: Vec<_> = names.iter().map(|_| None).collect();
+        let mut respawns = vec![respawns; names.len()];
+        while handles.iter().any(Option::is_some) {
+            for (index, slot) in handles.iter_mut().enumerate() {
+                let Some(handle) = slot.take_if(|handle| handle.is_finished()) else {
+                    continue;
+                };
+                let result = handle.join();
+                if result.is_err() && respawns[index] > 0 {
+                    respawns[index] -= 1;
+                    self.config.observer().on_event(&Event::new(&names[index], EventKind::Panicked));
+                    // A thread that can not be spawned leaves the philosopher panicked
+                    if let Ok(handle) = self.respawn(index, &names[index]) {
+                        *slot = Some(handle);
+                        continue;
+                    }
+                }
+                joined[index] = Some(result);
+            }
+            thread::sleep(SUPERVISION_INTERVAL);
+        }
+        names.into_iter().zip(joined.into_iter().map(|result| result.expect("every thread was joined"))).collect()
+    }
+
+    /// Sit the philosopher at `index`, called `name`, again in a new thread with the meals and
+    /// failures of its last attempt to eat, once the poison of its forks is cleared.
+    fn respawn(&self, index: usize, name: &str) -> io::Result<JoinHandle<Philosopher<()>>> {
+        let config = &self.config;
+        let (left_id, right_id) = (self.left_fork_id(index), self.left_fork_id((index + 1) % self.forks.len()));
+        self.forks[left_id].clear_poison();
+        self.forks[right_id].clear_poison();
+        let (meals, failures) = {
+            let seating = self.shared.seating.lock().unwrap();
+            (seating.meals[index], seating.failures[index])
+        };
+        let (think_time, eat_time) = config.times_of(index);
+        let left_handed = index < config.philosophers() && config.is_left_handed(index);
+        let philosopher = Philosopher::new(name, self.forks[left_id].clone(), self.forks[right_id].clone(), left_handed)
+            .expect("the forks at both sides of a seat are different")
+            .with_fork_ids(left_id, right_id)
+            .with_config(config)
+            .with_times(think_time, eat_time)
+            .with_seed(config.seed().wrapping_add(index as u64))
+            .with_meals(meals)
+            .with_failures(failures)
+            .with_priority(config.priority_of(index))
+            .with_table_lock(self.table_lock.clone())
+            .with_contention_window(self.shared.contention.clone())
+            .with_live_counters(self.shared.live.read().unwrap()[index].clone());
+        let philosopher = match config.fork_order_of(index) {
+            Some(order) => philosopher.with_fork_order(order).expect("a validated config has the ids of the forks of each philosopher in its fork order"),
+            None => philosopher,
+        };
+        let philosopher = if config.collect_samples() { philosopher.collect_samples(config.sample_limit_of(index)) } else { philosopher };
+        let philosopher = if config.collect_timeline() { philosopher.collect_timeline() } else { philosopher };
+        let philosopher = match &self.intent_board {
+            Some(intent_board) => philosopher.with_intent_board(intent_board.clone()),
+            None => philosopher,
+        };
+        let shared = self.shared.clone();
+        let stop_condition = config.stop_condition();
+        thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || dine_at_table(philosopher, index, &shared, Duration::ZERO, stop_condition, 0, None))
+    }
+
+    /// Id of the fork at the left of the philosopher at `index`, the newcomers have the forks
+    /// added after the ones of the config.
+    fn left_fork_id(&self, index: usize) -> usize {
+        if index < self.config.philosophers() {
+            self.config.fork_at(index)
+        } else {
+            index
+        }
+    }
+}
+
+/// Create the fork with the id `id` of the table of `config`, counted in `held_forks` and wrapped
+/// in a [HandOffFork] that is also kept in `hand_offs` when they are measured.
+fn create_fork(config: &SimulationConfig, id: usize, held_forks: &Arc<HeldForks>, hand_offs: &mut Vec<Arc<HandOffFork<()>>>) -> Arc<dyn Fork<()>> {
+    let fork = Arc::new(CountedFork::new(config.fork_backend().create((), id), id, held_forks.clone()));
+    if !config.measure_hand_offs() {
+        return fork;
+    }
+    let fork = Arc::new(HandOffFork::new(fork));
+    hand_offs.push(fork.clone());
+    fork
+}
+
+/// Reason why a [Simulation] did not finish.
+#[derive(Debug)]
+pub enum SimulationError {
+    /// The thread of a philosopher could not be spawned.
+    SpawnFailed(io::Error),
+    /// The time budget ran out before the philosophers ate all their meals. It holds the stats
+    /// of the meals eaten until then.
+    BudgetExceeded(Box<SimulationResult>),
+    /// The thread of the philosopher with the given name panicked with
+    /// [JoinErrorPolicy::FailFast], the rest of the table was stopped.
+    PhilosopherPanicked(String),
+    /// A philosopher was added to a table without philosophers.
+    EmptyTable,
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SpawnFailed(error) => write!(f, "could not spawn the thread of a philosopher: {error}"),
+            Self::BudgetExceeded(result) => write!(
+                f,
+                "the time budget ran out after {} of {} meals",
+                result.total_meals(),
+                result.meals_target as usize * result.philosophers.len()
+            ),
+            Self::PhilosopherPanicked(name) => write!(f, "the thread of {name} panicked"),
+            Self::EmptyTable => write!(f, "a philosopher can not sit alone at an empty table"),
+        }
+    }
+}
+
+impl Error for SimulationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SpawnFailed(error) => Some(error),
+            Self::BudgetExceeded(_) | Self::PhilosopherPanicked(_) | Self::EmptyTable => None,
+        }
+    }
+}
+
+/// Gate the philosophers go through before each attempt to eat, it is closed while the
+/// simulation is paused.
+#[derive(Default)]
+struct PauseGate {
+    state: Mutex<PauseState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct PauseState {
+    /// When the current pause began, `None` while the simulation is running.
+    paused_since: Option<Instant>,
+    /// Time spent paused by the previous pauses.
+    paused_time: Duration,
+}
+
+impl PauseGate {
+    fn set_paused(&self, paused: bool) {
+        let mut state = self.state.lock().unwrap();
+        match (paused, state.paused_since) {
+            (true, None) => state.paused_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                state.paused_time += since.elapsed();
+                state.paused_since = None;
+                self.condvar.notify_all();
+            }
+            _ => {}
+        }
+    }
+
+    fn wait_while_paused(&self) {
+        let state = self.state.lock().unwrap();
+        let _state = self
+            .condvar
+            .wait_while(state, |state| state.paused_since.is_some())
+            .unwrap();
+    }
+
+    /// Total time spent paused, including the current pause.
+    fn paused_time(&self) -> Duration {
+        let state = self.state.lock().unwrap();
+        state.paused_time + state.paused_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+
+    /// Forget the time spent paused so far.
+    fn reset_paused_time(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused_time = Duration::ZERO;
+        if state.paused_since.is_some() {
+            state.paused_since = Some(Instant::now());
+        }
+    }
+}
+
+/// Blocks every thread that waits on it until it is opened.
+#[derive(Default)]
+struct Latch {
+    value: Mutex<Option<bool>>,
+    condvar: Condvar,
+}
+
+impl Latch {
+    fn open(&self, value: bool) {
+        *self.value.lock().unwrap() = Some(value);
+        self.condvar.notify_all();
+    }
+
+    /// Wait until the latch is opened and return the value it was opened with.
+    fn wait(&self) -> bool {
+        let value = self.value.lock().unwrap();
+        let value = self.condvar.wait_while(value, |value| value.is_none()).unwrap();
+        value.unwrap_or_default()
+    }
+}
+
+/// Like a [std::sync::Barrier] for the philosophers of a table, but a philosopher can leave it
+/// so the others do not wait for one that panicked.
+struct TableBarrier {
+    state: Mutex<BarrierState>,
+    condvar: Condvar,
+}
+
+struct BarrierState {
+    /// Philosophers that still arrive at the barrier.
+    seats: usize,
+    waiting: usize,
+    /// Number of times the barrier let the waiting philosophers go.
+    generation: u64,
+    /// Whether the last ones were let go by someone leaving, so one of them is the leader.
+    unclaimed: bool,
+}
+
+impl TableBarrier {
+    fn new(seats: usize) -> Self {
+        Self {
+            state: Mutex::new(BarrierState { seats, waiting: 0, generation: 0, unclaimed: false }),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// Wait until every philosopher still at the table arrives, `true` for exactly one of them.
+    fn wait(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.waiting += 1;
+        if state.waiting >= state.seats {
+            Self::release(&mut state, false);
+            self.condvar.notify_all();
+            return true;
+        }
+        let generation = state.generation;
+        let mut state = self.condvar.wait_while(state, |state| state.generation == generation).unwrap();
+        mem::take(&mut state.unclaimed)
+    }
+
+    /// Stop waiting for a philosopher, letting the others go if they only waited for it.
+    fn leave(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.seats = state.seats.saturating_sub(1);
+        if state.waiting > 0 && state.waiting >= state.seats {
+            Self::release(&mut state, true);
+            self.condvar.notify_all();
+        }
+    }
+
+    fn release(state: &mut BarrierState, unclaimed: bool) {
+        state.waiting = 0;
+        state.generation += 1;
+        state.unclaimed = unclaimed;
+    }
+}
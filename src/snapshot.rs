@@ -0,0 +1,261 @@
+use std::future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::{FailureCounts, Phase, Simulation};
+
+/// What the table of a running [Simulation] looks like at a moment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSnapshot {
+    /// Time since the simulation started, or since its stats were last reset.
+    pub elapsed: Duration,
+    /// Meals eaten by each philosopher, by position at the table, as of its last attempt to eat.
+    pub meals: Vec<u32>,
+    /// Whether each philosopher already left the table.
+    pub left: Vec<bool>,
+}
+
+impl TableSnapshot {
+    /// Whether every philosopher left the table, so nothing changes anymore.
+    pub fn is_finished(&self) -> bool {
+        self.left.iter().all(|&left| left)
+    }
+}
+
+/// The counters of every philosopher of a running [Simulation] and what each one is doing
+/// ([See][Simulation::metrics]). Each value of each philosopher is read once, so it is one the
+/// philosopher really had, but the values of different philosophers are not read at the same
+/// instant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveMetrics {
+    /// Time since the simulation started, or since its stats were last reset.
+    pub elapsed: Duration,
+    /// Meals eaten by each philosopher, by position at the table.
+    pub meals: Vec<u32>,
+    /// Failed attempts to eat of each philosopher ([See][FailureCounts::total]).
+    pub failures: Vec<u64>,
+    /// Phase each philosopher is in, [Phase::Thinking] once it left the table.
+    pub phases: Vec<Phase>,
+}
+
+impl LiveMetrics {
+    /// Meals of the whole table, the sum of [LiveMetrics::meals].
+    pub fn total_meals(&self) -> u32 {
+        self.meals.iter().sum()
+    }
+
+    /// Failed attempts to eat of the whole table, the sum of [LiveMetrics::failures].
+    pub fn total_failures(&self) -> u64 {
+        self.failures.iter().sum()
+    }
+}
+
+/// Counters a philosopher publishes as they change for [LiveMetrics], read from other threads.
+#[derive(Default)]
+pub(crate) struct LiveCounters {
+    meals: AtomicU32,
+    failures: AtomicU64,
+    phase: AtomicU8,
+}
+
+impl LiveCounters {
+    pub(crate) fn publish(&self, meals: u32, failures: &FailureCounts) {
+        self.meals.store(meals, Ordering::SeqCst);
+        self.failures.store(failures.total(), Ordering::SeqCst);
+    }
+
+    pub(crate) fn enter(&self, phase: Phase) {
+        let phase = match phase {
+            Phase::Thinking => 0,
+            Phase::Hungry => 1,
+            Phase::Eating => 2,
+        };
+        self.phase.store(phase, Ordering::SeqCst);
+    }
+
+    /// The meals, failures and phase published last.
+    pub(crate) fn read(&self) -> (u32, u64, Phase) {
+        let phase = match self.phase.load(Ordering::SeqCst) {
+            1 => Phase::Hungry,
+            2 => Phase::Eating,
+            _ => Phase::Thinking,
+        };
+        (self.meals.load(Ordering::SeqCst), self.failures.load(Ordering::SeqCst), phase)
+    }
+}
+
+/// The counters of every philosopher of a [Simulation] taken at a safe point, to continue it
+/// later with [Simulation::restore] ([See][Simulation::checkpoint]). With the `serde` feature it
+/// can be saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+    /// Time since the simulation started, or since its stats were last reset.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub elapsed: Duration,
+    /// Meals eaten by each philosopher, by position at the table.
+    pub meals: Vec<u32>,
+    /// Failed attempts to eat of each philosopher.
+    pub failures: Vec<FailureCounts>,
+    /// Whether each philosopher already left the table.
+    pub left: Vec<bool>,
+}
+
+/// Snapshots of a running [Simulation] taken every interval, ending with the one where every
+/// philosopher left the table ([See][Simulation::snapshots]). The iterator blocks while it waits
+/// for the next snapshot, [SnapshotStream] waits without blocking.
+pub struct Snapshots<'a> {
+    simulation: &'a Simulation,
+    interval: Duration,
+    first: bool,
+    finished: bool,
+}
+
+impl<'a> Snapshots<'a> {
+    pub(crate) fn new(simulation: &'a Simulation, interval: Duration) -> Self {
+        Self {
+            simulation,
+            interval,
+            first: true,
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for Snapshots<'_> {
+    type Item = TableSnapshot;
+
+    fn next(&mut self) -> Option<TableSnapshot> {
+        if self.finished {
+            return None;
+        }
+        if !self.first {
+            thread::sleep(self.interval);
+        }
+        self.first = false;
+        let snapshot = self.simulation.snapshot();
+        self.finished = snapshot.is_finished();
+        Some(snapshot)
+    }
+}
+
+/// Async stream of the snapshots of a running [Simulation] taken every interval, ending with the
+/// one where every philosopher left the table ([See][Simulation::snapshot_stream]).
+///
+/// [SnapshotStream::poll_next] has the signature of `futures_core::Stream::poll_next`, so the
+/// stream can be driven by any executor, such as a tokio task forwarding the snapshots to a live
+/// view. The wait between snapshots does not need a timer of the runtime: a thread of the stream,
+/// started by the first wait and reused by the next ones, sleeps until the next snapshot is due
+/// and wakes the task.
+pub struct SnapshotStream<'a> {
+    simulation: &'a Simulation,
+    interval: Duration,
+    /// When the next snapshot is due, none before the first one.
+    next_at: Option<Instant>,
+    finished: bool,
+    timer: Option<Timer>,
+}
+
+impl<'a> SnapshotStream<'a> {
+    pub(crate) fn new(simulation: &'a Simulation, interval: Duration) -> Self {
+        Self {
+            simulation,
+            interval,
+            next_at: None,
+            finished: false,
+            timer: None,
+        }
+    }
+
+    /// Take the next snapshot if it is due, or have the task woken once it is. It is
+    /// `Poll::Ready(None)` once the snapshot where every philosopher left was taken.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<TableSnapshot>> {
+        let stream = self.get_mut();
+        if stream.finished {
+            return Poll::Ready(None);
+        }
+        if let Some(at) = stream.next_at.filter(|&at| Instant::now() < at) {
+            stream.wake_at(at, cx.waker());
+            return Poll::Pending;
+        }
+        let snapshot = stream.simulation.snapshot();
+        stream.finished = snapshot.is_finished();
+        stream.next_at = Some(Instant::now() + stream.interval);
+        Poll::Ready(Some(snapshot))
+    }
+
+    /// Wait for the next snapshot, `None` once the stream ended.
+    pub async fn next(&mut self) -> Option<TableSnapshot> {
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    /// Wake the task of `waker` at `at`, starting the thread of the timer on the first wait.
+    fn wake_at(&mut self, at: Instant, waker: &Waker) {
+        self.timer.get_or_insert_with(Timer::start).wake_at(at, waker);
+    }
+}
+
+/// Thread that wakes the task of a [SnapshotStream] when the next snapshot is due, stopped when
+/// the stream is dropped.
+struct Timer {
+    state: Arc<(Mutex<TimerState>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct TimerState {
+    /// When to wake the task, none while nothing is due.
+    at: Option<Instant>,
+    waker: Option<Waker>,
+    stopped: bool,
+}
+
+impl Timer {
+    fn start() -> Self {
+        let state: Arc<(Mutex<TimerState>, Condvar)> = Arc::default();
+        let thread_state = state.clone();
+        let thread = thread::spawn(move || {
+            let (state, condvar) = &*thread_state;
+            let mut guard = state.lock().unwrap();
+            while !guard.stopped {
+                let now = Instant::now();
+                match guard.at {
+                    Some(at) if at <= now => {
+                        guard.at = None;
+                        if let Some(waker) = guard.waker.take() {
+                            drop(guard);
+                            waker.wake();
+                            guard = state.lock().unwrap();
+                        }
+                    }
+                    Some(at) => guard = condvar.wait_timeout(guard, at - now).unwrap().0,
+                    None => guard = condvar.wait(guard).unwrap(),
+                }
+            }
+        });
+        Self { state, thread: Some(thread) }
+    }
+
+    fn wake_at(&self, at: Instant, waker: &Waker) {
+        let (state, condvar) = &*self.state;
+        let mut state = state.lock().unwrap();
+        state.at = Some(at);
+        state.waker = Some(waker.clone());
+        condvar.notify_one();
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let (state, condvar) = &*self.state;
+        state.lock().unwrap().stopped = true;
+        condvar.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
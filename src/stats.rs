@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use crate::WaitHistogram;
+
+/// Count, mean, standard deviation, extremes and histogram of a series of durations, kept in a
+/// fixed amount of memory no matter how many of them are recorded.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::RunningStats;
+///
+/// let mut first = RunningStats::default();
+/// let mut second = RunningStats::default();
+/// for millis in [2, 4, 4, 4] {
+///     first.record(Duration::from_millis(millis));
+/// }
+/// for millis in [5, 5, 7, 9] {
+///     second.record(Duration::from_millis(millis));
+/// }
+/// first.merge(&second);
+/// assert_eq!(first.count(), 8);
+/// assert_eq!(first.mean(), Some(Duration::from_millis(5)));
+/// assert_eq!(first.std_dev(), Some(Duration::from_millis(2)));
+/// assert_eq!(first.min(), Some(Duration::from_millis(2)));
+/// assert_eq!(first.max(), Some(Duration::from_millis(9)));
+/// assert_eq!(first.histogram().total(), 8);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunningStats {
+    count: u64,
+    /// Sums of the durations and of their squares in nanoseconds, exact so nothing is lost
+    /// however long the run.
+    sum: u128,
+    sum_of_squares: u128,
+    /// Only meaningful once something was recorded.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    min: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    max: Duration,
+    histogram: WaitHistogram,
+}
+
+impl RunningStats {
+    pub fn record(&mut self, duration: Duration) {
+        let nanos = duration.as_nanos();
+        if self.count == 0 || duration < self.min {
+            self.min = duration;
+        }
+        self.max = self.max.max(duration);
+        self.count += 1;
+        self.sum += nanos;
+        self.sum_of_squares += nanos * nanos;
+        self.histogram.record(duration);
+    }
+
+    /// Add what `other` recorded, as if it was recorded here.
+    pub fn merge(&mut self, other: &RunningStats) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 || other.min < self.min {
+            self.min = other.min;
+        }
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+        self.sum += other.sum;
+        self.sum_of_squares += other.sum_of_squares;
+        self.histogram.merge(&other.histogram);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| from_nanos(self.sum / u128::from(self.count)))
+    }
+
+    /// Population standard deviation of the durations.
+    pub fn std_dev(&self) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let count = u128::from(self.count);
+        // n² times the variance, computed without rounding
+        let scaled_variance = count.saturating_mul(self.sum_of_squares).saturating_sub(self.sum.saturating_mul(self.sum));
+        Some(Duration::from_nanos((scaled_variance as f64).sqrt().round() as u64 / self.count))
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    pub fn histogram(&self) -> &WaitHistogram {
+        &self.histogram
+    }
+}
+
+fn from_nanos(nanos: u128) -> Duration {
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+/// Aggregates of every [Sample][crate::Sample] measured, including the ones left out of
+/// [SimulationResult::samples][crate::SimulationResult::samples] by
+/// [SimulationConfigBuilder::max_samples][crate::SimulationConfigBuilder::max_samples].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SampleStats {
+    /// Time waited to take each fork.
+    pub fork_waits: RunningStats,
+    /// Time from the first attempt to eat to each meal.
+    pub meals: RunningStats,
+}
+
+impl SampleStats {
+    pub fn merge(&mut self, other: &SampleStats) {
+        self.fork_waits.merge(&other.fork_waits);
+        self.meals.merge(&other.meals);
+    }
+}
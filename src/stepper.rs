@@ -0,0 +1,219 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::SimulationConfig;
+
+/// A table of `config.philosophers()` philosophers that advances one transition at a time in a
+/// single thread, without any sleep nor timeout, so the same config always goes through the same
+/// steps. The philosophers take turns in order, skipping the ones that ate all their meals.
+///
+/// A thinking philosopher takes its first fork according to its handedness, a philosopher
+/// holding its first fork takes the second one and eats or releases the first one when it is
+/// taken, and an eating philosopher releases both forks.
+/// ```rust
+/// use actividad_12::{SimulationConfig, Stepper, Transition};
+///
+/// let config = SimulationConfig::builder().philosophers(2).meals(1).build().unwrap();
+/// let mut stepper = Stepper::new(&config);
+/// // Philosopher 1 takes the fork with id 1 at its right, philosopher 2 is left handed and finds it taken
+/// assert_eq!(stepper.step().unwrap().transition, Transition::TookFork(1));
+/// assert_eq!(stepper.step().unwrap().transition, Transition::Failed);
+/// assert_eq!(stepper.step().unwrap().transition, Transition::Eating);
+/// while stepper.step().is_some() {}
+/// assert!(stepper.is_finished());
+/// assert_eq!(stepper.meals(), [1, 1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stepper {
+    philosophers: Vec<StepState>,
+    /// Whether each fork is held by someone.
+    taken: Vec<bool>,
+    meals_target: u32,
+    /// Philosopher whose turn is next.
+    next: usize,
+    steps: usize,
+}
+
+#[derive(Debug, Clone)]
+struct StepState {
+    /// The fork taken first and the other one.
+    forks: [usize; 2],
+    phase: StepPhase,
+    meals: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepPhase {
+    Thinking,
+    HoldingFirst,
+    Eating,
+    Done,
+}
+
+/// A transition of a [Stepper].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// Position of the philosopher that advanced.
+    pub philosopher: usize,
+    pub transition: Transition,
+}
+
+/// What a philosopher did in a [Step].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// The philosopher took its first fork, with the given id.
+    TookFork(usize),
+    /// The philosopher took its second fork and began eating.
+    Eating,
+    /// The philosopher finished a meal and released both forks, `meals` is the number of meals
+    /// eaten so far.
+    Ate { meals: u32 },
+    /// The philosopher found a fork taken and released the one it held, if any.
+    Failed,
+}
+
+impl Stepper {
+    /// Sit the philosophers of `config`, each one eats `config.meals()` meals and is left handed
+    /// following `config.handedness()`. The times, timeouts and strategy are not used.
+    pub fn new(config: &SimulationConfig) -> Self {
+        let n = config.philosophers();
+        let philosophers = (0..n)
+            .map(|i| {
+                let (left, right) = (i, (i + 1) % n);
+                StepState {
+                    forks: if config.is_left_handed(i) { [left, right] } else { [right, left] },
+                    phase: if config.meals() == 0 { StepPhase::Done } else { StepPhase::Thinking },
+                    meals: 0,
+                }
+            })
+            .collect();
+        Self {
+            philosophers,
+            taken: vec![false; n],
+            meals_target: config.meals(),
+            next: 0,
+            steps: 0,
+        }
+    }
+
+    /// Advance the next philosopher that has not eaten all its meals by one transition, `None`
+    /// once every philosopher ate them.
+    pub fn step(&mut self) -> Option<Step> {
+        let n = self.philosophers.len();
+        let philosopher = (0..n)
+            .map(|offset| (self.next + offset) % n)
+            .find(|&i| self.philosophers[i].phase != StepPhase::Done)?;
+        self.next = (philosopher + 1) % n;
+        self.steps += 1;
+        let state = &mut self.philosophers[philosopher];
+        let [first, second] = state.forks;
+        let transition = match state.phase {
+            StepPhase::Thinking if self.taken[first] => Transition::Failed,
+            StepPhase::Thinking => {
+                self.taken[first] = true;
+                state.phase = StepPhase::HoldingFirst;
+                Transition::TookFork(first)
+            }
+            StepPhase::HoldingFirst if self.taken[second] => {
+                self.taken[first] = false;
+                state.phase = StepPhase::Thinking;
+                Transition::Failed
+            }
+            StepPhase::HoldingFirst => {
+                self.taken[second] = true;
+                state.phase = StepPhase::Eating;
+                Transition::Eating
+            }
+            StepPhase::Eating => {
+                self.taken[first] = false;
+                self.taken[second] = false;
+                state.meals += 1;
+                state.phase = if state.meals >= self.meals_target { StepPhase::Done } else { StepPhase::Thinking };
+                Transition::Ate { meals: state.meals }
+            }
+            StepPhase::Done => unreachable!("the philosophers that are done are skipped"),
+        };
+        Some(Step { philosopher, transition })
+    }
+
+    /// Number of steps taken so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Meals eaten by each philosopher so far.
+    pub fn meals(&self) -> Vec<u32> {
+        self.philosophers.iter().map(|state| state.meals).collect()
+    }
+
+    /// Whether every philosopher ate all its meals.
+    pub fn is_finished(&self) -> bool {
+        self.philosophers.iter().all(|state| state.phase == StepPhase::Done)
+    }
+}
+
+/// One line per philosopher with what it is doing and its meals.
+impl fmt::Display for Stepper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, state) in self.philosophers.iter().enumerate() {
+            let doing = match state.phase {
+                StepPhase::Thinking => "está pensando".to_string(),
+                StepPhase::HoldingFirst => format!("tiene el tenedor {}", state.forks[0] + 1),
+                StepPhase::Eating => "está comiendo".to_string(),
+                StepPhase::Done => "ha terminado".to_string(),
+            };
+            writeln!(f, "Filósofo {} {doing}, comidas: {}.", i + 1, state.meals)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("Filósofo {}", self.philosopher + 1);
+        match self.transition {
+            Transition::TookFork(fork) => write!(f, "{name} toma el tenedor {}.", fork + 1),
+            Transition::Eating => write!(f, "{name} toma su segundo tenedor y empieza a comer."),
+            Transition::Ate { meals } => write!(f, "{name} suelta los tenedores, comidas: {meals}."),
+            Transition::Failed => write!(f, "{name} no puede comer ya que no pudo tomar ambos tenedores."),
+        }
+    }
+}
+
+/// Advance `stepper` one step for each line read from `input`, writing the step and the state of
+/// the table to `output` after each one, until the input ends or the philosophers ate all their
+/// meals. Returns the number of steps taken.
+/// ```rust
+/// use actividad_12::{step_through, SimulationConfig, Stepper};
+///
+/// let config = SimulationConfig::builder().philosophers(3).meals(2).build().unwrap();
+/// let mut stepper = Stepper::new(&config);
+/// let mut output = Vec::new();
+/// assert_eq!(step_through(&mut stepper, "\n\n\n".as_bytes(), &mut output).unwrap(), 3);
+/// assert_eq!(stepper.steps(), 3);
+/// // The table is printed before the first step and after each one
+/// let output = String::from_utf8(output).unwrap();
+/// assert_eq!(output.lines().count(), 3 + 3 * (1 + 3));
+///
+/// // Without more input it stops
+/// assert_eq!(step_through(&mut stepper, "".as_bytes(), &mut Vec::new()).unwrap(), 0);
+/// assert_eq!(stepper.steps(), 3);
+/// ```
+pub fn step_through(stepper: &mut Stepper, input: impl BufRead, mut output: impl Write) -> io::Result<usize> {
+    write!(output, "{stepper}")?;
+    let mut steps = 0;
+    for line in input.lines() {
+        line?;
+        let Some(step) = stepper.step() else {
+            break;
+        };
+        steps += 1;
+        writeln!(output, "{step}")?;
+        write!(output, "{stepper}")?;
+        if stepper.is_finished() {
+            writeln!(output, "Los filósofos han terminado de comer.")?;
+            break;
+        }
+    }
+    Ok(steps)
+}
@@ -0,0 +1,434 @@
+use std::time::Duration;
+
+/// Max hold time of the [Strategy::OpportunisticWithMaxHold] listed by [Strategy::all].
+const DEFAULT_MAX_HOLD: Duration = Duration::from_millis(100);
+
+/// How a philosopher acquires its forks. With the `no-timeouts` feature the strategy is not used,
+/// besides serializing the table with [Strategy::Serialized], and every philosopher waits for its
+/// forks without a timeout nor sleeping, the one with the lower id first
+/// ([See][crate::Fork::lock_blocking]).
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{run_simulation, SimulationConfig, Strategy};
+///
+/// for strategy in [Strategy::Timeout, Strategy::OpportunisticWithMaxHold(Duration::from_millis(20))] {
+///     let config = SimulationConfig::builder()
+///         .strategy(strategy)
+///         .think_time(Duration::ZERO)
+///         .eat_time(Duration::from_millis(1))
+///         .meals(2)
+///         .build()
+///         .unwrap();
+///     assert!(run_simulation(&config).unwrap().is_complete());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Take the first fork according to the handedness and then the second one, waiting a limited
+    /// time for each. If any of them could not be acquired the other one is released and the
+    /// philosopher tries again after thinking.
+    #[default]
+    Timeout,
+    /// Take whichever fork is free first, ignoring the handedness, and hold it while waiting for
+    /// the other one. If the other fork is not free within the given time the held fork is
+    /// released so the neighbor can use it.
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::{Duration, Instant};
+    /// use actividad_12::{Philosopher, SimulationConfig, Strategy};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .strategy(Strategy::OpportunisticWithMaxHold(Duration::from_millis(50)))
+    ///     .think_time(Duration::ZERO)
+    ///     .fork_timeout(Duration::from_secs(5))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let (left, right) = (Arc::new(Mutex::new(())), Arc::new(Mutex::new(())));
+    /// let mut philosopher = Philosopher::new("Filósofo 1", left.clone(), right.clone(), false).unwrap().with_config(&config);
+    /// // The right fork is never free, the philosopher holds the left one meanwhile
+    /// let _held = right.lock().unwrap();
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// # {
+    /// let neighbor = thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(10));
+    ///     let start = Instant::now();
+    ///     let _guard = left.lock().unwrap();
+    ///     start.elapsed()
+    /// });
+    /// philosopher.dine();
+    /// assert_eq!(philosopher.failures().second_fork, 1);
+    /// // The neighbor gets the left fork once it was held for `max_hold`, long before the timeout
+    /// let waited = neighbor.join().unwrap();
+    /// assert!(waited >= Duration::from_millis(20) && waited < Duration::from_millis(500), "{waited:?}");
+    /// # }
+    /// ```
+    OpportunisticWithMaxHold(Duration),
+    /// Like [Strategy::Timeout] but on each attempt begin with the fork that was acquired on the
+    /// larger share of the times it was tried, so the more contended fork is taken last.
+    /// ```rust
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use actividad_12::{FaultyFork, Fork, Philosopher, SimulationConfig, Strategy};
+    ///
+    /// // The right fork, tried first, is almost never free and a neighbor takes the left fork
+    /// // for a while whenever it finds it free
+    /// fn meals(strategy: Strategy) -> u32 {
+    ///     let left = Arc::new(Mutex::new(()));
+    ///     let right: Arc<dyn Fork<()>> = Arc::new(FaultyFork::new((), 0.95, Duration::ZERO, 7));
+    ///     let done = Arc::new(AtomicBool::new(false));
+    ///     let neighbor = {
+    ///         let (left, done) = (left.clone(), done.clone());
+    ///         thread::spawn(move || {
+    ///             while !done.load(Ordering::SeqCst) {
+    ///                 if let Ok(_guard) = left.try_lock() {
+    ///                     thread::sleep(Duration::from_millis(30));
+    ///                 }
+    ///                 thread::sleep(Duration::from_millis(5));
+    ///             }
+    ///         })
+    ///     };
+    ///     let config = SimulationConfig::builder()
+    ///         .strategy(strategy)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(1))
+    ///         .fork_timeout(Duration::from_millis(10))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .build()
+    ///         .unwrap();
+    ///     let mut philosopher = Philosopher::new("Filósofo 1", left, right, false).unwrap().with_config(&config);
+    ///     for _ in 0..40 {
+    ///         philosopher.dine();
+    ///     }
+    ///     done.store(true, Ordering::SeqCst);
+    ///     neighbor.join().unwrap();
+    ///     philosopher.meals()
+    /// }
+    ///
+    /// let (fixed, adaptive) = (meals(Strategy::Timeout), meals(Strategy::AdaptiveOrder));
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(adaptive > fixed);
+    /// ```
+    AdaptiveOrder,
+    /// Let only one philosopher of the whole table take its forks and eat at a time, holding a
+    /// table-wide lock from the first fork to the end of the meal, also with the `no-timeouts`
+    /// feature. Nobody can deadlock but nobody eats in parallel either, a baseline to compare
+    /// the throughput of the other strategies with.
+    /// ```rust
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, Event, EventKind, Observer, SimulationConfig, Strategy, TimeDistribution};
+    ///
+    /// // Most philosophers eating at once
+    /// #[derive(Default)]
+    /// struct Eaters {
+    ///     eating: AtomicUsize,
+    ///     most: AtomicUsize,
+    /// }
+    ///
+    /// impl Observer for Eaters {
+    ///     fn on_event(&self, event: &Event) {
+    ///         match event.kind {
+    ///             EventKind::Eating => {
+    ///                 let eating = self.eating.fetch_add(1, Ordering::SeqCst) + 1;
+    ///                 self.most.fetch_max(eating, Ordering::SeqCst);
+    ///             }
+    ///             EventKind::DoneEating => {
+    ///                 self.eating.fetch_sub(1, Ordering::SeqCst);
+    ///             }
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let most_eating = |strategy| {
+    ///     let eaters = Arc::new(Eaters::default());
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(6)
+    ///         .meals(5)
+    ///         .strategy(strategy)
+    ///         .think_distribution(TimeDistribution::Uniform { min: Duration::ZERO, max: Duration::from_millis(4) })
+    ///         .eat_time(Duration::from_millis(3))
+    ///         .fork_timeout(Duration::from_millis(50))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .observer(eaters.clone())
+    ///         .build()
+    ///         .unwrap();
+    ///     assert!(run_simulation(&config).unwrap().is_complete());
+    ///     eaters.most.load(Ordering::SeqCst)
+    /// };
+    /// assert_eq!(most_eating(Strategy::Serialized), 1);
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(most_eating(Strategy::Timeout) > 1);
+    /// ```
+    Serialized,
+    /// Like [Strategy::Timeout] but tell the neighbors through a [Mailbox][crate::Mailbox] when
+    /// getting hungry and after eating, and before taking the forks wait, at most the fork
+    /// timeout, while a neighbor that got hungry earlier has not eaten yet. Neighbors rarely go
+    /// for the same fork at once so fewer attempts fail.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig, Strategy, TimeDistribution};
+    ///
+    /// // Attempts to eat that failed because a neighbor had a fork
+    /// let failed_attempts = |strategy| {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(5)
+    ///         .meals(10)
+    ///         .strategy(strategy)
+    ///         .think_distribution(TimeDistribution::Uniform { min: Duration::ZERO, max: Duration::from_millis(2) })
+    ///         .eat_time(Duration::from_millis(3))
+    ///         .fork_timeout(Duration::from_millis(2))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .build()
+    ///         .unwrap();
+    ///     let result = run_simulation(&config).unwrap();
+    ///     assert!(result.is_complete());
+    ///     result.failures.iter().map(|failures| failures.total()).sum::<u64>()
+    /// };
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(failed_attempts(Strategy::Polite) < failed_attempts(Strategy::Timeout));
+    /// ```
+    Polite,
+    /// Like [Strategy::Timeout] but before taking the forks wait, at most the fork timeout,
+    /// while a neighbor ate fewer meals, so the philosophers that lag behind get the forks first
+    /// and nobody is left with many meals to eat alone at the end. It aims at the shortest time
+    /// to finish every meal rather than at the same meals at every moment.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig, Strategy, TimeDistribution};
+    ///
+    /// // Time until the last philosopher ate all its meals, the first one is slower to get hungry
+    /// let makespan = |strategy| {
+    ///     let config = SimulationConfig::builder()
+    ///         .philosophers(5)
+    ///         .meals(10)
+    ///         .strategy(strategy)
+    ///         .think_time(Duration::ZERO)
+    ///         .eat_time(Duration::from_millis(2))
+    ///         .philosopher_times(0, TimeDistribution::Constant(Duration::from_millis(1)), TimeDistribution::Constant(Duration::from_millis(2)))
+    ///         .fork_timeout(Duration::from_millis(2))
+    ///         .fork_interval(Duration::from_millis(1))
+    ///         .seed(7)
+    ///         .build()
+    ///         .unwrap();
+    ///     let result = run_simulation(&config).unwrap();
+    ///     assert!(result.is_complete());
+    ///     result.elapsed
+    /// };
+    /// # #[cfg(not(feature = "no-timeouts"))]
+    /// assert!(makespan(Strategy::MinMakespan) <= makespan(Strategy::Timeout));
+    /// ```
+    MinMakespan,
+    /// Ignore the left and right forks and check out any two free forks of a
+    /// [ForkPool][crate::ForkPool] shared by the whole table, both at once or none, retrying every
+    /// fork interval for at most the fork timeout. Nobody holds a fork while waiting for another
+    /// one so the table never deadlocks, and as long as there are two forks for each philosopher
+    /// eating nobody even waits. [run_on_pool][crate::run_on_pool], [run_reproducible][crate::run_reproducible]
+    /// and the philosophers added with [Simulation::add_philosopher][crate::Simulation::add_philosopher]
+    /// take their own forks like with [Strategy::Timeout] instead.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, SimulationConfig, Strategy, TimeDistribution};
+    ///
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(6)
+    ///     .meals(10)
+    ///     .strategy(Strategy::SharedPool)
+    ///     .think_distribution(TimeDistribution::Uniform { min: Duration::ZERO, max: Duration::from_millis(2) })
+    ///     .eat_time(Duration::from_millis(2))
+    ///     .fork_timeout(Duration::from_millis(20))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.is_complete());
+    /// // The pool has a fork for each philosopher
+    /// assert!(result.peak_forks_held <= 6);
+    /// ```
+    SharedPool,
+    /// Like [Strategy::Timeout] but before taking the forks announce them on an
+    /// [IntentBoard][crate::IntentBoard] shared by the whole table and wait, at most the fork
+    /// timeout, until they can be reserved: none of them is reserved and no neighbor with a
+    /// higher priority, or the same one and an earlier announcement, wants one of them. Without
+    /// the reservation the attempt fails ([See][crate::FailureCounts::not_reserved]). Neighbors
+    /// never hold reservations at once, so even waiting for the first fork without a timeout the
+    /// table does not deadlock.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use actividad_12::{run_simulation, AcquisitionStyle, HandednessPattern, SimulationConfig, Strategy};
+    ///
+    /// // Everybody takes the right fork first and waits for it as long as it takes
+    /// let config = SimulationConfig::builder()
+    ///     .philosophers(5)
+    ///     .meals(10)
+    ///     .strategy(Strategy::Reservation)
+    ///     .handedness(HandednessPattern::AllRightHanded)
+    ///     .acquisition_style(AcquisitionStyle::BlockingFirst)
+    ///     .think_time(Duration::ZERO)
+    ///     .eat_time(Duration::from_millis(1))
+    ///     .fork_timeout(Duration::from_millis(50))
+    ///     .fork_interval(Duration::from_millis(1))
+    ///     .priority(2, 1)
+    ///     .build()
+    ///     .unwrap();
+    /// let result = run_simulation(&config).unwrap();
+    /// assert!(result.is_complete());
+    /// // With a reservation the forks are free
+    /// assert!(result.failures.iter().all(|failures| failures.first_fork + failures.second_fork == 0));
+    /// ```
+    Reservation,
+}
+
+/// How a philosopher following [Strategy::Timeout] waits for its first fork, the second one is
+/// always waited for with the fork timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AcquisitionStyle {
+    /// Give up on the first fork after the fork timeout too.
+    #[default]
+    TimeoutFirst,
+    /// Wait for the first fork as long as it takes. Without a left handed philosopher this could
+    /// deadlock.
+    BlockingFirst,
+}
+
+/// Which of its two forks a philosopher tries first, whatever the strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AcquisitionOrder {
+    /// The right fork first, or the left one if the philosopher is left handed.
+    #[default]
+    Handedness,
+    /// The fork with the lower id first.
+    LowerIdFirst,
+    /// The fork with the higher id first.
+    HigherIdFirst,
+}
+
+impl Strategy {
+    /// Every strategy, the ones with a parameter use a default value.
+    pub fn all() -> &'static [Strategy] {
+        &[Strategy::Timeout, Strategy::OpportunisticWithMaxHold(DEFAULT_MAX_HOLD), Strategy::AdaptiveOrder, Strategy::Serialized, Strategy::Polite, Strategy::MinMakespan, Strategy::SharedPool, Strategy::Reservation]
+    }
+
+    /// Short description of the strategy and of whether it guarantees that the philosophers
+    /// never deadlock and never starve.
+    /// ```rust
+    /// use actividad_12::Strategy;
+    ///
+    /// assert!(Strategy::all().iter().all(|strategy| !strategy.describe().is_empty()));
+    /// ```
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Timeout => {
+                "Toma los tenedores según la mano dominante esperando un tiempo limitado por cada uno. \
+                 Sin interbloqueo: sí. Sin inanición: no."
+            }
+            Self::OpportunisticWithMaxHold(_) => {
+                "Toma primero el tenedor que esté libre y lo suelta si el otro no se libera a tiempo. \
+                 Sin interbloqueo: sí. Sin inanición: no."
+            }
+            Self::AdaptiveOrder => {
+                "Toma primero el tenedor que más veces ha conseguido y espera un tiempo limitado por cada uno. \
+                 Sin interbloqueo: sí. Sin inanición: no."
+            }
+            Self::Serialized => {
+                "Solo un filósofo de toda la mesa toma sus tenedores y come a la vez. \
+                 Sin interbloqueo: sí. Sin inanición: no."
+            }
+            Self::Polite => {
+                "Avisa a sus vecinos cuando tiene hambre y cede el turno al que lleva más tiempo esperando. \
+                 Sin interbloqueo: sí. Sin inanición: no."
+            }
+            Self::MinMakespan => {
+                "Cede el turno a los vecinos que han comido menos para que todos terminen lo antes posible. \
+                 Sin interbloqueo: sí. Sin inanición: no."
+            }
+            Self::SharedPool => {
+                "Toma dos tenedores libres cualesquiera de un montón compartido por toda la mesa. \
+                 Sin interbloqueo: sí. Sin inanición: no."
+            }
+            Self::Reservation => {
+                "Anuncia qué tenedores quiere y solo los toma cuando ningún vecino con más prioridad los ha pedido antes. \
+                 Sin interbloqueo: sí. Sin inanición: no."
+            }
+        }
+    }
+}
+
+/// Which philosophers are left handed, taking their left fork first with
+/// [AcquisitionOrder::Handedness]. Philosopher `i` has fork `i` at its left and fork `i + 1` at its
+/// right, so when every philosopher takes the fork at the same side first they can all hold one
+/// fork and wait for the other one.
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use actividad_12::{run_simulation, AcquisitionStyle, DeadlockDetector, HandednessPattern, SimulationConfig, TimeDistribution};
+///
+/// assert_eq!(HandednessPattern::Alternating.left_handed(4), [false, true, false, true]);
+/// assert_eq!(HandednessPattern::HalfAndHalf.left_handed(5), [false, false, true, true, true]);
+///
+/// // Circular waits detected and whether the table ate all its meals, waiting for the first fork
+/// // without a timeout as the naive solution does
+/// fn dine(handedness: HandednessPattern) -> (usize, bool) {
+///     let detector = Arc::new(DeadlockDetector::new(4));
+///     let config = SimulationConfig::builder()
+///         .philosophers(4)
+///         .meals(20)
+///         .handedness(handedness)
+///         .acquisition_style(AcquisitionStyle::BlockingFirst)
+///         .think_distribution(TimeDistribution::Uniform { min: Duration::ZERO, max: Duration::from_millis(2) })
+///         .eat_time(Duration::from_millis(1))
+///         .fork_timeout(Duration::from_millis(20))
+///         .fork_interval(Duration::from_millis(1))
+///         .time_budget(Duration::from_secs(5))
+///         .observer(detector.clone())
+///         .build()
+///         .unwrap();
+///     let complete = run_simulation(&config).is_ok_and(|result| result.is_complete());
+///     (detector.detections(), complete)
+/// }
+///
+/// // Two neighbors take the same fork first so they never all hold one fork
+/// assert_eq!(dine(HandednessPattern::Alternating), (0, true));
+/// # #[cfg(not(feature = "no-timeouts"))]
+/// assert!(dine(HandednessPattern::AllRightHanded).0 > 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HandednessPattern {
+    /// Only one philosopher is left handed, the last one unless
+    /// [SimulationConfigBuilder::left_handed_index][crate::SimulationConfigBuilder::left_handed_index]
+    /// chooses another one.
+    #[default]
+    SingleLeftHanded,
+    /// Nobody is left handed, which can deadlock when the first fork is waited for without a
+    /// timeout.
+    AllRightHanded,
+    /// Everyone is left handed, which can deadlock like [HandednessPattern::AllRightHanded].
+    AllLeftHanded,
+    /// Every other philosopher is left handed, beginning with the second one.
+    Alternating,
+    /// The first half of the table is right handed and the second half left handed.
+    HalfAndHalf,
+}
+
+impl HandednessPattern {
+    /// Whether the philosopher at `index` of a table of `philosophers` is left handed.
+    pub fn is_left_handed(&self, index: usize, philosophers: usize) -> bool {
+        match self {
+            Self::SingleLeftHanded => index + 1 == philosophers,
+            Self::AllRightHanded => false,
+            Self::AllLeftHanded => true,
+            Self::Alternating => index % 2 == 1,
+            Self::HalfAndHalf => index >= philosophers / 2,
+        }
+    }
+
+    /// Whether each philosopher of a table of `philosophers` is left handed.
+    pub fn left_handed(&self, philosophers: usize) -> Vec<bool> {
+        (0..philosophers).map(|index| self.is_left_handed(index, philosophers)).collect()
+    }
+}
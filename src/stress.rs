@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use crate::{run_simulation, SimulationConfig, SimulationError, SimulationResult, Strategy};
+
+/// Meals each philosopher of [stress_test] has to eat.
+const STRESS_MEALS: u32 = 20;
+
+/// Time [stress_test] waits for the table before giving up on it.
+const STRESS_BUDGET: Duration = Duration::from_secs(10);
+
+/// Sit `philosophers` philosophers following `strategy` around a table with no time to think nor
+/// to eat and a fork timeout of a millisecond, so they fight for the forks all the time, and
+/// return how they did. If they do not finish their meals within ten seconds, as if they
+/// deadlocked, the partial result is returned and it is not complete.
+///
+/// # Panics
+///
+/// If `philosophers` is 1, or a thread can not be spawned.
+/// ```rust
+/// use actividad_12::{stress_test, Strategy};
+///
+/// for &strategy in Strategy::all() {
+///     let result = stress_test(strategy, 12);
+///     assert!(result.is_complete(), "{strategy:?}");
+///     assert_eq!(result.total_meals(), 12 * 20);
+/// }
+/// ```
+pub fn stress_test(strategy: Strategy, philosophers: usize) -> SimulationResult {
+    let config = SimulationConfig::builder()
+        .philosophers(philosophers)
+        .meals(STRESS_MEALS)
+        .strategy(strategy)
+        .think_time(Duration::ZERO)
+        .eat_time(Duration::ZERO)
+        .fork_timeout(Duration::from_millis(1))
+        .fork_interval(Duration::from_micros(100))
+        .time_budget(STRESS_BUDGET)
+        .build()
+        .expect("a stress test needs 0 or at least 2 philosophers");
+    match run_simulation(&config) {
+        Ok(result) => result,
+        Err(SimulationError::BudgetExceeded(partial)) => *partial,
+        Err(error) => panic!("{error}"),
+    }
+}
@@ -0,0 +1,182 @@
+use std::array;
+use std::sync::atomic::AtomicU32;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crate::fork::{CountedFork, HeldForks};
+use crate::{Event, EventKind, Fork, ForkPool, ForkStats, IntentBoard, Mailbox, Observer, Philosopher, PhilosopherStats, RetryHistogram, SampleStats, SimulationConfig, SimulationResult, Strategy};
+
+/// A round table of exactly `N` philosophers, with the forks and philosophers kept in arrays. The
+/// number of philosophers is checked when the program is compiled.
+/// ```rust
+/// use std::time::Duration;
+/// use actividad_12::{SimulationConfig, Table};
+///
+/// let config = SimulationConfig::builder()
+///     .meals(2)
+///     .think_time(Duration::from_millis(1))
+///     .eat_time(Duration::from_millis(1))
+///     .build()
+///     .unwrap();
+/// let five = Table::<5>::with_config(&config).run();
+/// assert!(five.is_complete());
+/// assert_eq!(five.total_meals(), 10);
+/// let three = Table::<3>::with_config(&config).run();
+/// assert!(three.is_complete());
+/// assert_eq!(three.total_meals(), 6);
+/// ```
+///
+/// A table needs at least two philosophers:
+/// ```rust,compile_fail
+/// let table = actividad_12::Table::<1>::new();
+/// ```
+pub struct Table<const N: usize> {
+    forks: [Arc<Mutex<()>>; N],
+    held_forks: Arc<HeldForks>,
+    philosophers: [Philosopher<()>; N],
+    meals: u32,
+    observer: Arc<dyn Observer>,
+}
+
+impl<const N: usize> Table<N> {
+    /// A table using the default [SimulationConfig].
+    pub fn new() -> Self {
+        Self::with_config(&SimulationConfig::default())
+    }
+
+    /// A table where philosopher `i` shares fork `i` with the previous one and fork `i + 1` with
+    /// the next one, the handedness pattern of `config` decides who is left handed. It uses the
+    /// timings, meals and observer of `config` but not its number of philosophers, its left
+    /// handed index nor its fork permutation.
+    pub fn with_config(config: &SimulationConfig) -> Self {
+        const { assert!(N >= 2, "a table needs at least 2 philosophers") };
+        let forks: [Arc<Mutex<()>>; N] = array::from_fn(|_| Arc::new(Mutex::new(())));
+        let held_forks = Arc::new(HeldForks::default());
+        let table_lock = Arc::new(Mutex::new(()));
+        let mut mailboxes = (config.strategy() == Strategy::Polite).then(|| Mailbox::ring(N).into_iter());
+        let meal_counts: [Arc<AtomicU32>; N] = array::from_fn(|_| Arc::new(AtomicU32::new(0)));
+        let fork_pool = (config.strategy() == Strategy::SharedPool).then(|| {
+            let pooled = (0..N).map(|i| Arc::new(CountedFork::new(forks[i].clone(), i, held_forks.clone())) as Arc<dyn Fork<()>>);
+            Arc::new(ForkPool::new(pooled.collect()))
+        });
+        let intent_board = (config.strategy() == Strategy::Reservation).then(|| Arc::new(IntentBoard::new()));
+        let philosophers = array::from_fn(|i| {
+            let left_fork: Arc<dyn Fork<()>> = Arc::new(CountedFork::new(forks[i].clone(), i, held_forks.clone()));
+            let right_fork: Arc<dyn Fork<()>> = Arc::new(CountedFork::new(forks[(i + 1) % N].clone(), (i + 1) % N, held_forks.clone()));
+            let philosopher = Philosopher::new(&format!("Filósofo {}", i + 1), left_fork, right_fork, config.handedness().is_left_handed(i, N))
+                .expect("a table has at least 2 philosophers so each one has different forks")
+                .with_fork_ids(i, (i + 1) % N)
+                .with_config(config)
+                .with_seed(config.seed().wrapping_add(i as u64))
+                .with_table_lock(table_lock.clone());
+            let philosopher = match mailboxes.as_mut().and_then(Iterator::next) {
+                Some(mailbox) => philosopher.with_mailbox(mailbox),
+                None => philosopher,
+            };
+            let philosopher = match &fork_pool {
+                Some(fork_pool) => philosopher.with_fork_pool(fork_pool.clone()),
+                None => philosopher,
+            };
+            let philosopher = match &intent_board {
+                Some(intent_board) => philosopher.with_intent_board(intent_board.clone()),
+                None => philosopher,
+            };
+            if config.strategy() == Strategy::MinMakespan {
+                philosopher.with_meal_counts(meal_counts[i].clone(), [meal_counts[(i + N - 1) % N].clone(), meal_counts[(i + 1) % N].clone()])
+            } else {
+                philosopher
+            }
+        });
+        Self {
+            forks,
+            held_forks,
+            philosophers,
+            meals: config.meals(),
+            observer: config.observer().clone(),
+        }
+    }
+
+    pub fn forks(&self) -> &[Arc<Mutex<()>>; N] {
+        &self.forks
+    }
+
+    /// Let each philosopher dine in its own thread until it ate all its meals or is satiated.
+    pub fn run(mut self) -> SimulationResult {
+        let meals = self.meals;
+        let start = Instant::now();
+        let panicked: [bool; N] = thread::scope(|scope| {
+            let handles = self.philosophers.each_mut().map(|philosopher| {
+                scope.spawn(move || {
+                    while philosopher.meals() < meals && !philosopher.is_satiated() {
+                        philosopher.dine();
+                    }
+                    philosopher.leave_meal_counts();
+                })
+            });
+            handles.map(|handle| handle.join().is_err())
+        });
+        let elapsed = start.elapsed();
+
+        for (philosopher, _) in self.philosophers.iter().zip(panicked).filter(|(_, panicked)| *panicked) {
+            self.observer.on_event(&Event::new(philosopher.name(), EventKind::Panicked));
+        }
+        let mut fork_waits: Vec<_> = self.philosophers.iter().flat_map(|philosopher| philosopher.fork_waits().to_vec()).collect();
+        fork_waits.sort_unstable();
+        let mut single_fork_holds: Vec<_> = self.philosophers.iter().flat_map(|philosopher| philosopher.single_fork_holds().to_vec()).collect();
+        single_fork_holds.sort_unstable();
+        let mut fork_acquisitions = vec![0; N];
+        for (fork, acquisitions) in self.philosophers.iter().flat_map(|philosopher| philosopher.fork_acquisitions()) {
+            fork_acquisitions[fork] += acquisitions;
+        }
+        let result = SimulationResult {
+            philosophers: self
+                .philosophers
+                .iter()
+                .zip(panicked)
+                .map(|(philosopher, panicked)| PhilosopherStats {
+                    name: philosopher.name().to_string(),
+                    meals: if panicked { 0 } else { philosopher.meals() },
+                    panicked,
+                    satiated: !panicked && philosopher.is_satiated(),
+                })
+                .collect(),
+            meals_target: meals,
+            elapsed,
+            fork_waits,
+            single_fork_holds,
+            fork_acquisitions,
+            retry_sleeps: self.philosophers.iter().map(|philosopher| philosopher.retry_sleep()).collect(),
+            failures: self.philosophers.iter().map(|philosopher| philosopher.failures()).collect(),
+            polls: Vec::new(),
+            first_meals: self
+                .philosophers
+                .iter()
+                .map(|philosopher| philosopher.first_meal().map(|at| at.saturating_duration_since(start)))
+                .collect(),
+            samples: Vec::new(),
+            sample_stats: SampleStats::default(),
+            hand_offs: Vec::new(),
+            peak_forks_held: self.held_forks.peak(),
+            timelines: Vec::new(),
+            cpu_time: None,
+            fork_stats: self.held_forks.held_times(N).into_iter().map(|held| ForkStats::held_for(held, elapsed)).collect(),
+            second_fork_retries: self.philosophers.iter().fold(RetryHistogram::default(), |mut retries, philosopher| {
+                retries.merge(philosopher.second_fork_retries());
+                retries
+            }),
+            round_progress: Vec::new(),
+            tastes: self.philosophers.iter().map(Philosopher::tastes).collect(),
+        };
+        let counters: Vec<_> = self.philosophers.iter().zip(panicked).map(|(philosopher, panicked)| if panicked { 0 } else { philosopher.meals() }).collect();
+        result.debug_assert_meals(&counters);
+        self.observer.on_finish(&result);
+        result
+    }
+}
+
+impl<const N: usize> Default for Table<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{Event, EventKind, Observer};
+
+/// Record when each philosopher thinks and eats and write it in the Chrome tracing JSON format,
+/// which `chrome://tracing` and Perfetto can open. Each philosopher gets its own track.
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use actividad_12::{run_simulation, SimulationConfig, TraceExporter};
+///
+/// let exporter = Arc::new(TraceExporter::new());
+/// let config = SimulationConfig::builder()
+///     .philosophers(3)
+///     .meals(2)
+///     .think_time(Duration::from_millis(1))
+///     .eat_time(Duration::from_millis(1))
+///     .observer(exporter.clone())
+///     .build()
+///     .unwrap();
+/// let result = run_simulation(&config).unwrap();
+///
+/// let path = std::env::temp_dir().join("actividad_12_trace.json");
+/// exporter.write(&path).unwrap();
+/// let trace = std::fs::read_to_string(&path).unwrap();
+/// assert!(trace.starts_with("{\"traceEvents\":[") && trace.ends_with("]}\n"));
+/// assert_eq!(trace.matches("\"name\":\"Comiendo\"").count() as u32, result.total_meals());
+/// ```
+pub struct TraceExporter {
+    start: Instant,
+    state: Mutex<TraceState>,
+}
+
+#[derive(Default)]
+struct TraceState {
+    /// Names of the philosophers, their position is the id of their track.
+    tracks: Vec<String>,
+    /// Phase each philosopher is in and when it began.
+    open: Vec<Option<(&'static str, Instant)>>,
+    spans: Vec<Span>,
+}
+
+struct Span {
+    track: usize,
+    name: &'static str,
+    start: Duration,
+    duration: Duration,
+}
+
+impl TraceExporter {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            state: Mutex::new(TraceState::default()),
+        }
+    }
+
+    /// The recorded spans as a Chrome tracing JSON document.
+    pub fn to_json(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut events = Vec::with_capacity(state.tracks.len() + state.spans.len());
+        for (track, name) in state.tracks.iter().enumerate() {
+            events.push(format!(
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{track},\"args\":{{\"name\":{}}}}}",
+                json_string(name)
+            ));
+        }
+        for span in &state.spans {
+            events.push(format!(
+                "{{\"name\":\"{}\",\"ph\":\"X\",\"pid\":1,\"tid\":{},\"ts\":{},\"dur\":{}}}",
+                span.name,
+                span.track,
+                span.start.as_micros(),
+                span.duration.as_micros()
+            ));
+        }
+        format!("{{\"traceEvents\":[{}]}}\n", events.join(","))
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+}
+
+impl Default for TraceExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceState {
+    fn track(&mut self, philosopher: &str) -> usize {
+        match self.tracks.iter().position(|name| name == philosopher) {
+            Some(track) => track,
+            None => {
+                self.tracks.push(philosopher.to_string());
+                self.open.push(None);
+                self.tracks.len() - 1
+            }
+        }
+    }
+
+    /// End the current phase of the philosopher at `track` and begin `next` if any.
+    fn switch(&mut self, track: usize, next: Option<&'static str>, now: Instant, origin: Instant) {
+        if let Some((name, since)) = self.open[track].take() {
+            self.spans.push(Span {
+                track,
+                name,
+                start: since.saturating_duration_since(origin),
+                duration: now.saturating_duration_since(since),
+            });
+        }
+        self.open[track] = next.map(|name| (name, now));
+    }
+}
+
+impl Observer for TraceExporter {
+    fn on_event(&self, event: &Event) {
+        // Thinking spans include the time spent waiting for the forks
+        let next = match event.kind {
+            EventKind::Thinking => Some("Pensando"),
+            EventKind::Eating => Some("Comiendo"),
+            EventKind::DoneEating | EventKind::Failed | EventKind::Panicked => None,
+            EventKind::Ate { .. } | EventKind::Tasted => return,
+        };
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let track = state.track(&event.philosopher);
+        state.switch(track, next, now, self.start);
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}